@@ -198,6 +198,8 @@ mod tests {
     let json_config = DicomJsonConfig {
       store_encapsulated_pixel_data: true,
       pretty_print,
+      include_keywords: false,
+      include_private_creators: false,
     };
 
     // Convert the data set to JSON
@@ -237,6 +239,8 @@ mod tests {
     let json_config = DicomJsonConfig {
       store_encapsulated_pixel_data: true,
       pretty_print: false,
+      include_keywords: false,
+      include_private_creators: false,
     };
 
     // Check the reverse by converting the expected JSON to a data set then back
@@ -306,6 +310,99 @@ mod tests {
     }
   }
 
+  #[test]
+  fn write_encapsulated_pixel_data_frames_test() {
+    use dcmfx_pixel_data::DataSetPixelDataExtensions;
+
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(
+        &dictionary::TRANSFER_SYNTAX_UID,
+        &[transfer_syntax::JPEG_BASELINE_8BIT.uid],
+      )
+      .unwrap();
+    data_set
+      .insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[2])
+      .unwrap();
+
+    let frames = [vec![1, 2, 3], vec![4, 5, 6, 7]];
+
+    for frame_lengths in [None, Some([3, 4])] {
+      let mut context = P10WriteContext::new();
+      let mut bytes = vec![];
+
+      // Write the data set, and then write the frames incrementally prior to
+      // ending the write
+      data_set
+        .to_p10_parts(&mut |part: &P10Part| -> Result<(), P10Error> {
+          if *part == P10Part::End {
+            context.write_encapsulated_pixel_data_start(
+              ValueRepresentation::OtherByteString,
+              frame_lengths.as_ref().map(|lengths| lengths.as_slice()),
+            )?;
+
+            for frame in frames.iter() {
+              context.write_encapsulated_pixel_data_frame(frame)?;
+            }
+
+            context.write_encapsulated_pixel_data_end()?;
+          }
+
+          context.write_part(part)?;
+
+          for p10_bytes in context.read_bytes() {
+            bytes.extend_from_slice(&p10_bytes);
+          }
+
+          Ok(())
+        })
+        .unwrap();
+
+      let read_data_set = read_bytes(bytes).map_err(|e| e.0).unwrap();
+
+      let items = read_data_set
+        .get_value(dictionary::PIXEL_DATA.tag)
+        .unwrap()
+        .encapsulated_pixel_data()
+        .unwrap();
+      match frame_lengths {
+        Some(_) => assert_eq!(*items[0], vec![0, 0, 0, 0, 12, 0, 0, 0]),
+        None => assert!(items[0].is_empty()),
+      }
+
+      assert_eq!(
+        read_data_set.get_pixel_data(),
+        Ok((
+          ValueRepresentation::OtherByteString,
+          vec![vec![[1, 2, 3, 0].as_slice()], vec![[4, 5, 6, 7].as_slice()]]
+        ))
+      );
+    }
+  }
+
+  #[test]
+  fn decode_rle_lossless_frame_test() {
+    use dcmfx_pixel_data::{decode_rle_frame, DataSetPixelDataExtensions};
+
+    let test_files = concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/../../../test/assets/pydicom/test_files"
+    );
+
+    let rle_data_set =
+      read_file(&format!("{}/MR_small_RLE.dcm", test_files)).unwrap();
+    let native_data_set =
+      read_file(&format!("{}/MR_small.dcm", test_files)).unwrap();
+
+    let (_, frames) = rle_data_set.get_pixel_data().unwrap();
+    let (_, native_frames) = native_data_set.get_pixel_data().unwrap();
+
+    assert_eq!(
+      decode_rle_frame(&frames[0], 64, 64, 1, 16),
+      Ok(native_frames[0][0].to_vec())
+    );
+  }
+
   /// Reads a DICOM in streaming fashion with each chunk of incoming P10 data
   /// being of a random size. This tests that DICOM reading is unaffected by
   /// different input chunk sizes and where the boundaries between chunks fall.
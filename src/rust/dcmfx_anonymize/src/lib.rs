@@ -1,7 +1,15 @@
 //! Anonymization of data sets by removing data elements that identify the
 //! patient, or potentially contribute to identification of the patient.
 
-use dcmfx_core::{dictionary, DataElementTag, DataSet, ValueRepresentation};
+use std::collections::HashMap;
+
+use dcmfx_core::{
+  data_element_value::{
+    person_name::{PersonNameComponents, StructuredPersonName},
+    unique_identifier,
+  },
+  dictionary, DataElementTag, DataError, DataSet, ValueRepresentation,
+};
 
 /// A list of data elements that identify the patient, or potentially contribute
 /// to identification of the patient, and that should be removed during
@@ -94,7 +102,109 @@ pub const IDENTIFYING_DATA_ELEMENTS: [&dictionary::Item; 80] = [
   &dictionary::UID,
 ];
 
+/// The UID data elements that are kept by default during anonymization. How
+/// these are handled is controlled by [`AnonymizeConfig::uid_handling`].
+///
+pub const INSTANCE_UID_DATA_ELEMENTS: [&dictionary::Item; 4] = [
+  &dictionary::MEDIA_STORAGE_SOP_INSTANCE_UID,
+  &dictionary::SOP_INSTANCE_UID,
+  &dictionary::SERIES_INSTANCE_UID,
+  &dictionary::STUDY_INSTANCE_UID,
+];
+
+/// The dummy values that replace identifying data elements when
+/// [`AnonymizeConfig::use_dummy_values`] is set. These data elements are
+/// required in many IODs, so some consumers reject data sets that don't have
+/// them. Ref: PS3.15 Annex E, action code 'D'.
+///
+/// Only Patient ID and Patient's Name are given dummy values. All other
+/// identifying data elements are always removed.
+///
+pub const DUMMY_VALUES: [(&dictionary::Item, &str); 2] = [
+  (&dictionary::PATIENT_ID, "ANON"),
+  (&dictionary::PATIENT_NAME, "Anonymous"),
+];
+
+/// Configuration used when anonymizing a data set.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnonymizeConfig {
+  /// How to handle the data elements in [`INSTANCE_UID_DATA_ELEMENTS`].
+  /// Defaults to [`UidHandling::Keep`].
+  pub uid_handling: UidHandling,
+
+  /// Whether to replace the data elements in [`DUMMY_VALUES`] with their dummy
+  /// value rather than removing them. Only data elements that are present are
+  /// replaced. Defaults to `false`.
+  pub use_dummy_values: bool,
+}
+
+/// Specifies how the SOP Instance UID, Series Instance UID, and Study Instance
+/// UID data elements are handled during anonymization.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum UidHandling {
+  /// The UIDs are left unchanged.
+  #[default]
+  Keep,
+
+  /// The UIDs are removed.
+  Remove,
+
+  /// The UIDs are replaced with new UIDs generated by the contained
+  /// [`UidMapper`].
+  Remap(UidMapper),
+}
+
+/// Maps UIDs to newly generated random UIDs. The same input UID is always
+/// mapped to the same output UID, which means a single mapper can be used
+/// across multiple data sets to preserve the relationships between them, e.g.
+/// instances in the same series will still share a Series Instance UID after
+/// being remapped.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct UidMapper {
+  prefix: String,
+  uids: HashMap<String, String>,
+}
+
+impl UidMapper {
+  /// Creates a new UID mapper that generates UIDs with the given prefix. If a
+  /// prefix is specified then it must itself be a valid UID and no longer than
+  /// 60 characters.
+  ///
+  #[allow(clippy::result_unit_err)]
+  pub fn new(prefix: &str) -> Result<Self, ()> {
+    // Check the prefix is able to be used to generate UIDs
+    unique_identifier::new(prefix)?;
+
+    Ok(Self {
+      prefix: prefix.to_string(),
+      uids: HashMap::new(),
+    })
+  }
+
+  /// Returns the new UID for the given UID, generating one if this UID hasn't
+  /// been mapped previously.
+  ///
+  pub fn map_uid(&mut self, uid: &str) -> &str {
+    self
+      .uids
+      .entry(uid.to_string())
+      .or_insert_with(|| unique_identifier::new(&self.prefix).unwrap())
+  }
+}
+
 /// Returns whether the given tag is allowed through the anonymization process.
+/// This is used to anonymize a stream of DICOM P10 parts with a filter
+/// transform.
+///
+/// This doesn't take an [`AnonymizeConfig`] because a filter can only remove
+/// data elements. When anonymizing this way the data elements in
+/// [`INSTANCE_UID_DATA_ELEMENTS`] are always kept as-is, and the data elements
+/// in [`DUMMY_VALUES`] are always removed, i.e. neither
+/// [`AnonymizeConfig::uid_handling`] nor [`AnonymizeConfig::use_dummy_values`]
+/// is supported.
 ///
 pub fn filter_tag(tag: DataElementTag, vr: ValueRepresentation) -> bool {
   // Strip all tags that specify an ApplicationEntity which could be identifying
@@ -123,57 +233,236 @@ pub trait DataSetAnonymizeExtensions {
   /// or potentially contribute to identification of the patient.
   ///
   fn anonymize(&mut self);
+
+  /// Anonymizes a data set in the same way as [`Self::anonymize()`], with the
+  /// handling of the data elements in [`INSTANCE_UID_DATA_ELEMENTS`]
+  /// specified by the passed config.
+  ///
+  /// The config is mutable so that a [`UidMapper`] records the UIDs it has
+  /// mapped, allowing the same config to be reused across multiple data sets.
+  ///
+  /// When [`AnonymizeConfig::use_dummy_values`] is set, only the data elements
+  /// in [`DUMMY_VALUES`] are given dummy values, and an error is returned if
+  /// one of them can't be inserted.
+  ///
+  fn anonymize_with_config(
+    &mut self,
+    config: &mut AnonymizeConfig,
+  ) -> Result<(), DataError>;
 }
 
 impl DataSetAnonymizeExtensions for DataSet {
   fn anonymize(&mut self) {
+    self
+      .anonymize_with_config(&mut AnonymizeConfig::default())
+      .expect("Anonymization with the default config never inserts values");
+  }
+
+  fn anonymize_with_config(
+    &mut self,
+    config: &mut AnonymizeConfig,
+  ) -> Result<(), DataError> {
+    let dummy_values: Vec<_> = DUMMY_VALUES
+      .iter()
+      .filter(|(item, _)| config.use_dummy_values && self.has(item.tag))
+      .collect();
+
     for el in IDENTIFYING_DATA_ELEMENTS {
       self.delete(el.tag);
     }
+
+    for (item, value) in dummy_values {
+      match item.vrs {
+        [ValueRepresentation::PersonName] => self.insert_person_name_value(
+          item,
+          &[StructuredPersonName {
+            alphabetic: Some(PersonNameComponents {
+              last_name: value.to_string(),
+              ..PersonNameComponents::default()
+            }),
+            ideographic: None,
+            phonetic: None,
+          }],
+        ),
+        _ => self.insert_string_value(item, &[value]),
+      }?;
+    }
+
+    match &mut config.uid_handling {
+      UidHandling::Keep => (),
+
+      UidHandling::Remove => {
+        for el in INSTANCE_UID_DATA_ELEMENTS {
+          self.delete(el.tag);
+        }
+      }
+
+      UidHandling::Remap(uid_mapper) => {
+        for el in INSTANCE_UID_DATA_ELEMENTS {
+          let new_uid = match self.get_string(el.tag) {
+            Ok(uid) => uid_mapper.map_uid(uid).to_string(),
+
+            // UIDs that aren't present don't need remapping
+            Err(e) if e.is_tag_not_present() => continue,
+
+            // UIDs that can't be read are removed rather than being left in
+            // place unmapped
+            Err(_) => {
+              self.delete(el.tag);
+              continue;
+            }
+          };
+
+          self.insert_string_value(el, &[&new_uid])?;
+        }
+      }
+    }
+
+    Ok(())
   }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use dcmfx_core::DataElementValue;
+  use std::rc::Rc;
 
   #[test]
-  fn filter_tag_test() {
-    assert_eq!(
-      filter_tag(
-        dictionary::SPECIFIC_CHARACTER_SET.tag,
-        ValueRepresentation::CodeString,
-      ),
-      true
-    );
+  fn anonymize_with_config_test() {
+    let sop_instance_uid = "1.2.3.4";
+
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::SOP_INSTANCE_UID, &[sop_instance_uid])
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::SERIES_INSTANCE_UID, &["1.2.3"])
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::PATIENT_ID, &["123"])
+      .unwrap();
 
+    // Check the SOP Instance UID is kept by default
+    let mut ds = data_set.clone();
+    ds.anonymize();
+    assert!(!ds.has(dictionary::PATIENT_ID.tag));
     assert_eq!(
-      filter_tag(dictionary::UID.tag, ValueRepresentation::UniqueIdentifier),
-      false
+      ds.get_string(dictionary::SOP_INSTANCE_UID.tag),
+      Ok(sop_instance_uid)
     );
 
-    assert_eq!(
-      filter_tag(
-        dictionary::STATION_AE_TITLE.tag,
-        ValueRepresentation::ApplicationEntity,
-      ),
-      false
+    // Check the SOP Instance UID is removed
+    let mut ds = data_set.clone();
+    ds.anonymize_with_config(&mut AnonymizeConfig {
+      uid_handling: UidHandling::Remove,
+      ..AnonymizeConfig::default()
+    })
+    .unwrap();
+    assert!(!ds.has(dictionary::PATIENT_ID.tag));
+    assert!(!ds.has(dictionary::SOP_INSTANCE_UID.tag));
+    assert!(!ds.has(dictionary::SERIES_INSTANCE_UID.tag));
+
+    // Check the SOP Instance UID is remapped, and that remapping is consistent
+    // across data sets when the same config is used
+    let mut config = AnonymizeConfig {
+      uid_handling: UidHandling::Remap(UidMapper::new("1.2.826").unwrap()),
+      ..AnonymizeConfig::default()
+    };
+
+    let mut ds = data_set.clone();
+    ds.anonymize_with_config(&mut config).unwrap();
+    assert!(!ds.has(dictionary::PATIENT_ID.tag));
+
+    let new_uid = ds.get_string(dictionary::SOP_INSTANCE_UID.tag).unwrap();
+    assert_ne!(new_uid, sop_instance_uid);
+    assert!(new_uid.starts_with("1.2.826."));
+    assert!(unique_identifier::is_valid(new_uid));
+    assert_ne!(
+      ds.get_string(dictionary::SERIES_INSTANCE_UID.tag).unwrap(),
+      new_uid
     );
+    assert!(!ds.has(dictionary::STUDY_INSTANCE_UID.tag));
 
+    let mut ds2 = data_set.clone();
+    ds2.anonymize_with_config(&mut config).unwrap();
     assert_eq!(
-      filter_tag(
-        DataElementTag::new(0x0009, 0x0002),
-        ValueRepresentation::CodeString,
-      ),
-      false
+      ds2.get_string(dictionary::SOP_INSTANCE_UID.tag).unwrap(),
+      new_uid
     );
 
-    assert_eq!(
-      filter_tag(
-        DataElementTag::new(0x0010, 0xABCD),
+    assert_eq!(UidMapper::new("1.02"), Err(()));
+  }
+
+  #[test]
+  fn anonymize_with_dummy_values_test() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::PATIENT_ID, &["123"])
+      .unwrap();
+    data_set
+      .insert_string_value(&dictionary::OTHER_PATIENT_IDS, &["456"])
+      .unwrap();
+
+    let mut config = AnonymizeConfig {
+      use_dummy_values: true,
+      ..AnonymizeConfig::default()
+    };
+
+    // Patient ID is replaced with its dummy value, and Patient's Name isn't
+    // added because it wasn't present
+    let mut ds = data_set.clone();
+    ds.anonymize_with_config(&mut config).unwrap();
+    assert_eq!(ds.get_string(dictionary::PATIENT_ID.tag), Ok("ANON"));
+    assert!(!ds.has(dictionary::PATIENT_NAME.tag));
+    assert!(!ds.has(dictionary::OTHER_PATIENT_IDS.tag));
+
+    data_set.insert(
+      dictionary::PATIENT_NAME.tag,
+      DataElementValue::new_binary(
         ValueRepresentation::PersonName,
-      ),
-      false
+        Rc::new(b"Doe^John".to_vec()),
+      )
+      .unwrap(),
+    );
+    data_set.anonymize_with_config(&mut config).unwrap();
+    assert_eq!(
+      data_set
+        .get_person_name(dictionary::PATIENT_NAME.tag)
+        .unwrap()
+        .alphabetic
+        .as_ref()
+        .unwrap()
+        .last_name,
+      "Anonymous"
     );
   }
+
+  #[test]
+  fn filter_tag_test() {
+    assert!(filter_tag(
+      dictionary::SPECIFIC_CHARACTER_SET.tag,
+      ValueRepresentation::CodeString,
+    ));
+
+    assert!(!filter_tag(
+      dictionary::UID.tag,
+      ValueRepresentation::UniqueIdentifier
+    ));
+
+    assert!(!filter_tag(
+      dictionary::STATION_AE_TITLE.tag,
+      ValueRepresentation::ApplicationEntity,
+    ));
+
+    assert!(!filter_tag(
+      DataElementTag::new(0x0009, 0x0002),
+      ValueRepresentation::CodeString,
+    ));
+
+    assert!(!filter_tag(
+      DataElementTag::new(0x0010, 0xABCD),
+      ValueRepresentation::PersonName,
+    ));
+  }
 }
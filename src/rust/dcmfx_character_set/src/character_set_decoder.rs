@@ -0,0 +1,272 @@
+//! Provides incremental decoding of string data that uses a specific character
+//! set, for use when the bytes are received in chunks.
+
+use crate::internal::character_set::{
+  CharacterSet, CodeElementPair, DecodeNextCodepointFn,
+};
+use crate::{
+  active_decoder, is_iso_2022_delimiter, single_byte_decoder,
+  SpecificCharacterSet, StringType,
+};
+
+/// The maximum number of bytes used by a single encoded character or ISO 2022
+/// escape sequence. Decoding only proceeds while at least this many bytes are
+/// available so that characters and escape sequences split across chunks are
+/// decoded once their remaining bytes arrive.
+///
+const MAX_SEQUENCE_LENGTH: usize = 4;
+
+/// Decodes string data that uses a specific character set incrementally, one
+/// chunk of bytes at a time. This avoids holding all of a large value, such as
+/// an `UnlimitedText` value, in memory at once.
+///
+/// The active ISO 2022 code elements are preserved between chunks. Any bytes at
+/// the end of a chunk that may be part of an incomplete character or escape
+/// sequence are retained and decoded along with the next chunk.
+///
+/// Concatenating the strings returned by [`Self::decode_chunk()`] and
+/// [`Self::finish()`] gives the same result as
+/// [`SpecificCharacterSet::decode_bytes()`] on the complete value.
+///
+pub struct CharacterSetDecoder {
+  specific_character_set: SpecificCharacterSet,
+  string_type: StringType,
+
+  /// The decoder to use when the specific character set doesn't use ISO 2022
+  /// Code Extension techniques.
+  decoder: Option<DecodeNextCodepointFn>,
+
+  /// The currently active code elements when the specific character set uses
+  /// ISO 2022 Code Extension techniques.
+  active_code_elements: CodeElementPair,
+
+  /// Bytes that haven't yet been decoded because they may be the start of a
+  /// character or escape sequence that continues in the next chunk.
+  pending_bytes: Vec<u8>,
+
+  /// Trailing U+0000 and U+0020 characters that have been decoded but not yet
+  /// returned. These are only returned if they turn out not to be padding at
+  /// the end of the value.
+  pending_padding: String,
+}
+
+impl CharacterSetDecoder {
+  /// Creates a new decoder for string data of the given type that uses the
+  /// specified specific character set.
+  ///
+  pub fn new(
+    specific_character_set: &SpecificCharacterSet,
+    string_type: StringType,
+  ) -> Self {
+    let decoder = match specific_character_set.charsets.as_slice() {
+      [CharacterSet::SingleByteWithoutExtensions {
+        defined_term,
+        decoder,
+        ..
+      }] => Some(single_byte_decoder(defined_term, *decoder, string_type)),
+
+      [CharacterSet::MultiByteWithoutExtensions { decoder, .. }] => {
+        Some(*decoder)
+      }
+
+      _ => None,
+    };
+
+    Self {
+      specific_character_set: specific_character_set.clone(),
+      string_type,
+      decoder,
+      active_code_elements: specific_character_set.default_code_elements(),
+      pending_bytes: vec![],
+      pending_padding: String::new(),
+    }
+  }
+
+  /// Decodes the next chunk of bytes and returns the string decoded so far.
+  /// Bytes at the end of the chunk that may be part of an incomplete character
+  /// or escape sequence are retained until the next call.
+  ///
+  pub fn decode_chunk(&mut self, bytes: &[u8]) -> String {
+    self.pending_bytes.extend_from_slice(bytes);
+
+    self.decode_pending_bytes(false)
+  }
+
+  /// Decodes any bytes retained from previous chunks and returns the final
+  /// part of the string. Trailing U+0000 and U+0020 characters at the end of
+  /// the value are removed in the same way as by
+  /// [`SpecificCharacterSet::decode_bytes()`].
+  ///
+  /// The decoder is then reset so it can be used to decode a new value.
+  ///
+  pub fn finish(&mut self) -> String {
+    let s = self.decode_pending_bytes(true);
+
+    self.pending_padding.clear();
+    self.active_code_elements =
+      self.specific_character_set.default_code_elements();
+
+    s
+  }
+
+  fn decode_pending_bytes(&mut self, is_final: bool) -> String {
+    let mut s = std::mem::take(&mut self.pending_padding);
+    let mut bytes = self.pending_bytes.as_slice();
+
+    while !bytes.is_empty() && (is_final || bytes.len() >= MAX_SEQUENCE_LENGTH)
+    {
+      // Detect escape sequences and use them to update the active code
+      // elements
+      if self.decoder.is_none() && bytes[0] == 0x1B {
+        bytes = self
+          .specific_character_set
+          .apply_escape_sequence(&bytes[1..], &mut self.active_code_elements);
+
+        continue;
+      }
+
+      let decoder = match self.decoder {
+        Some(decoder) => decoder,
+        None => active_decoder(bytes, &self.active_code_elements).0,
+      };
+
+      // This unwrap is safe because decoders only error when fed no bytes
+      let (char, next_bytes) = decoder(bytes).unwrap();
+
+      // Detect delimiters and reset code elements to default when they occur
+      if self.decoder.is_none() && is_iso_2022_delimiter(char, self.string_type)
+      {
+        self.active_code_elements =
+          self.specific_character_set.default_code_elements();
+      }
+
+      s.push(char);
+
+      bytes = next_bytes;
+    }
+
+    self
+      .pending_bytes
+      .drain(..self.pending_bytes.len() - bytes.len());
+
+    // Hold back trailing padding characters as they are removed if they turn
+    // out to be at the end of the value
+    let trimmed_length = s.trim_end_matches(['\0', ' ']).len();
+    self.pending_padding = s.split_off(trimmed_length);
+
+    s
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Decodes the bytes in chunks of the given size and returns the combined
+  /// result.
+  ///
+  fn decode_in_chunks(
+    specific_character_set: &str,
+    bytes: &[u8],
+    string_type: StringType,
+    chunk_size: usize,
+  ) -> String {
+    let specific_character_set =
+      SpecificCharacterSet::from_string(specific_character_set).unwrap();
+
+    let mut decoder =
+      CharacterSetDecoder::new(&specific_character_set, string_type);
+
+    let mut s: String = bytes
+      .chunks(chunk_size)
+      .map(|chunk| decoder.decode_chunk(chunk))
+      .collect();
+    s.push_str(&decoder.finish());
+
+    s
+  }
+
+  #[test]
+  pub fn decode_chunk_test() {
+    for (specific_character_set, bytes, string_type) in [
+      (
+        "ISO_IR 100",
+        b"Buc^J\xe9r\xf4me  ".as_slice(),
+        StringType::PersonName,
+      ),
+      (
+        "ISO_IR 192",
+        "Wang^XiaoDong=王^小東= \0".as_bytes(),
+        StringType::PersonName,
+      ),
+      (
+        "GB18030",
+        &[
+          0x57, 0x61, 0x6E, 0x67, 0x5E, 0x58, 0x69, 0x61, 0x6F, 0x44, 0x6F,
+          0x6E, 0x67, 0x3D, 0xCD, 0xF5, 0x5E, 0xD0, 0xA1, 0xB6, 0xAB, 0x3D,
+          0x90, 0x30, 0xD5, 0x30,
+        ],
+        StringType::PersonName,
+      ),
+      (
+        "ISO 2022 IR 13\\ISO 2022 IR 87",
+        &[
+          0xD4, 0xCF, 0xC0, 0xDE, 0x5E, 0xC0, 0xDB, 0xB3, 0x3D, 0x1B, 0x24,
+          0x42, 0x3B, 0x33, 0x45, 0x44, 0x1B, 0x28, 0x4A, 0x5E, 0x1B, 0x24,
+          0x42, 0x42, 0x40, 0x4F, 0x3A, 0x1B, 0x28, 0x4A, 0x3D, 0x1B, 0x24,
+          0x42, 0x24, 0x64, 0x24, 0x5E, 0x24, 0x40, 0x1B, 0x28, 0x4A, 0x5E,
+          0x1B, 0x24, 0x42, 0x24, 0x3F, 0x24, 0x6D, 0x24, 0x26, 0x1B, 0x28,
+          0x4A, 0x20,
+        ],
+        StringType::PersonName,
+      ),
+      (
+        "\\ISO 2022 IR 149",
+        &[
+          0x48, 0x6F, 0x6E, 0x67, 0x5E, 0x47, 0x69, 0x6C, 0x64, 0x6F, 0x6E,
+          0x67, 0x3D, 0x1B, 0x24, 0x29, 0x43, 0xFB, 0xF3, 0x5E, 0x1B, 0x24,
+          0x29, 0x43, 0xD1, 0xCE, 0xD4, 0xD7, 0x3D, 0x1B, 0x24, 0x29, 0x43,
+          0xC8, 0xAB, 0x5E, 0x1B, 0x24, 0x29, 0x43, 0xB1, 0xE6, 0xB5, 0xBF,
+        ],
+        StringType::PersonName,
+      ),
+    ] {
+      let expected = SpecificCharacterSet::from_string(specific_character_set)
+        .unwrap()
+        .decode_bytes(bytes, string_type);
+
+      for chunk_size in 1..=bytes.len() {
+        assert_eq!(
+          decode_in_chunks(
+            specific_character_set,
+            bytes,
+            string_type,
+            chunk_size
+          ),
+          expected
+        );
+      }
+    }
+  }
+
+  #[test]
+  pub fn decode_chunk_retains_incomplete_tail_test() {
+    let specific_character_set =
+      SpecificCharacterSet::from_string("\\ISO 2022 IR 87").unwrap();
+    let mut decoder = CharacterSetDecoder::new(
+      &specific_character_set,
+      StringType::SingleValue,
+    );
+
+    // The escape sequence and the two byte characters are split across chunks
+    assert_eq!(decoder.decode_chunk(b"ab\x1B$"), "a");
+    assert_eq!(decoder.decode_chunk(b"B;"), "b");
+    assert_eq!(decoder.decode_chunk(b"3E"), "");
+    assert_eq!(decoder.decode_chunk(b"D"), "山");
+    assert_eq!(decoder.finish(), "田");
+
+    // After finishing the decoder is reset to the default code elements
+    assert_eq!(decoder.decode_chunk(b";3"), "");
+    assert_eq!(decoder.finish(), ";3");
+  }
+}
@@ -20,13 +20,18 @@ use crate::internal::jis_x_0208;
 use crate::internal::jis_x_0212;
 use crate::internal::ks_x_1001;
 use crate::internal::utf8;
+use crate::internal::utils;
+
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, LazyLock, Mutex};
 
 /// Describes a single character set as defined by the DICOM standard. This
 /// holds metadata about the structure of the character set that can be used to
 /// decode data that uses it.
 ///
-#[derive(Clone, Debug, PartialEq)]
-#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Debug)]
+#[allow(clippy::enum_variant_names, dead_code)]
 pub enum CharacterSet {
   SingleByteWithoutExtensions {
     defined_term: &'static str,
@@ -68,16 +73,102 @@ impl CharacterSet {
   }
 }
 
+/// Character sets are compared using their defined term, which is unique.
+/// Decoder function pointers can't be meaningfully compared.
+///
+impl PartialEq for CharacterSet {
+  fn eq(&self, other: &Self) -> bool {
+    self.defined_term() == other.defined_term()
+  }
+}
+
 /// Describes the G0 or G1 code element for a character set, including its
 /// unique escape sequence bytes (either 2 or 3 bytes), and its decoder
 /// function.
 ///
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug)]
 pub struct CodeElement {
   pub escape_sequence: [u8; 3],
   pub decoder: DecodeNextCodepointFn,
 }
 
+/// Code elements are compared using their escape sequence, which is unique.
+///
+impl PartialEq for CodeElement {
+  fn eq(&self, other: &Self) -> bool {
+    self.escape_sequence == other.escape_sequence
+  }
+}
+
+impl CodeElement {
+  /// Returns the escape sequence bytes that designate this code element, which
+  /// excludes the initial ESC byte.
+  ///
+  pub fn escape_sequence(&self) -> &[u8] {
+    if self.escape_sequence[2] == 0 {
+      &self.escape_sequence[0..2]
+    } else {
+      &self.escape_sequence
+    }
+  }
+
+  /// Returns whether this code element is for a multi-byte character set. The
+  /// ISO 2022 escape sequences that designate multi-byte character sets all
+  /// start with 0x24, i.e. the dollar sign.
+  ///
+  pub fn is_multi_byte(&self) -> bool {
+    self.escape_sequence[0] == 0x24
+  }
+
+  /// Encodes a character using this code element when it's active as either
+  /// the G0 or G1 code element. G0 characters use bytes in the range 0x00 -
+  /// 0x7F, and G1 characters use bytes in the range 0xA0 - 0xFF.
+  ///
+  /// Returns `None` if the character can't be encoded.
+  ///
+  pub fn encode_char(&self, char: char, is_g1: bool) -> Option<Vec<u8>> {
+    match (self.is_multi_byte(), is_g1) {
+      (false, false) => encode_char(
+        char,
+        self.reverse_lookup_table_id(),
+        self.decoder,
+        Some(0x00..=0x7F),
+        None,
+      ),
+      (false, true) => encode_char(
+        char,
+        self.reverse_lookup_table_id(),
+        self.decoder,
+        Some(0xA0..=0xFF),
+        None,
+      ),
+      (true, false) => encode_char(
+        char,
+        self.reverse_lookup_table_id(),
+        self.decoder,
+        Some(0x00..=0x20),
+        Some(0x21..=0x7E),
+      ),
+      (true, true) => encode_char(
+        char,
+        self.reverse_lookup_table_id(),
+        self.decoder,
+        None,
+        Some(0xA1..=0xFE),
+      ),
+    }
+  }
+
+  /// Returns the ID that this code element's reverse lookup tables are cached
+  /// under.
+  ///
+  fn reverse_lookup_table_id(&self) -> ReverseLookupTableId {
+    ReverseLookupTableId::CodeElement {
+      escape_sequence: self.escape_sequence,
+    }
+  }
+}
+
 /// A function that decodes the next codepoint from the given bytes and returns
 /// its integer value along with the remaining bytes.
 ///
@@ -220,7 +311,9 @@ pub const ISO_IR_166: CharacterSet =
 // Single-byte character sets with code extensions.
 //
 
-const ISO_IR_6_CODE_ELEMENT: CodeElement = CodeElement {
+/// The G0 code element for the DICOM default character set, ISO IR 6.
+///
+pub const ISO_IR_6_CODE_ELEMENT: CodeElement = CodeElement {
   escape_sequence: [0x28, 0x42, 0x00],
   decoder: iso_ir_6::decode_next_codepoint,
 };
@@ -580,6 +673,149 @@ pub fn decode_bytes(
   }
 }
 
+/// Decodes bytes into a string using the specified decoder in the same way as
+/// [`decode_bytes()`], except that the offset of the first bytes that can't be
+/// decoded is returned as an error.
+///
+pub fn decode_bytes_strict(
+  mut bytes: &[u8],
+  decoder: DecodeNextCodepointFn,
+) -> Result<String, usize> {
+  let mut s = String::with_capacity(bytes.len());
+  let length = bytes.len();
+
+  loop {
+    match decoder(bytes) {
+      Ok((char, rest)) => {
+        if !is_valid_decode(char, &bytes[..bytes.len() - rest.len()]) {
+          return Err(length - bytes.len());
+        }
+
+        let mut char_utf8: [u8; 4] = [0, 0, 0, 0];
+        s.push_str(char.encode_utf8(&mut char_utf8));
+
+        bytes = rest;
+      }
+
+      Err(()) => return Ok(s),
+    }
+  }
+}
+
+/// Returns whether a decoder successfully decoded the given bytes into the
+/// given character. Decoders return the replacement character for bytes they
+/// can't decode, so it's only valid when it was explicitly encoded in UTF-8.
+///
+pub fn is_valid_decode(char: char, bytes: &[u8]) -> bool {
+  char != utils::REPLACEMENT_CHARACTER || bytes == "\u{FFFD}".as_bytes()
+}
+
+/// Encodes a character by looking it up in the reverse lookup table for the
+/// specified decoder, which is cached under the given ID. See
+/// [`reverse_lookup_table()`] for details.
+///
+/// Returns `None` if the character can't be encoded. The replacement character
+/// is never encoded because decoders use it to indicate invalid bytes.
+///
+pub fn encode_char(
+  char: char,
+  id: ReverseLookupTableId,
+  decoder: DecodeNextCodepointFn,
+  single_bytes: Option<RangeInclusive<u8>>,
+  double_bytes: Option<RangeInclusive<u8>>,
+) -> Option<Vec<u8>> {
+  if char == utils::REPLACEMENT_CHARACTER {
+    return None;
+  }
+
+  reverse_lookup_table(id, decoder, single_bytes, double_bytes)
+    .get(&char)
+    .cloned()
+}
+
+/// A reverse lookup table that maps characters to the bytes that encode them.
+///
+type ReverseLookupTable = HashMap<char, Vec<u8>>;
+
+/// Identifies the decoder that a reverse lookup table is built from. Decoders
+/// are identified by the character set or code element they belong to rather
+/// than by their address, because distinct functions aren't guaranteed to have
+/// distinct addresses.
+///
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ReverseLookupTableId {
+  /// The decoder for a character set without code extensions, identified by
+  /// its defined term. `allows_backslash` is set when the variant of the
+  /// decoder that allows the backslash character is used.
+  CharacterSet {
+    defined_term: &'static str,
+    allows_backslash: bool,
+  },
+
+  /// The decoder for a code element, identified by its escape sequence.
+  CodeElement { escape_sequence: [u8; 3] },
+}
+
+/// The key for a cached reverse lookup table, made up of the ID of its decoder
+/// along with the single-byte and double-byte ranges.
+///
+type ReverseLookupTableKey =
+  (ReverseLookupTableId, Option<(u8, u8)>, Option<(u8, u8)>);
+
+static REVERSE_LOOKUP_TABLES: LazyLock<
+  Mutex<HashMap<ReverseLookupTableKey, Arc<ReverseLookupTable>>>,
+> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Returns the reverse lookup table for a decoder, building it the first time
+/// it's needed. The table is built by decoding each single byte in
+/// `single_bytes`, followed by each pair of bytes that are both in
+/// `double_bytes`. When more than one byte sequence decodes to the same
+/// character, the first one is used.
+///
+fn reverse_lookup_table(
+  id: ReverseLookupTableId,
+  decoder: DecodeNextCodepointFn,
+  single_bytes: Option<RangeInclusive<u8>>,
+  double_bytes: Option<RangeInclusive<u8>>,
+) -> Arc<ReverseLookupTable> {
+  let key = (
+    id,
+    single_bytes.as_ref().map(|r| (*r.start(), *r.end())),
+    double_bytes.as_ref().map(|r| (*r.start(), *r.end())),
+  );
+
+  let mut tables = REVERSE_LOOKUP_TABLES.lock().unwrap();
+
+  tables
+    .entry(key)
+    .or_insert_with(|| {
+      let mut table = ReverseLookupTable::new();
+
+      let mut add_bytes = |bytes: Vec<u8>| {
+        if let Ok((char, [])) = decoder(&bytes) {
+          if char != utils::REPLACEMENT_CHARACTER {
+            table.entry(char).or_insert(bytes);
+          }
+        }
+      };
+
+      for byte in single_bytes.into_iter().flatten() {
+        add_bytes(vec![byte]);
+      }
+
+      if let Some(double_bytes) = double_bytes {
+        for byte_0 in double_bytes.clone() {
+          for byte_1 in double_bytes.clone() {
+            add_bytes(vec![byte_0, byte_1]);
+          }
+        }
+      }
+
+      Arc::new(table)
+    })
+    .clone()
+}
+
 /// A pair of G0/G1 code elements.
 ///
 pub type CodeElementPair = (Option<CodeElement>, Option<CodeElement>);
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
 use crate::internal::utils;
 
 /// Decodes the next codepoint from the given GB 18030 bytes.
@@ -305,7 +308,97 @@ pub fn decode_next_codepoint(bytes: &[u8]) -> Result<(char, &[u8]), ()> {
   }
 }
 
-const GBK_LOOKUP_TABLE: [u16; 24066] = [
+/// Encodes a codepoint into GB 18030 bytes. If `allow_four_byte` is false then
+/// only the single-byte and double-byte GBK characters are used.
+///
+/// Returns `None` if the codepoint can't be encoded.
+///
+pub fn encode_codepoint(char: char, allow_four_byte: bool) -> Option<Vec<u8>> {
+  let codepoint = char as u32;
+
+  if codepoint <= 0x7F {
+    return Some(vec![codepoint as u8]);
+  }
+
+  if char == utils::REPLACEMENT_CHARACTER {
+    return None;
+  }
+
+  // Look up a double-byte character in the GBK lookup table
+  if let Some(index) = GBK_REVERSE_LOOKUP_TABLE.get(&codepoint) {
+    return Some(vec![
+      (index / 0xBF + 0x81) as u8,
+      (index % 0xBF + 0x40) as u8,
+    ]);
+  }
+
+  if !allow_four_byte {
+    return None;
+  }
+
+  let index_to_bytes = |index: u32, first_byte: u32| {
+    vec![
+      (first_byte + index / 12600) as u8,
+      (0x30 + index / 1260 % 10) as u8,
+      (0x81 + index / 10 % 126) as u8,
+      (0x30 + index % 10) as u8,
+    ]
+  };
+
+  // Codepoints outside the Basic Multilingual Plane map directly to four-byte
+  // characters
+  if codepoint >= 0x10000 {
+    return Some(index_to_bytes(codepoint - 0x10000, 0x90));
+  }
+
+  // Look up a four-byte character that has a first byte in the range 0x81 -
+  // 0x84
+  FOUR_BYTE_REVERSE_LOOKUP_TABLE
+    .get(&char)
+    .map(|index| index_to_bytes(*index, 0x81))
+}
+
+/// Maps codepoints to their index in [`GBK_LOOKUP_TABLE`]. When a codepoint
+/// occurs more than once, the first index is used.
+///
+static GBK_REVERSE_LOOKUP_TABLE: LazyLock<HashMap<u32, usize>> =
+  LazyLock::new(|| {
+    let mut table = HashMap::with_capacity(GBK_LOOKUP_TABLE.len());
+
+    for (index, codepoint) in GBK_LOOKUP_TABLE.iter().enumerate() {
+      table.entry(*codepoint as u32).or_insert(index);
+    }
+
+    table
+  });
+
+/// Maps characters to the index of the four-byte characters that have a first
+/// byte in the range 0x81 - 0x84. This is built using the decoder, as it holds
+/// the mapping for this area.
+///
+static FOUR_BYTE_REVERSE_LOOKUP_TABLE: LazyLock<HashMap<char, u32>> =
+  LazyLock::new(|| {
+    let mut table = HashMap::new();
+
+    for index in 0..(4 * 12600) {
+      let bytes = [
+        (0x81 + index / 12600) as u8,
+        (0x30 + index / 1260 % 10) as u8,
+        (0x81 + index / 10 % 126) as u8,
+        (0x30 + index % 10) as u8,
+      ];
+
+      if let Ok((char, [])) = decode_next_codepoint(&bytes) {
+        if char != utils::REPLACEMENT_CHARACTER {
+          table.entry(char).or_insert(index);
+        }
+      }
+    }
+
+    table
+  });
+
+static GBK_LOOKUP_TABLE: [u16; 24066] = [
   0x4E02, 0x4E04, 0x4E05, 0x4E06, 0x4E0F, 0x4E12, 0x4E17, 0x4E1F, 0x4E20,
   0x4E21, 0x4E23, 0x4E26, 0x4E29, 0x4E2E, 0x4E2F, 0x4E31, 0x4E33, 0x4E35,
   0x4E37, 0x4E3C, 0x4E40, 0x4E41, 0x4E42, 0x4E44, 0x4E46, 0x4E4A, 0x4E51,
@@ -2986,10 +3079,27 @@ const GBK_LOOKUP_TABLE: [u16; 24066] = [
 mod tests {
   use super::*;
 
+  #[test]
+  fn encode_codepoint_test() {
+    for (char, expected_bytes) in [
+      ('A', vec![0x41]),
+      ('\u{81EA}', vec![0xD7, 0xD4]),
+      ('\u{00A5}', vec![0x81, 0x30, 0x84, 0x36]),
+      ('\u{2017}', vec![0x81, 0x36, 0xA5, 0x34]),
+      ('\u{10348}', vec![0x90, 0x30, 0xD5, 0x30]),
+    ] {
+      assert_eq!(encode_codepoint(char, true), Some(expected_bytes.clone()));
+      assert_eq!(decode_next_codepoint(&expected_bytes), Ok((char, &[][..])));
+    }
+
+    assert_eq!(encode_codepoint('\u{00A5}', false), None);
+    assert_eq!(encode_codepoint('\u{FFFD}', true), None);
+  }
+
   #[test]
   fn decode_next_codepoint_test() {
     for (bytes, expected_codepoint) in [
-      (vec![0xD7 as u8, 0xD4], '\u{81EA}'),
+      (vec![0xD7, 0xD4], '\u{81EA}'),
       (vec![0xDB, 0xFA], '\u{57F4}'),
       (vec![0x82, 0x7F], '\u{FFFD}'),
       (vec![0xA8, 0xBC], '\u{E7C7}'),
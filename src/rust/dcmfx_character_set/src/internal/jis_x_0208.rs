@@ -6,7 +6,7 @@ pub fn decode_next_codepoint(bytes: &[u8]) -> Result<(char, &[u8]), ()> {
   lookup_table_16bit::decode_next_codepoint(bytes, &LOOKUP_TABLE)
 }
 
-const LOOKUP_TABLE: [u16; 8836] = [
+static LOOKUP_TABLE: [u16; 8836] = [
   0x3000, 0x3001, 0x3002, 0xFF0C, 0xFF0E, 0x30FB, 0xFF1A, 0xFF1B, 0xFF1F,
   0xFF01, 0x309B, 0x309C, 0x00B4, 0xFF40, 0x00A8, 0xFF3E, 0xFFE3, 0xFF3F,
   0x30FD, 0x30FE, 0x309D, 0x309E, 0x3003, 0x4EDD, 0x3005, 0x3006, 0x3007,
@@ -1,9 +1,14 @@
 //! Decodes DICOM string data that uses a Specific Character Set into a native
 //! UTF-8 string.
 
+mod character_set_decoder;
 mod internal;
 
-use internal::character_set::{self, CharacterSet, CodeElementPair};
+pub use character_set_decoder::CharacterSetDecoder;
+
+use internal::character_set::{
+  self, CharacterSet, CodeElementPair, DecodeNextCodepointFn,
+};
 
 /// The type of string to be decoded. This determines the characters that act as
 /// delimiters and reset the active character set during decoding of encoded
@@ -39,7 +44,64 @@ pub enum StringType {
 /// be encountered when decoding.
 ///
 #[derive(Clone, Debug, PartialEq)]
-pub struct SpecificCharacterSet(Vec<&'static CharacterSet>);
+pub struct SpecificCharacterSet {
+  charsets: Vec<&'static CharacterSet>,
+
+  /// Whether ISO 2022 IR 6 was appended to the character sets because code
+  /// extensions are in use but it wasn't specified. An appended ISO 2022 IR 6
+  /// is not included when converting back to a string.
+  is_default_appended: bool,
+}
+
+/// An error that occurred when decoding bytes using
+/// [`SpecificCharacterSet::decode_bytes_strict()`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodeError {
+  /// The offset of the first byte that couldn't be decoded.
+  pub offset: usize,
+
+  /// The defined term of the character set that was active when the bytes
+  /// that couldn't be decoded were encountered, e.g. "ISO 2022 IR 87".
+  pub character_set: &'static str,
+
+  /// The code element that was active when the bytes that couldn't be decoded
+  /// were encountered. This is `None` when the specific character set doesn't
+  /// use ISO 2022 Code Extension techniques.
+  pub code_element: Option<CodeElementKind>,
+}
+
+/// The two code elements that can be active when decoding bytes that use ISO
+/// 2022 Code Extension techniques. The G0 code element is used for bytes less
+/// than 0x80, and the G1 code element is used for bytes from 0x80 onwards.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CodeElementKind {
+  G0,
+  G1,
+}
+
+impl std::fmt::Display for DecodeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "Invalid bytes at offset {} when decoding with character set \"{}\"",
+      self.offset, self.character_set
+    )?;
+
+    match self.code_element {
+      Some(CodeElementKind::G0) => write!(f, " using its G0 code element"),
+      Some(CodeElementKind::G1) => write!(f, " using its G1 code element"),
+      None => Ok(()),
+    }
+  }
+}
+
+impl std::fmt::Display for SpecificCharacterSet {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.to_defined_term_string())
+  }
+}
 
 impl SpecificCharacterSet {
   /// Converts a raw value from a "SpecificCharacterSet" data element into a
@@ -63,19 +125,26 @@ impl SpecificCharacterSet {
       }
     }
 
+    // Japanese data sets sometimes specify ISO_IR 13 as the first of multiple
+    // character sets when ISO 2022 IR 13 was intended. Its code elements are
+    // the same, so treat it as ISO 2022 IR 13 in order to keep the JIS X 0201
+    // katakana in G1 available across escapes to other G0 code elements.
+    if charsets.len() > 1 && charsets[0] == "ISO_IR 13" {
+      charsets[0] = "ISO 2022 IR 13".to_string();
+    }
+
     // Convert to recognized character sets
-    let mut charsets = charsets
+    let charsets = charsets
       .into_iter()
       .map(character_set::from_string)
-      .collect::<Result<Vec<&'static CharacterSet>, String>>(
-    )?;
+      .collect::<Result<Vec<&'static CharacterSet>, String>>()?;
 
     // If the first character set does not use extensions then it must be the
     // only one. Conversely, if extensions are in use then all character sets
     // must support them.
-    let charsets = match charsets.as_slice() {
+    match charsets.as_slice() {
       // A single value is always fine
-      [_] => Ok(charsets),
+      [_] => (),
 
       // If there are multiple values they must all support Code Extensions
       _ => {
@@ -89,21 +158,123 @@ impl SpecificCharacterSet {
           });
 
         if has_non_iso_2022_charset {
-          Err("SpecificCharacterSet has multiple non-ISO 2022 values")
-        } else {
-          // If ISO 2022 IR 6 isn't specified in the character sets then
-          // append it so it can still be used. This isn't mandated by the spec
-          // but it improves compatibility.
-          if !charsets.contains(&&character_set::ISO_2022_IR_6) {
-            charsets.push(&character_set::ISO_2022_IR_6);
-          }
-
-          Ok(charsets)
+          return Err(
+            "SpecificCharacterSet has multiple non-ISO 2022 values".to_string(),
+          );
         }
       }
-    }?;
+    };
+
+    let mut specific_character_set = Self {
+      charsets,
+      is_default_appended: false,
+    };
+
+    // If code extensions are in use and ISO 2022 IR 6 isn't specified in the
+    // character sets then append it so it can still be used. This isn't
+    // mandated by the spec but it improves compatibility.
+    if specific_character_set.uses_code_extensions()
+      && !specific_character_set
+        .charsets
+        .contains(&&character_set::ISO_2022_IR_6)
+    {
+      specific_character_set
+        .charsets
+        .push(&character_set::ISO_2022_IR_6);
+      specific_character_set.is_default_appended = true;
+    }
+
+    Ok(specific_character_set)
+  }
+
+  /// Converts the individual values of a multi-valued "SpecificCharacterSet"
+  /// data element into a `SpecificCharacterSet` instance. This is for use when
+  /// the values have already been split apart, e.g. by
+  /// `DataSet::get_strings()`, and so are no longer joined by backslashes.
+  ///
+  /// The values are joined with a backslash and then parsed using
+  /// [`Self::from_string()`].
+  ///
+  pub fn from_strings(
+    specific_character_sets: &[&str],
+  ) -> Result<Self, String> {
+    Self::from_string(&specific_character_sets.join("\\"))
+  }
+
+  /// Converts a specific character set to the canonical value for a
+  /// *'(0008,0005) Specific Character Set'* data element, with the defined
+  /// terms of its character sets joined by backslashes in their original order.
+  ///
+  /// When code extensions are in use and the first character set is the
+  /// default character set (ISO 2022 IR 6) then the first value is empty. An
+  /// ISO 2022 IR 6 that [`Self::from_string()`] appended automatically is
+  /// omitted. The default character set on its own is returned as "ISO_IR 6".
+  ///
+  /// For specific character set values that are already canonical, this is
+  /// the exact inverse of [`Self::from_string()`]. This is also the string
+  /// returned by the [`std::fmt::Display`] implementation.
+  ///
+  pub fn to_defined_term_string(&self) -> String {
+    let mut defined_terms = self.defined_terms();
+
+    if defined_terms.len() > 1
+      && defined_terms[0] == character_set::ISO_2022_IR_6.defined_term()
+    {
+      defined_terms[0] = "";
+    }
+
+    defined_terms.join("\\")
+  }
+
+  /// Returns the narrowest specific character set that is able to encode the
+  /// given string. This is the DICOM default character set (ISO_IR 6) if it
+  /// suffices, otherwise the first single-byte character set without code
+  /// extensions that can encode the string, and otherwise UTF-8 (ISO_IR 192).
+  ///
+  /// A specific character set that uses code extensions is never returned
+  /// because UTF-8 is always able to encode the string.
+  ///
+  pub fn minimal_for_string(s: &str) -> Self {
+    for charset in character_set::ALL_CHARACTER_SETS {
+      if !matches!(charset, CharacterSet::SingleByteWithoutExtensions { .. }) {
+        continue;
+      }
+
+      let specific_character_set = Self {
+        charsets: vec![charset],
+        is_default_appended: false,
+      };
+      if specific_character_set
+        .encode_bytes(s, StringType::SingleValue)
+        .is_ok()
+      {
+        return specific_character_set;
+      }
+    }
+
+    Self {
+      charsets: vec![&character_set::ISO_IR_192],
+      is_default_appended: false,
+    }
+  }
+
+  /// Returns the defined terms of the character sets in a specific character
+  /// set, e.g. `["ISO 2022 IR 6", "ISO 2022 IR 87"]`. These can be used as the
+  /// values of a *'(0008,0005) Specific Character Set'* data element. An
+  /// ISO 2022 IR 6 that [`Self::from_string()`] appended automatically is not
+  /// included.
+  ///
+  pub fn defined_terms(&self) -> Vec<&'static str> {
+    let charsets = if self.is_default_appended {
+      &self.charsets[..self.charsets.len() - 1]
+    } else {
+      &self.charsets[..]
+    };
 
-    Ok(Self(charsets))
+    charsets
+      .iter()
+      .map(|charset| charset.defined_term())
+      .collect()
   }
 
   /// Returns whether a specific character set is byte compatible with UTF-8.
@@ -111,9 +282,37 @@ impl SpecificCharacterSet {
   /// the UTF-8 character set itself (ISO_IR 192).
   ///
   pub fn is_utf8_compatible(&self) -> bool {
-    self.0.len() == 1
-      && (self.0[0] == &character_set::ISO_IR_6
-        || self.0[0] == &character_set::ISO_IR_192)
+    self.charsets.len() == 1
+      && (self.charsets[0] == &character_set::ISO_IR_6
+        || self.charsets[0] == &character_set::ISO_IR_192)
+  }
+
+  /// Returns whether a specific character set uses ISO 2022 Code Extension
+  /// techniques, i.e. its character sets are switched between using escape
+  /// sequences.
+  ///
+  pub fn uses_code_extensions(&self) -> bool {
+    self.charsets.iter().any(|charset| {
+      matches!(
+        charset,
+        CharacterSet::SingleByteWithExtensions { .. }
+          | CharacterSet::MultiByteWithExtensions { .. }
+      )
+    })
+  }
+
+  /// Returns whether a specific character set contains a multi-byte character
+  /// set, i.e. whether a single character may be encoded using more than one
+  /// byte.
+  ///
+  pub fn is_multi_byte(&self) -> bool {
+    self.charsets.iter().any(|charset| {
+      matches!(
+        charset,
+        CharacterSet::MultiByteWithExtensions { .. }
+          | CharacterSet::MultiByteWithoutExtensions { .. }
+      )
+    })
   }
 
   /// Decodes bytes using a specific character set to a native string.
@@ -122,41 +321,251 @@ impl SpecificCharacterSet {
   /// replaced with the U+FFFD character: �.
   ///
   pub fn decode_bytes(&self, bytes: &[u8], string_type: StringType) -> String {
-    let mut s = match self.0.as_slice() {
+    let mut s = match self.charsets.as_slice() {
       [CharacterSet::SingleByteWithoutExtensions {
         defined_term,
         decoder,
         ..
-      }] => {
-        // When using the ISO_IR 13 character set to decode bytes that support
-        // multiplicity, use a variant of JIS X 0201 that allows the backslash
-        // character
-        let decoder = if *defined_term == "ISO_IR 13"
-          && (string_type == StringType::MultiValue
-            || string_type == StringType::PersonName)
-        {
-          internal::jis_x_0201::decode_next_codepoint_allowing_backslash
-        } else {
-          *decoder
-        };
-
-        character_set::decode_bytes(bytes, decoder)
-      }
+      }] => character_set::decode_bytes(
+        bytes,
+        single_byte_decoder(defined_term, *decoder, string_type),
+      ),
 
       [CharacterSet::MultiByteWithoutExtensions { decoder, .. }] => {
         character_set::decode_bytes(bytes, *decoder)
       }
 
+      // This unwrap is safe because decoding only errors when strict
+      _ => self
+        .decode_iso_2022_bytes(
+          bytes,
+          string_type,
+          self.default_code_elements(),
+          false,
+        )
+        .unwrap(),
+    };
+
+    trim_codepoints_end(&mut s);
+
+    s
+  }
+
+  /// Decodes bytes using a specific character set to a native string in the
+  /// same way as [`Self::decode_bytes()`], except that bytes which can't be
+  /// decoded result in an error rather than being replaced with the U+FFFD
+  /// character. Unrecognized ISO 2022 escape sequences are also an error.
+  ///
+  /// This allows detection of string data that doesn't match its declared
+  /// specific character set.
+  ///
+  pub fn decode_bytes_strict(
+    &self,
+    bytes: &[u8],
+    string_type: StringType,
+  ) -> Result<String, DecodeError> {
+    let mut s = match self.charsets.as_slice() {
+      [CharacterSet::SingleByteWithoutExtensions {
+        defined_term,
+        decoder,
+        ..
+      }] => character_set::decode_bytes_strict(
+        bytes,
+        single_byte_decoder(defined_term, *decoder, string_type),
+      )
+      .map_err(|offset| DecodeError {
+        offset,
+        character_set: defined_term,
+        code_element: None,
+      })?,
+
+      [CharacterSet::MultiByteWithoutExtensions {
+        defined_term,
+        decoder,
+        ..
+      }] => character_set::decode_bytes_strict(bytes, *decoder).map_err(
+        |offset| DecodeError {
+          offset,
+          character_set: defined_term,
+          code_element: None,
+        },
+      )?,
+
       _ => self.decode_iso_2022_bytes(
         bytes,
         string_type,
         self.default_code_elements(),
-      ),
+        true,
+      )?,
     };
 
     trim_codepoints_end(&mut s);
 
-    s
+    Ok(s)
+  }
+
+  /// Encodes a native string into bytes using a specific character set.
+  ///
+  /// When the specific character set uses ISO 2022 Code Extension techniques,
+  /// each character is encoded using the active code elements if possible,
+  /// and otherwise the first character set able to encode it is designated by
+  /// emitting its escape sequence. The default code elements are reactivated
+  /// before every delimiter and at the end of the string.
+  ///
+  /// Returns an error if a character can't be encoded by any of the character
+  /// sets.
+  ///
+  pub fn encode_bytes(
+    &self,
+    s: &str,
+    string_type: StringType,
+  ) -> Result<Vec<u8>, String> {
+    match self.charsets.as_slice() {
+      [CharacterSet::SingleByteWithoutExtensions {
+        defined_term,
+        decoder,
+        ..
+      }] => {
+        let decoder = single_byte_decoder(defined_term, *decoder, string_type);
+        let id = character_set::ReverseLookupTableId::CharacterSet {
+          defined_term,
+          allows_backslash: allows_backslash(defined_term, string_type),
+        };
+
+        let mut bytes = Vec::with_capacity(s.len());
+        for char in s.chars() {
+          match character_set::encode_char(
+            char,
+            id,
+            decoder,
+            Some(0x00..=0xFF),
+            None,
+          ) {
+            Some(char_bytes) => bytes.extend_from_slice(&char_bytes),
+            None => return Err(self.unencodable_char_error(char)),
+          }
+        }
+
+        Ok(bytes)
+      }
+
+      [charset @ CharacterSet::MultiByteWithoutExtensions { .. }] => {
+        if *charset == &character_set::ISO_IR_192 {
+          return Ok(s.as_bytes().to_vec());
+        }
+
+        // The other multi-byte character sets without extensions are GB 18030
+        // and its GBK subset
+        let allow_four_byte = *charset == &character_set::GB_18030;
+
+        let mut bytes = Vec::with_capacity(s.len());
+        for char in s.chars() {
+          match internal::gb_18030::encode_codepoint(char, allow_four_byte) {
+            Some(char_bytes) => bytes.extend_from_slice(&char_bytes),
+            None => return Err(self.unencodable_char_error(char)),
+          }
+        }
+
+        Ok(bytes)
+      }
+
+      _ => self.encode_iso_2022_bytes(s, string_type),
+    }
+  }
+
+  fn encode_iso_2022_bytes(
+    &self,
+    s: &str,
+    string_type: StringType,
+  ) -> Result<Vec<u8>, String> {
+    // When there is no default G0 code element the decoder falls back to the
+    // default character set, so the same is done here
+    let mut default_code_elements = self.default_code_elements();
+    if default_code_elements.0.is_none() {
+      default_code_elements.0 = Some(character_set::ISO_IR_6_CODE_ELEMENT);
+    }
+
+    let mut active_code_elements = default_code_elements;
+    let mut bytes = Vec::with_capacity(s.len());
+
+    for char in s.chars() {
+      let is_delimiter = is_iso_2022_delimiter(char, string_type);
+
+      if is_delimiter {
+        activate_code_elements(
+          &mut bytes,
+          &mut active_code_elements,
+          &default_code_elements,
+        );
+      }
+
+      let char_bytes =
+        match encode_char_with_code_elements(char, &active_code_elements) {
+          Some(char_bytes) => char_bytes,
+          None => self.designate_code_element_for_char(
+            char,
+            &mut bytes,
+            &mut active_code_elements,
+          )?,
+        };
+
+      bytes.extend_from_slice(&char_bytes);
+
+      // Decoding resets the code elements after every delimiter
+      if is_delimiter {
+        active_code_elements = default_code_elements;
+      }
+    }
+
+    activate_code_elements(
+      &mut bytes,
+      &mut active_code_elements,
+      &default_code_elements,
+    );
+
+    Ok(bytes)
+  }
+
+  /// Finds the first code element in the specific character set that can
+  /// encode the given character, and makes it active by appending its escape
+  /// sequence. Returns the encoded bytes for the character.
+  ///
+  fn designate_code_element_for_char(
+    &self,
+    char: char,
+    bytes: &mut Vec<u8>,
+    active_code_elements: &mut CodeElementPair,
+  ) -> Result<Vec<u8>, String> {
+    for charset in self.charsets.iter() {
+      let (g0, g1) = charset.code_elements();
+
+      if let Some(g0) = g0 {
+        if let Some(char_bytes) = g0.encode_char(char, false) {
+          append_escape_sequence(bytes, &g0);
+          active_code_elements.0 = Some(g0);
+          return Ok(char_bytes);
+        }
+      }
+
+      if let Some(g1) = g1 {
+        if let Some(char_bytes) = g1.encode_char(char, true) {
+          append_escape_sequence(bytes, &g1);
+          active_code_elements.1 = Some(g1);
+          return Ok(char_bytes);
+        }
+      }
+    }
+
+    Err(self.unencodable_char_error(char))
+  }
+
+  fn unencodable_char_error(&self, char: char) -> String {
+    format!(
+      "Character {:?} can't be encoded using the specific character set \
+       \"{}\"",
+      char,
+      self.defined_terms().join("\\")
+    )
   }
 
   fn decode_iso_2022_bytes(
@@ -164,52 +573,59 @@ impl SpecificCharacterSet {
     mut bytes: &[u8],
     string_type: StringType,
     mut active_code_elements: CodeElementPair,
-  ) -> String {
+    strict: bool,
+  ) -> Result<String, DecodeError> {
     let mut s = String::with_capacity(bytes.len());
+    let length = bytes.len();
 
     loop {
       match bytes {
-        [] => return s,
+        [] => return Ok(s),
 
         // Detect escape sequences and use them to update the active code
         // elements
         [0x1B, rest @ ..] => {
-          bytes = self.apply_escape_sequence(rest, &mut active_code_elements);
+          let next_bytes =
+            self.apply_escape_sequence(rest, &mut active_code_elements);
+
+          // In strict mode an unrecognized escape sequence is an error
+          if strict && next_bytes.len() == rest.len() {
+            return Err(DecodeError {
+              offset: length - bytes.len(),
+              character_set: self
+                .code_element_character_set(&active_code_elements.0),
+              code_element: Some(CodeElementKind::G0),
+            });
+          }
+
+          bytes = next_bytes;
         }
 
         _ => {
-          // Determine the decoder to use
-          let decoder = match (bytes, &active_code_elements) {
-            // If the byte has its high bit set and there is a G1 code element
-            // active then use it
-            ([byte, ..], (_, Some(g1))) if *byte >= 0x80 => g1.decoder,
-
-            // Otherwise if there is a G0 code element active then use it
-            (_, (Some(g0), _)) => g0.decoder,
-
-            // Fall back to the default character set
-            _ => internal::iso_ir_6::decode_next_codepoint,
-          };
+          let (decoder, code_element, code_element_kind) =
+            active_decoder(bytes, &active_code_elements);
 
           // This unwrap is safe because decoders only error when fed no bytes
           let (char, next_bytes) = decoder(bytes).unwrap();
 
+          if strict
+            && !character_set::is_valid_decode(
+              char,
+              &bytes[..bytes.len() - next_bytes.len()],
+            )
+          {
+            return Err(DecodeError {
+              offset: length - bytes.len(),
+              character_set: self.code_element_character_set(&code_element),
+              code_element: Some(code_element_kind),
+            });
+          }
+
           // Detect delimiters and reset code elements to default when they
           // occur
-          match (char, &string_type) {
-            ('\u{9}', _)
-            | ('\u{A}', _)
-            | ('\u{C}', _)
-            | ('\u{D}', _)
-            | ('\\', StringType::MultiValue)
-            | ('\\', StringType::PersonName)
-            | ('=', StringType::PersonName)
-            | ('^', StringType::PersonName) => {
-              active_code_elements = self.default_code_elements()
-            }
-
-            _ => (),
-          };
+          if is_iso_2022_delimiter(char, string_type) {
+            active_code_elements = self.default_code_elements();
+          }
 
           let mut char_utf8: [u8; 4] = [0, 0, 0, 0];
           s.push_str(char.encode_utf8(&mut char_utf8));
@@ -220,12 +636,30 @@ impl SpecificCharacterSet {
     }
   }
 
+  /// Returns the defined term of the character set that a code element belongs
+  /// to. When there's no code element the default character set is in use.
+  ///
+  fn code_element_character_set(
+    &self,
+    code_element: &Option<character_set::CodeElement>,
+  ) -> &'static str {
+    self
+      .charsets
+      .iter()
+      .find(|charset| {
+        let (g0, g1) = charset.code_elements();
+        code_element.is_some() && (g0 == *code_element || g1 == *code_element)
+      })
+      .map(|charset| charset.defined_term())
+      .unwrap_or(character_set::ISO_2022_IR_6.defined_term())
+  }
+
   /// Returns the default G0 and G1 code elements which are the ones specified
   /// by the first character set. These are the initially active code elements
   /// and they are also reactivated after any delimiter is encountered.
   ///
   fn default_code_elements(&self) -> CodeElementPair {
-    match self.0.as_slice() {
+    match self.charsets.as_slice() {
       [CharacterSet::SingleByteWithExtensions {
         code_element_g0,
         code_element_g1,
@@ -252,7 +686,7 @@ impl SpecificCharacterSet {
     bytes: &'a [u8],
     active_code_elements: &mut CodeElementPair,
   ) -> &'a [u8] {
-    for charset in self.0.iter() {
+    for charset in self.charsets.iter() {
       let code_elements = charset.code_elements();
 
       // See if the escape sequence applies to the G0 code element of this
@@ -280,17 +714,134 @@ impl SpecificCharacterSet {
   }
 }
 
+/// Returns the decoder to use for the next character in the given bytes based
+/// on the active code elements, along with the code element it belongs to.
+///
+fn active_decoder(
+  bytes: &[u8],
+  active_code_elements: &CodeElementPair,
+) -> (
+  DecodeNextCodepointFn,
+  Option<character_set::CodeElement>,
+  CodeElementKind,
+) {
+  match (bytes, active_code_elements) {
+    // If the byte has its high bit set and there is a G1 code element active
+    // then use it
+    ([byte, ..], (_, Some(g1))) if *byte >= 0x80 => {
+      (g1.decoder, Some(*g1), CodeElementKind::G1)
+    }
+
+    // Otherwise if there is a G0 code element active then use it
+    (_, (Some(g0), _)) => (g0.decoder, Some(*g0), CodeElementKind::G0),
+
+    // Fall back to the default character set
+    _ => (
+      internal::iso_ir_6::decode_next_codepoint as DecodeNextCodepointFn,
+      None,
+      CodeElementKind::G0,
+    ),
+  }
+}
+
+/// Returns the decoder to use for a single-byte character set without code
+/// extensions. When using the ISO_IR 13 character set with strings that support
+/// multiplicity, a variant of JIS X 0201 that allows the backslash character is
+/// used.
+///
+fn single_byte_decoder(
+  defined_term: &str,
+  decoder: DecodeNextCodepointFn,
+  string_type: StringType,
+) -> DecodeNextCodepointFn {
+  if allows_backslash(defined_term, string_type) {
+    internal::jis_x_0201::decode_next_codepoint_allowing_backslash
+  } else {
+    decoder
+  }
+}
+
+/// Returns whether [`single_byte_decoder()`] uses the variant of a decoder
+/// that allows the backslash character.
+///
+fn allows_backslash(defined_term: &str, string_type: StringType) -> bool {
+  defined_term == "ISO_IR 13"
+    && (string_type == StringType::MultiValue
+      || string_type == StringType::PersonName)
+}
+
+/// Returns whether a character is a delimiter that resets the active code
+/// elements to their defaults when using ISO 2022 Code Extension techniques.
+///
+fn is_iso_2022_delimiter(char: char, string_type: StringType) -> bool {
+  matches!(
+    (char, string_type),
+    ('\u{9}', _)
+      | ('\u{A}', _)
+      | ('\u{C}', _)
+      | ('\u{D}', _)
+      | ('\\', StringType::MultiValue)
+      | ('\\', StringType::PersonName)
+      | ('=', StringType::PersonName)
+      | ('^', StringType::PersonName)
+  )
+}
+
+/// Encodes a character using the active G0 code element, falling back to the
+/// active G1 code element.
+///
+fn encode_char_with_code_elements(
+  char: char,
+  code_elements: &CodeElementPair,
+) -> Option<Vec<u8>> {
+  code_elements
+    .0
+    .and_then(|g0| g0.encode_char(char, false))
+    .or_else(|| code_elements.1.and_then(|g1| g1.encode_char(char, true)))
+}
+
+/// Appends the escape sequences needed to make the target code elements
+/// active. A G1 code element can't be made inactive, so if the target has no
+/// G1 code element then the active one is left unchanged.
+///
+fn activate_code_elements(
+  bytes: &mut Vec<u8>,
+  active_code_elements: &mut CodeElementPair,
+  target_code_elements: &CodeElementPair,
+) {
+  if let Some(g0) = target_code_elements.0 {
+    if active_code_elements.0 != Some(g0) {
+      append_escape_sequence(bytes, &g0);
+      active_code_elements.0 = Some(g0);
+    }
+  }
+
+  if let Some(g1) = target_code_elements.1 {
+    if active_code_elements.1 != Some(g1) {
+      append_escape_sequence(bytes, &g1);
+      active_code_elements.1 = Some(g1);
+    }
+  }
+}
+
+fn append_escape_sequence(
+  bytes: &mut Vec<u8>,
+  code_element: &character_set::CodeElement,
+) {
+  bytes.push(0x1B);
+  bytes.extend_from_slice(code_element.escape_sequence());
+}
+
 fn update_code_element<'a>(
   candidate: &Option<character_set::CodeElement>,
   bytes: &'a [u8],
 ) -> Result<&'a [u8], ()> {
   match candidate {
     Some(candidate) => {
-      let escape_sequence = candidate.escape_sequence;
-      let escape_sequence_length = if escape_sequence[2] == 0 { 2 } else { 3 };
+      let escape_sequence = candidate.escape_sequence();
 
-      if bytes.starts_with(&escape_sequence[0..escape_sequence_length]) {
-        Ok(&bytes[escape_sequence_length..])
+      if bytes.starts_with(escape_sequence) {
+        Ok(&bytes[escape_sequence.len()..])
       } else {
         Err(())
       }
@@ -345,6 +896,10 @@ mod tests {
     assert!(
       SpecificCharacterSet::from_string("ISO_IR 6\\ISO 2022 IR 87").is_err()
     );
+    assert_eq!(
+      SpecificCharacterSet::from_string("ISO_IR 13\\ISO 2022 IR 87"),
+      SpecificCharacterSet::from_string("ISO 2022 IR 13\\ISO 2022 IR 87")
+    );
     assert!(SpecificCharacterSet::from_string("ISO_IR 192").is_ok());
     assert!(
       SpecificCharacterSet::from_string("ISO_IR 192\\ISO 2022 IR 149").is_err()
@@ -354,6 +909,132 @@ mod tests {
     assert!(SpecificCharacterSet::from_string("ISO_IR 90210").is_err());
   }
 
+  #[test]
+  pub fn specific_character_set_from_strings_test() {
+    assert_eq!(
+      SpecificCharacterSet::from_strings(&["ISO 2022 IR 6", " ISO 2022 IR 87"]),
+      SpecificCharacterSet::from_string("ISO 2022 IR 6\\ISO 2022 IR 87")
+    );
+    assert_eq!(
+      SpecificCharacterSet::from_strings(&["", "ISO 2022 IR 144"]),
+      SpecificCharacterSet::from_string("\\ISO 2022 IR 144")
+    );
+    assert!(SpecificCharacterSet::from_strings(&["ISO_IR 192"]).is_ok());
+    assert!(SpecificCharacterSet::from_strings(&[
+      "ISO_IR 6",
+      "ISO 2022 IR 87"
+    ])
+    .is_err());
+  }
+
+  #[test]
+  pub fn to_defined_term_string_test() {
+    for specific_character_set in [
+      "ISO_IR 6",
+      "ISO_IR 100",
+      "ISO_IR 192",
+      "GB18030",
+      "ISO 2022 IR 100",
+      "\\ISO 2022 IR 87",
+      "\\ISO 2022 IR 149",
+      "ISO 2022 IR 13\\ISO 2022 IR 87",
+      "ISO 2022 IR 13\\ISO 2022 IR 6",
+      "ISO 2022 IR 100\\ISO 2022 IR 6\\ISO 2022 IR 87",
+      "ISO 2022 IR 13\\ISO 2022 IR 87\\ISO 2022 IR 6",
+    ] {
+      assert_eq!(
+        SpecificCharacterSet::from_string(specific_character_set)
+          .unwrap()
+          .to_defined_term_string(),
+        specific_character_set
+      );
+    }
+
+    for (specific_character_set, expected) in [
+      ("", "ISO_IR 6"),
+      ("iso_ir 100", "ISO_IR 100"),
+      ("ISO 2022 IR 6\\ISO 2022 IR 87", "\\ISO 2022 IR 87"),
+    ] {
+      assert_eq!(
+        SpecificCharacterSet::from_string(specific_character_set)
+          .unwrap()
+          .to_defined_term_string(),
+        expected
+      );
+    }
+  }
+
+  #[test]
+  pub fn specific_character_set_display_test() {
+    // ISO 2022 IR 6 is appended automatically when code extensions are in use,
+    // but isn't included when displayed
+    let specific_character_set =
+      SpecificCharacterSet::from_string("ISO 2022 IR 87").unwrap();
+    assert!(specific_character_set
+      .charsets
+      .contains(&&character_set::ISO_2022_IR_6));
+    assert_eq!(specific_character_set.to_string(), "ISO 2022 IR 87");
+    assert_eq!(specific_character_set.defined_terms(), ["ISO 2022 IR 87"]);
+
+    assert_eq!(
+      SpecificCharacterSet::from_string("ISO 2022 IR 13\\ISO 2022 IR 87")
+        .unwrap()
+        .to_string(),
+      "ISO 2022 IR 13\\ISO 2022 IR 87"
+    );
+
+    // An explicitly specified ISO 2022 IR 6 is retained
+    assert_eq!(
+      SpecificCharacterSet::from_string("ISO 2022 IR 87\\ISO 2022 IR 6")
+        .unwrap()
+        .to_string(),
+      "ISO 2022 IR 87\\ISO 2022 IR 6"
+    );
+  }
+
+  #[test]
+  pub fn minimal_for_string_test() {
+    for (s, expected_specific_character_set) in [
+      ("", "ISO_IR 6"),
+      ("Doe^John\r\n", "ISO_IR 6"),
+      ("Äneas^Rüdiger", "ISO_IR 100"),
+      ("Wałęsa", "ISO_IR 101"),
+      ("Διονυσιος", "ISO_IR 126"),
+      ("ﾔﾏﾀﾞ^ﾀﾛｳ", "ISO_IR 13"),
+      ("Äneas=Διονυσιος", "ISO_IR 192"),
+      ("山田^太郎", "ISO_IR 192"),
+    ] {
+      assert_eq!(
+        SpecificCharacterSet::minimal_for_string(s),
+        SpecificCharacterSet::from_string(expected_specific_character_set)
+          .unwrap()
+      );
+    }
+
+    assert_eq!(
+      SpecificCharacterSet::minimal_for_string("Äneas").defined_terms(),
+      vec!["ISO_IR 100"]
+    );
+  }
+
+  #[test]
+  pub fn character_set_classification_test() {
+    for (specific_character_set, uses_code_extensions, is_multi_byte) in [
+      ("ISO_IR 100", false, false),
+      ("ISO 2022 IR 100", true, false),
+      ("ISO 2022 IR 87", true, true),
+      ("\\ISO 2022 IR 149", true, true),
+      ("GB18030", false, true),
+      ("ISO_IR 192", false, true),
+    ] {
+      let charset =
+        SpecificCharacterSet::from_string(specific_character_set).unwrap();
+
+      assert_eq!(charset.uses_code_extensions(), uses_code_extensions);
+      assert_eq!(charset.is_multi_byte(), is_multi_byte);
+    }
+  }
+
   #[test]
   pub fn decode_bytes_single_byte_without_extensions_test() {
     // Test decoding of ISO IR 100 bytes (ISO 646, US-ASCII)
@@ -606,10 +1287,7 @@ mod tests {
             &[0x1B, 0x2D, 0x46, 0xED, *delimiter, 0xED],
             string_type,
           ),
-          format!(
-            "ν{}í",
-            char::from_u32(*delimiter as u32).unwrap().to_string()
-          )
+          format!("ν{}í", char::from_u32(*delimiter as u32).unwrap())
         );
       }
 
@@ -620,10 +1298,7 @@ mod tests {
             &[0x1B, 0x2D, 0x46, 0xED, *non_delimiter, 0xED],
             string_type,
           ),
-          format!(
-            "ν{}ν",
-            char::from_u32(*non_delimiter as u32).unwrap().to_string()
-          )
+          format!("ν{}ν", char::from_u32(*non_delimiter as u32).unwrap())
         );
       }
     }
@@ -725,6 +1400,26 @@ mod tests {
       ),
       "ﾔﾏﾀﾞ^ﾀﾛｳ=山田^太郎=やまだ^たろう"
     );
+
+    // Test that the JIS X 0201 katakana in G1 remain available after escaping
+    // to JIS X 0208 and then back to either JIS X 0201 or ISO 646 in G0
+    for specific_character_set in [
+      "ISO 2022 IR 13\\ISO 2022 IR 87",
+      "ISO_IR 13\\ISO 2022 IR 87",
+    ] {
+      assert_eq!(
+        decode_bytes(
+          specific_character_set,
+          &[
+            0xD4, 0x1B, 0x24, 0x42, 0x3B, 0x33, 0xCF, 0x1B, 0x28, 0x4A, 0xC0,
+            0xDE, 0x5E, 0x1B, 0x24, 0x42, 0x45, 0x44, 0x1B, 0x28, 0x42, 0xC0,
+            0xDB, 0xB3,
+          ],
+          StringType::PersonName,
+        ),
+        "ﾔ山ﾏﾀﾞ^田ﾀﾛｳ"
+      );
+    }
   }
 
   #[test]
@@ -944,6 +1639,201 @@ mod tests {
     );
   }
 
+  #[test]
+  pub fn decode_bytes_strict_test() {
+    assert_eq!(
+      decode_bytes_strict("ISO_IR 192", "Ä�  ".as_bytes()),
+      Ok("Ä�".to_string())
+    );
+
+    assert_eq!(
+      decode_bytes_strict("ISO_IR 192", &[0x41, 0xFF, 0x42]),
+      Err(DecodeError {
+        offset: 1,
+        character_set: "ISO_IR 192",
+        code_element: None,
+      })
+    );
+
+    assert_eq!(
+      decode_bytes_strict("ISO_IR 6", &[0x41, 0x42, 0xC4]),
+      Err(DecodeError {
+        offset: 2,
+        character_set: "ISO_IR 6",
+        code_element: None,
+      })
+    );
+
+    assert_eq!(
+      decode_bytes_strict(
+        "\\ISO 2022 IR 87",
+        &[0x59, 0x3D, 0x1B, 0x24, 0x42, 0x3B, 0x33, 0x1B, 0x28, 0x42]
+      ),
+      Ok("Y=山".to_string())
+    );
+
+    assert_eq!(
+      decode_bytes_strict(
+        "\\ISO 2022 IR 87",
+        &[0x59, 0x3D, 0x1B, 0x24, 0x42, 0x7E, 0x7E]
+      ),
+      Err(DecodeError {
+        offset: 5,
+        character_set: "ISO 2022 IR 87",
+        code_element: Some(CodeElementKind::G0),
+      })
+    );
+
+    assert_eq!(
+      decode_bytes_strict(
+        "\\ISO 2022 IR 149",
+        &[0x1B, 0x24, 0x29, 0x43, 0xFB, 0xF3, 0xA1, 0x41]
+      ),
+      Err(DecodeError {
+        offset: 6,
+        character_set: "ISO 2022 IR 149",
+        code_element: Some(CodeElementKind::G1),
+      })
+    );
+
+    // Unrecognized escape sequences are an error
+    let error = decode_bytes_strict(
+      "\\ISO 2022 IR 100",
+      &[0x41, 0x1B, 0x24, 0x42, 0x3B, 0x33],
+    )
+    .unwrap_err();
+    assert_eq!(
+      error,
+      DecodeError {
+        offset: 1,
+        character_set: "ISO 2022 IR 6",
+        code_element: Some(CodeElementKind::G0),
+      }
+    );
+    assert_eq!(
+      error.to_string(),
+      "Invalid bytes at offset 1 when decoding with character set \
+       \"ISO 2022 IR 6\" using its G0 code element"
+    );
+  }
+
+  fn decode_bytes_strict(
+    specific_character_set: &str,
+    bytes: &[u8],
+  ) -> Result<String, DecodeError> {
+    let charset =
+      SpecificCharacterSet::from_string(specific_character_set).unwrap();
+
+    charset.decode_bytes_strict(bytes, StringType::PersonName)
+  }
+
+  #[test]
+  pub fn encode_bytes_test() {
+    assert_eq!(
+      encode_bytes("ISO_IR 100", "Äneas^Rüdiger", StringType::PersonName),
+      Ok(vec![
+        0xC4, 0x6E, 0x65, 0x61, 0x73, 0x5E, 0x52, 0xFC, 0x64, 0x69, 0x67, 0x65,
+        0x72
+      ])
+    );
+
+    assert_eq!(
+      encode_bytes("ISO_IR 192", "王^小東", StringType::PersonName),
+      Ok("王^小東".as_bytes().to_vec())
+    );
+
+    assert_eq!(
+      encode_bytes("GB18030", "Wang^XiaoDong=王^小东", StringType::PersonName),
+      Ok(vec![
+        0x57, 0x61, 0x6E, 0x67, 0x5E, 0x58, 0x69, 0x61, 0x6F, 0x44, 0x6F, 0x6E,
+        0x67, 0x3D, 0xCD, 0xF5, 0x5E, 0xD0, 0xA1, 0xB6, 0xAB
+      ])
+    );
+
+    // Test examples from Annex H and Annex I of the DICOM standard, which use
+    // ISO 2022 escape sequences
+    for (specific_character_set, s) in [
+      ("\\ISO 2022 IR 87", "Yamada^Tarou=山田^太郎=やまだ^たろう"),
+      (
+        "ISO 2022 IR 13\\ISO 2022 IR 87",
+        "ﾔﾏﾀﾞ^ﾀﾛｳ=山田^太郎=やまだ^たろう",
+      ),
+      ("\\ISO 2022 IR 149", "Hong^Gildong=洪^吉洞=홍^길동"),
+    ] {
+      let bytes =
+        encode_bytes(specific_character_set, s, StringType::PersonName)
+          .unwrap();
+
+      assert_eq!(
+        decode_bytes(specific_character_set, &bytes, StringType::PersonName),
+        s
+      );
+    }
+
+    assert_eq!(
+      encode_bytes(
+        "\\ISO 2022 IR 87",
+        "Yamada^Tarou=山田^太郎",
+        StringType::PersonName
+      ),
+      Ok(vec![
+        0x59, 0x61, 0x6D, 0x61, 0x64, 0x61, 0x5E, 0x54, 0x61, 0x72, 0x6F, 0x75,
+        0x3D, 0x1B, 0x24, 0x42, 0x3B, 0x33, 0x45, 0x44, 0x1B, 0x28, 0x42, 0x5E,
+        0x1B, 0x24, 0x42, 0x42, 0x40, 0x4F, 0x3A, 0x1B, 0x28, 0x42,
+      ])
+    );
+
+    assert_eq!(
+      encode_bytes("\\ISO 2022 IR 149", "Hong=洪^吉洞", StringType::PersonName),
+      Ok(vec![
+        0x48, 0x6F, 0x6E, 0x67, 0x3D, 0x1B, 0x24, 0x29, 0x43, 0xFB, 0xF3, 0x5E,
+        0x1B, 0x24, 0x29, 0x43, 0xD1, 0xCE, 0xD4, 0xD7,
+      ])
+    );
+
+    // ISO_IR 13 encodes 0x5C as a yen sign, except in strings that support
+    // multiplicity where it's a backslash. Each uses its own reverse lookup
+    // table.
+    assert_eq!(
+      encode_bytes("ISO_IR 13", "¥", StringType::SingleValue),
+      Ok(vec![0x5C])
+    );
+    assert_eq!(
+      encode_bytes("ISO_IR 13", "ﾔ\\ﾏ", StringType::MultiValue),
+      Ok(vec![0xD4, 0x5C, 0xCF])
+    );
+    assert!(encode_bytes("ISO_IR 13", "¥", StringType::MultiValue).is_err());
+
+    assert_eq!(
+      encode_bytes("ISO_IR 100", "山", StringType::SingleValue),
+      Err(
+        "Character '山' can't be encoded using the specific character set \
+         \"ISO_IR 100\""
+          .to_string()
+      )
+    );
+
+    assert_eq!(
+      encode_bytes("\\ISO 2022 IR 87", "Ä", StringType::SingleValue),
+      Err(
+        "Character 'Ä' can't be encoded using the specific character set \
+         \"ISO 2022 IR 6\\ISO 2022 IR 87\""
+          .to_string()
+      )
+    );
+  }
+
+  fn encode_bytes(
+    specific_character_set: &str,
+    s: &str,
+    string_type: StringType,
+  ) -> Result<Vec<u8>, String> {
+    let charset =
+      SpecificCharacterSet::from_string(specific_character_set).unwrap();
+
+    charset.encode_bytes(s, string_type)
+  }
+
   fn decode_bytes(
     specific_character_set: &str,
     bytes: &[u8],
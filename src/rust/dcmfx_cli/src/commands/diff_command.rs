@@ -0,0 +1,251 @@
+use std::io::Write;
+use std::process::ExitCode;
+
+use clap::Args;
+
+use dcmfx::core::*;
+use dcmfx::p10::*;
+
+pub const ABOUT: &str = "Compares two DICOM P10 files and prints the \
+  differences between them. Exits with status 1 if there are any \
+  differences, or 2 if an error occurs.";
+
+/// The maximum width of the values included in JSON output. Longer values,
+/// such as large binary values, are truncated with a trailing ellipsis.
+///
+const JSON_VALUE_MAX_WIDTH: usize = 256;
+
+#[derive(Args)]
+pub struct DiffArgs {
+  #[clap(help = "The name of the first DICOM P10 file to compare")]
+  input_filename_a: String,
+
+  #[clap(help = "The name of the second DICOM P10 file to compare")]
+  input_filename_b: String,
+
+  #[arg(
+    long,
+    help = "A data element tag to exclude from the comparison. Data elements \
+      with this tag are ignored at all levels of nesting. This option can be \
+      specified multiple times. E.g. --ignore 00080018",
+    value_parser = validate_data_element_tag,
+    value_name = "TAG"
+  )]
+  ignore: Vec<DataElementTag>,
+
+  #[arg(
+    long,
+    help = "Whether to print the differences as a JSON array instead of as \
+      human-readable text",
+    default_value_t = false
+  )]
+  json: bool,
+}
+
+fn validate_data_element_tag(s: &str) -> Result<DataElementTag, String> {
+  DataElementTag::from_hex_string(s)
+    .map_err(|_| format!("Invalid data element tag: {}", s))
+}
+
+/// The exit status when the files being compared have differences. Following
+/// diff(1), this is distinct from the exit status used when an error occurs.
+///
+const EXIT_STATUS_DIFFERENT: u8 = 1;
+
+/// The exit status when an error occurs, such as a file being unreadable.
+///
+const EXIT_STATUS_ERROR: u8 = 2;
+
+pub fn run(args: &DiffArgs) -> ExitCode {
+  let differences = match perform_diff(
+    &args.input_filename_a,
+    &args.input_filename_b,
+    &args.ignore,
+  ) {
+    Ok(differences) => differences,
+    Err((e, filename)) => {
+      e.print(&format!("reading file \"{}\"", filename));
+      return ExitCode::from(EXIT_STATUS_ERROR);
+    }
+  };
+
+  let output = if args.json {
+    differences_to_json(&differences)
+  } else {
+    differences
+      .iter()
+      .map(|difference| format!("{}\n", difference))
+      .collect()
+  };
+
+  if let Err(e) = std::io::stdout().write_all(output.as_bytes()) {
+    P10Error::FileError {
+      when: "Writing to stdout".to_string(),
+      details: e.to_string(),
+    }
+    .print("writing diff output");
+
+    return ExitCode::from(EXIT_STATUS_ERROR);
+  }
+
+  if differences.is_empty() {
+    ExitCode::SUCCESS
+  } else {
+    ExitCode::from(EXIT_STATUS_DIFFERENT)
+  }
+}
+
+/// Reads two DICOM P10 files and returns the differences between them. On
+/// error, the name of the file that couldn't be read is returned along with
+/// the error.
+///
+fn perform_diff<'a>(
+  input_filename_a: &'a str,
+  input_filename_b: &'a str,
+  ignore: &[DataElementTag],
+) -> Result<Vec<DataSetDifference>, (P10Error, &'a str)> {
+  let mut data_set_a = dcmfx::p10::read_file(input_filename_a)
+    .map_err(|e| (e, input_filename_a))?;
+  let mut data_set_b = dcmfx::p10::read_file(input_filename_b)
+    .map_err(|e| (e, input_filename_b))?;
+
+  delete_tags(&mut data_set_a, ignore);
+  delete_tags(&mut data_set_b, ignore);
+
+  Ok(data_set_a.diff(&data_set_b))
+}
+
+/// Deletes the specified tags from a data set, including from inside any
+/// sequences it contains.
+///
+fn delete_tags(data_set: &mut DataSet, tags: &[DataElementTag]) {
+  for tag in tags {
+    data_set.delete(*tag);
+  }
+
+  for (_, value) in data_set.iter_mut() {
+    if let Ok(items) = value.sequence_items_mut() {
+      for item in items.iter_mut() {
+        delete_tags(item, tags);
+      }
+    }
+  }
+}
+
+/// Converts a list of data set differences to a JSON array with one object
+/// per difference.
+///
+fn differences_to_json(differences: &[DataSetDifference]) -> String {
+  let value_to_json = |path: &DataSetPath, value: &DataElementValue| {
+    let tag = path
+      .final_data_element()
+      .unwrap_or(DataElementTag::new(0, 0));
+
+    serde_json::Value::String(value.to_string(tag, JSON_VALUE_MAX_WIDTH))
+  };
+
+  let differences = differences
+    .iter()
+    .map(|difference| {
+      let path = difference.path();
+
+      let mut object = serde_json::Map::new();
+      object.insert("path".to_string(), path.to_string().into());
+      object
+        .insert("description".to_string(), path.to_detailed_string().into());
+
+      match difference {
+        DataSetDifference::Added { value, .. } => {
+          object.insert("change".to_string(), "added".into());
+          object.insert("new_value".to_string(), value_to_json(path, value));
+        }
+
+        DataSetDifference::Removed { value, .. } => {
+          object.insert("change".to_string(), "removed".into());
+          object.insert("old_value".to_string(), value_to_json(path, value));
+        }
+
+        DataSetDifference::Changed {
+          old_value,
+          new_value,
+          ..
+        } => {
+          object.insert("change".to_string(), "changed".into());
+          object
+            .insert("old_value".to_string(), value_to_json(path, old_value));
+          object
+            .insert("new_value".to_string(), value_to_json(path, new_value));
+        }
+      }
+
+      serde_json::Value::Object(object)
+    })
+    .collect();
+
+  format!("{}\n", serde_json::Value::Array(differences))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use dcmfx::anonymize::*;
+
+  #[test]
+  fn diff_anonymized_test() {
+    let input_filename =
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm");
+
+    let mut data_set = dcmfx::p10::read_file(input_filename).unwrap();
+    data_set.anonymize();
+
+    let anonymized_filename = std::env::temp_dir().join(format!(
+      "dcmfx_diff_anonymized_test_{}.dcm",
+      std::process::id()
+    ));
+    let anonymized_filename = anonymized_filename.to_str().unwrap();
+    dcmfx::p10::write_file(anonymized_filename, &data_set, None).unwrap();
+
+    let differences =
+      perform_diff(input_filename, anonymized_filename, &[]).unwrap();
+
+    assert!(differences.contains(&DataSetDifference::Removed {
+      path: DataSetPath::new_with_data_element(dictionary::PATIENT_ID.tag),
+      value: DataElementValue::new_long_string(&["4MR1"]).unwrap(),
+    }));
+
+    let report: String = differences
+      .iter()
+      .map(|difference| format!("{}\n", difference))
+      .collect();
+    assert!(report.contains("Removed (0010,0010) Patient's Name"));
+
+    let json: serde_json::Value =
+      serde_json::from_str(&differences_to_json(&differences)).unwrap();
+    assert!(json.as_array().unwrap().contains(&serde_json::json!({
+      "path": "00100020",
+      "description": "(0010,0020) Patient ID",
+      "change": "removed",
+      "old_value": "\"4MR1\""
+    })));
+
+    // Ignoring the removed tags, and the specific character set that is added
+    // when writing, leaves no differences
+    let mut ignored_tags: Vec<DataElementTag> = differences
+      .iter()
+      .filter_map(|difference| match difference {
+        DataSetDifference::Removed { path, .. } => {
+          path.final_data_element().ok()
+        }
+        _ => None,
+      })
+      .collect();
+    ignored_tags.push(dictionary::SPECIFIC_CHARACTER_SET.tag);
+    assert_eq!(
+      perform_diff(input_filename, anonymized_filename, &ignored_tags),
+      Ok(vec![])
+    );
+
+    std::fs::remove_file(anonymized_filename).unwrap();
+  }
+}
@@ -0,0 +1,66 @@
+use std::io::Write;
+
+use clap::Args;
+
+use dcmfx::core::*;
+use dcmfx::p10::*;
+
+pub const ABOUT: &str = "Extracts the document stored in the Encapsulated \
+  Document data element of a DICOM P10 file, e.g. an encapsulated PDF";
+
+#[derive(Args)]
+pub struct ExtractDocumentArgs {
+  #[clap(
+    help = "The name of the file to read DICOM P10 content from. Specify '-' \
+      to read from stdin."
+  )]
+  input_filename: String,
+
+  #[clap(
+    help = "The name of the file to write the encapsulated document to. \
+      Specify '-' to write to stdout."
+  )]
+  output_filename: String,
+}
+
+pub fn run(args: &ExtractDocumentArgs) -> Result<(), ()> {
+  match perform_extract_document(&args.input_filename, &args.output_filename) {
+    Ok(_) => Ok(()),
+
+    Err(e) => {
+      e.print(&format!("reading file \"{}\"", args.input_filename));
+      Err(())
+    }
+  }
+}
+
+fn perform_extract_document(
+  input_filename: &str,
+  output_filename: &str,
+) -> Result<(), P10Error> {
+  let data_set = match input_filename {
+    "-" => DataSet::read_p10_stream(&mut std::io::stdin()),
+    _ => DataSet::read_p10_file(input_filename),
+  }?;
+
+  let document =
+    data_set
+      .get_encapsulated_document()
+      .map_err(|e| P10Error::OtherError {
+        error_type: "Failed getting encapsulated document".to_string(),
+        details: e.to_string(),
+      })?;
+
+  let result = match output_filename {
+    "-" => {
+      let mut stdout = std::io::stdout();
+      stdout.write_all(document).and_then(|_| stdout.flush())
+    }
+    _ => std::fs::write(output_filename, document),
+  };
+
+  result.map_err(|e| P10Error::FileError {
+    when: format!("Writing file \"{}\"", output_filename),
+    details: e.to_string(),
+  })
+}
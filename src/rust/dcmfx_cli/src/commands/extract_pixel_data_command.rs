@@ -25,13 +25,27 @@ pub struct ExtractPixelDataArgs {
       frame number. By default, the output prefix is the input filename."
   )]
   output_prefix: Option<String>,
+
+  #[arg(
+    long,
+    default_value_t = false,
+    help = "Whether to write a JSON sidecar file alongside each output image \
+      file that contains the frame's index and imaging parameters such as \
+      rows, columns, bits allocated, photometric interpretation, and rescale \
+      slope and intercept."
+  )]
+  with_metadata: bool,
 }
 
 pub fn run(args: &ExtractPixelDataArgs) -> Result<(), ()> {
   let output_prefix =
     args.output_prefix.as_ref().unwrap_or(&args.input_filename);
 
-  match perform_extract_pixel_data(&args.input_filename, output_prefix) {
+  match perform_extract_pixel_data(
+    &args.input_filename,
+    output_prefix,
+    args.with_metadata,
+  ) {
     Ok(_) => Ok(()),
 
     Err(e) => {
@@ -44,6 +58,7 @@ pub fn run(args: &ExtractPixelDataArgs) -> Result<(), ()> {
 fn perform_extract_pixel_data(
   input_filename: &str,
   output_prefix: &str,
+  with_metadata: bool,
 ) -> Result<(), P10Error> {
   let data_set = match input_filename {
     "-" => DataSet::read_p10_stream(&mut std::io::stdin()),
@@ -54,19 +69,32 @@ fn perform_extract_pixel_data(
     .get_transfer_syntax()
     .unwrap_or(&transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN);
 
-  let (_vr, frames) =
-    data_set
-      .get_pixel_data()
-      .map_err(|e| P10Error::OtherError {
-        error_type: "Failed getting pixel data".to_string(),
-        details: format!("{:?}", e),
-      })?;
-
-  write_frame_data_files(&frames, output_prefix, transfer_syntax).map_err(|e| {
-    P10Error::FileError {
-      when: "Failed writing pixel data".to_string(),
-      details: e.to_string(),
-    }
+  let (_vr, frames, warnings) = data_set
+    .get_pixel_data_with_warnings()
+    .map_err(|e| P10Error::OtherError {
+      error_type: "Failed getting pixel data".to_string(),
+      details: format!("{:?}", e),
+    })?;
+
+  for warning in warnings {
+    eprintln!("Warning: {}", warning);
+  }
+
+  let metadata = if with_metadata {
+    Some(FrameMetadata::from_data_set(&data_set))
+  } else {
+    None
+  };
+
+  write_frame_data_files(
+    &frames,
+    output_prefix,
+    transfer_syntax,
+    metadata.as_ref(),
+  )
+  .map_err(|e| P10Error::FileError {
+    when: "Failed writing pixel data".to_string(),
+    details: e.to_string(),
   })
 }
 
@@ -74,6 +102,7 @@ fn write_frame_data_files(
   frames: &[Vec<&[u8]>],
   output_prefix: &str,
   transfer_syntax: &TransferSyntax,
+  metadata: Option<&FrameMetadata>,
 ) -> Result<(), std::io::Error> {
   for (index, frame) in frames.iter().enumerate() {
     let filename = format!(
@@ -86,14 +115,133 @@ fn write_frame_data_files(
     print!("Writing file \"{}\" ... ", filename);
     let _ = std::io::stdout().flush();
 
-    let mut stream = File::create(filename)?;
+    let mut stream = File::create(&filename)?;
     for fragment in frame {
       stream.write_all(fragment)?;
     }
     stream.flush()?;
 
     println!("done");
+
+    if let Some(metadata) = metadata {
+      let sidecar_filename = format!("{}.json", filename);
+
+      print!("Writing file \"{}\" ... ", sidecar_filename);
+      let _ = std::io::stdout().flush();
+
+      std::fs::write(&sidecar_filename, metadata.to_json(index))?;
+
+      println!("done");
+    }
   }
 
   Ok(())
 }
+
+/// The imaging parameters written to the JSON sidecar file for each frame when
+/// `--with-metadata` is specified. Values that aren't present in the data set
+/// are written as nulls.
+///
+struct FrameMetadata {
+  rows: Option<i64>,
+  columns: Option<i64>,
+  bits_allocated: Option<i64>,
+  photometric_interpretation: Option<String>,
+  rescale_slope: Option<f64>,
+  rescale_intercept: Option<f64>,
+}
+
+impl FrameMetadata {
+  fn from_data_set(data_set: &DataSet) -> Self {
+    Self {
+      rows: data_set.get_int(dictionary::ROWS.tag).ok(),
+      columns: data_set.get_int(dictionary::COLUMNS.tag).ok(),
+      bits_allocated: data_set.get_int(dictionary::BITS_ALLOCATED.tag).ok(),
+      photometric_interpretation: data_set
+        .get_string(dictionary::PHOTOMETRIC_INTERPRETATION.tag)
+        .ok()
+        .map(|s| s.to_string()),
+      rescale_slope: data_set.get_float(dictionary::RESCALE_SLOPE.tag).ok(),
+      rescale_intercept: data_set
+        .get_float(dictionary::RESCALE_INTERCEPT.tag)
+        .ok(),
+    }
+  }
+
+  fn to_json(&self, frame_index: usize) -> String {
+    // Non-finite floats can't be represented in JSON and are written as nulls
+    let json = serde_json::json!({
+      "frame_index": frame_index,
+      "rows": self.rows,
+      "columns": self.columns,
+      "bits_allocated": self.bits_allocated,
+      "photometric_interpretation": self.photometric_interpretation,
+      "rescale_slope": self.rescale_slope,
+      "rescale_intercept": self.rescale_intercept,
+    });
+
+    format!("{}\n", serde_json::to_string_pretty(&json).unwrap())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn with_metadata_test() {
+    let output_prefix = std::env::temp_dir()
+      .join(format!("dcmfx_extract_pixel_data_{}", std::process::id()));
+    let output_prefix = output_prefix.to_str().unwrap();
+
+    perform_extract_pixel_data(
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm"),
+      output_prefix,
+      true,
+    )
+    .unwrap();
+
+    let frame_filename = format!("{}.0000.bin", output_prefix);
+    let sidecar_filename = format!("{}.json", frame_filename);
+
+    let sidecar = std::fs::read_to_string(&sidecar_filename).unwrap();
+
+    std::fs::remove_file(frame_filename).unwrap();
+    std::fs::remove_file(sidecar_filename).unwrap();
+
+    assert_eq!(
+      sidecar,
+      r#"{
+  "bits_allocated": 16,
+  "columns": 64,
+  "frame_index": 0,
+  "photometric_interpretation": "MONOCHROME2",
+  "rescale_intercept": null,
+  "rescale_slope": null,
+  "rows": 64
+}
+"#
+    );
+  }
+
+  #[test]
+  fn frame_metadata_to_json_test() {
+    let metadata = FrameMetadata {
+      rows: Some(2),
+      columns: None,
+      bits_allocated: None,
+      photometric_interpretation: Some("A\"B".to_string()),
+      rescale_slope: Some(f64::NAN),
+      rescale_intercept: Some(f64::INFINITY),
+    };
+
+    let json: serde_json::Value =
+      serde_json::from_str(&metadata.to_json(1)).unwrap();
+
+    assert_eq!(json["frame_index"], 1);
+    assert_eq!(json["rows"], 2);
+    assert_eq!(json["photometric_interpretation"], "A\"B");
+    assert!(json["rescale_slope"].is_null());
+    assert!(json["rescale_intercept"].is_null());
+  }
+}
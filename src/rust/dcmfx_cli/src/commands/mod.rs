@@ -1,5 +1,8 @@
+pub mod diff_command;
+pub mod extract_document_command;
 pub mod extract_pixel_data_command;
 pub mod modify_command;
 pub mod print_command;
+pub mod to_csv_command;
 pub mod to_dcm_command;
 pub mod to_json_command;
@@ -1,5 +1,6 @@
 use std::fs::File;
 use std::io::{Read, Write};
+use std::rc::Rc;
 
 use clap::Args;
 
@@ -67,6 +68,27 @@ pub struct ModifyArgs {
     default_values_t = Vec::<DataElementTag>::new()
   )]
   delete_tags: Vec<DataElementTag>,
+
+  #[arg(
+    long,
+    help = "Inserts a data element into the root data set of the output DICOM \
+      P10 file with its value set to the binary content of a file. The value \
+      is padded to an even length if needed. This option can be specified \
+      multiple times. E.g. --set-binary 00420011=document.pdf",
+    value_parser = validate_set_binary,
+    value_name = "TAG=FILE"
+  )]
+  set_binary: Vec<(DataElementTag, String)>,
+
+  #[arg(
+    long,
+    help = "The VR to use for data elements inserted with --set-binary. Must \
+      be one of 'OB', 'OW', or 'UN'. By default, the VR is taken from the \
+      data element dictionary, or 'UN' is used if the data element is not in \
+      the dictionary.",
+    value_parser = validate_binary_vr
+  )]
+  vr: Option<ValueRepresentation>,
 }
 
 fn validate_data_element_tag_list(
@@ -80,10 +102,34 @@ fn validate_data_element_tag_list(
     .collect()
 }
 
+fn validate_set_binary(s: &str) -> Result<(DataElementTag, String), String> {
+  let (tag, filename) = s
+    .split_once('=')
+    .ok_or_else(|| "Expected a value in the format TAG=FILE".to_string())?;
+
+  let tag = DataElementTag::from_hex_string(tag)
+    .map_err(|_| format!("Invalid data element tag: {}", tag))?;
+
+  Ok((tag, filename.to_string()))
+}
+
+fn validate_binary_vr(s: &str) -> Result<ValueRepresentation, String> {
+  match ValueRepresentation::from_bytes(s.as_bytes()) {
+    Ok(
+      vr @ (ValueRepresentation::OtherByteString
+      | ValueRepresentation::OtherWordString
+      | ValueRepresentation::Unknown),
+    ) => Ok(vr),
+
+    _ => Err("VR must be one of 'OB', 'OW', or 'UN'".to_string()),
+  }
+}
+
 pub fn run(args: &ModifyArgs) -> Result<(), ()> {
   // Set the zlib compression level in the write config
   let write_config = P10WriteConfig {
     zlib_compression_level: args.zlib_compression_level,
+    ..P10WriteConfig::default()
   };
 
   let anonymize = args.anonymize;
@@ -102,17 +148,26 @@ pub fn run(args: &ModifyArgs) -> Result<(), ()> {
     None
   };
 
-  let modify_result = match parse_transfer_syntax_flag(&args.transfer_syntax) {
-    Ok(output_transfer_syntax) => streaming_rewrite(
-      &args.input_filename,
-      &args.output_filename,
-      write_config,
-      output_transfer_syntax,
-      filter_context,
-    ),
-
-    Err(e) => Err(e),
-  };
+  let modify_result = parse_transfer_syntax_flag(&args.transfer_syntax)
+    .and_then(|output_transfer_syntax| {
+      let insert_transform = if args.set_binary.is_empty() {
+        None
+      } else {
+        Some(P10InsertTransform::new(read_set_binary_data_elements(
+          &args.set_binary,
+          args.vr,
+        )?))
+      };
+
+      streaming_rewrite(
+        &args.input_filename,
+        &args.output_filename,
+        &write_config,
+        output_transfer_syntax,
+        filter_context,
+        insert_transform,
+      )
+    });
 
   match modify_result {
     Ok(_) => Ok(()),
@@ -161,15 +216,88 @@ fn parse_transfer_syntax_flag(
   }
 }
 
+/// Reads the files specified by --set-binary and returns a data set containing
+/// their content as binary values that can be inserted into the output.
+///
+fn read_set_binary_data_elements(
+  set_binary: &[(DataElementTag, String)],
+  vr: Option<ValueRepresentation>,
+) -> Result<DataSet, P10Error> {
+  let mut data_set = DataSet::new();
+
+  for (tag, filename) in set_binary {
+    let vr = match vr {
+      Some(vr) => vr,
+      None => binary_vr_from_dictionary(*tag)?,
+    };
+
+    let mut bytes =
+      std::fs::read(filename).map_err(|e| P10Error::FileError {
+        when: format!("Reading file \"{}\"", filename),
+        details: e.to_string(),
+      })?;
+
+    // Pad the value to an even length
+    if bytes.len() % 2 == 1 {
+      bytes.push(vr.padding_byte());
+    }
+
+    let value =
+      DataElementValue::new_binary(vr, Rc::new(bytes)).map_err(|e| {
+        P10Error::OtherError {
+          error_type: "Invalid --set-binary value".to_string(),
+          details: e.to_string(),
+        }
+      })?;
+
+    data_set.insert(*tag, value);
+  }
+
+  Ok(data_set)
+}
+
+/// Returns the binary VR to use for a data element being inserted with
+/// --set-binary when no VR has been explicitly specified.
+///
+fn binary_vr_from_dictionary(
+  tag: DataElementTag,
+) -> Result<ValueRepresentation, P10Error> {
+  let item = match dictionary::find(tag, None) {
+    Ok(item) => item,
+    Err(()) => return Ok(ValueRepresentation::Unknown),
+  };
+
+  item
+    .vrs
+    .iter()
+    .find(|vr| {
+      [
+        ValueRepresentation::OtherByteString,
+        ValueRepresentation::OtherWordString,
+        ValueRepresentation::Unknown,
+      ]
+      .contains(vr)
+    })
+    .copied()
+    .ok_or_else(|| P10Error::OtherError {
+      error_type: "Invalid --set-binary value".to_string(),
+      details: format!(
+        "Data element '{}' does not have a binary VR, use --vr to specify one",
+        dictionary::tag_with_name(tag, None)
+      ),
+    })
+}
+
 /// Rewrites by streaming the parts of the DICOM P10 straight to the output
 /// file.
 ///
 fn streaming_rewrite(
   input_filename: &str,
   output_filename: &str,
-  write_config: P10WriteConfig,
+  write_config: &P10WriteConfig,
   output_transfer_syntax: Option<&TransferSyntax>,
   mut filter_context: Option<P10FilterTransform>,
+  mut insert_transform: Option<P10InsertTransform>,
 ) -> Result<(), P10Error> {
   // Check that the input and output filenames don't point to the same
   // underlying file. In-place modification isn't supported because of the
@@ -218,7 +346,7 @@ fn streaming_rewrite(
     ..P10ReadConfig::default()
   });
   let mut p10_write_context = P10WriteContext::new();
-  p10_write_context.set_config(&write_config);
+  p10_write_context.set_config(write_config);
 
   // Stream P10 parts from the input stream to the output stream
   loop {
@@ -238,6 +366,14 @@ fn streaming_rewrite(
       parts
     };
 
+    // Pass parts through the insert transform if one is specified
+    if let Some(insert_transform) = insert_transform.as_mut() {
+      parts = parts
+        .iter()
+        .flat_map(|part| insert_transform.add_part(part))
+        .collect();
+    }
+
     // If converting the transfer syntax then update the transfer syntax in the
     // File Meta Information part
     if let Some(ts) = output_transfer_syntax {
@@ -307,3 +443,53 @@ fn change_transfer_syntax(
     })
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn set_binary_test() {
+    let temp_prefix = std::env::temp_dir()
+      .join(format!("dcmfx_modify_set_binary_{}", std::process::id()));
+    let temp_prefix = temp_prefix.to_str().unwrap();
+
+    let binary_filename = format!("{}.bin", temp_prefix);
+    let output_filename = format!("{}.dcm", temp_prefix);
+
+    std::fs::write(&binary_filename, [1, 2, 3]).unwrap();
+
+    let data_elements = read_set_binary_data_elements(
+      &[(
+        dictionary::ENCAPSULATED_DOCUMENT.tag,
+        binary_filename.clone(),
+      )],
+      None,
+    )
+    .unwrap();
+
+    streaming_rewrite(
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm"),
+      &output_filename,
+      &P10WriteConfig::default(),
+      None,
+      None,
+      Some(P10InsertTransform::new(data_elements)),
+    )
+    .unwrap();
+
+    let data_set = DataSet::read_p10_file(&output_filename).unwrap();
+
+    std::fs::remove_file(binary_filename).unwrap();
+    std::fs::remove_file(output_filename).unwrap();
+
+    assert_eq!(
+      data_set.get_value_bytes(
+        dictionary::ENCAPSULATED_DOCUMENT.tag,
+        ValueRepresentation::OtherByteString
+      ),
+      Ok(&Rc::new(vec![1, 2, 3, 0]))
+    );
+    assert!(data_set.has(dictionary::PIXEL_DATA.tag));
+  }
+}
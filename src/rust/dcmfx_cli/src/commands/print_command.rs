@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Write;
 
@@ -32,6 +33,27 @@ pub struct PrintArgs {
       colored output."
   )]
   styled: Option<bool>,
+
+  #[arg(
+    long,
+    help = "\
+      The maximum number of items to print for each sequence and each \
+      encapsulated pixel data value. When there are more items than this, the \
+      first items are printed followed by a line stating how many more items \
+      there are. By default all items are printed."
+  )]
+  max_sequence_items: Option<usize>,
+
+  #[arg(
+    long,
+    default_value_t = false,
+    help = "\
+      Whether to print a summary of the DICOM P10 file instead of its full \
+      content. The summary shows the number of data elements and their total \
+      size in bytes for each group, along with the number of sequences and \
+      their maximum nesting depth."
+  )]
+  summary: bool,
 }
 
 pub fn run(args: &PrintArgs) -> Result<(), ()> {
@@ -44,6 +66,7 @@ pub fn run(args: &PrintArgs) -> Result<(), ()> {
     max_part_size: 256 * 1024,
     max_string_size: u32::MAX,
     max_sequence_depth: u32::MAX,
+    ..P10ReadConfig::default()
   });
 
   // Apply any print option arguments
@@ -54,8 +77,22 @@ pub fn run(args: &PrintArgs) -> Result<(), ()> {
   if let Some(styled) = args.styled {
     print_options = print_options.styled(styled);
   }
+  print_options = print_options.max_sequence_items(args.max_sequence_items);
+
+  let result = if args.summary {
+    perform_print_summary(&args.input_filename, context).and_then(|summary| {
+      std::io::stdout()
+        .write_all(summary.as_bytes())
+        .map_err(|e| P10Error::FileError {
+          when: "Writing to stdout".to_string(),
+          details: e.to_string(),
+        })
+    })
+  } else {
+    perform_print(&args.input_filename, context, &print_options)
+  };
 
-  match perform_print(&args.input_filename, context, &print_options) {
+  match result {
     Ok(()) => Ok(()),
     Err(e) => {
       e.print(&format!("printing file \"{}\"", args.input_filename));
@@ -64,20 +101,19 @@ pub fn run(args: &PrintArgs) -> Result<(), ()> {
   }
 }
 
+fn open_input_file(input_filename: &str) -> Result<File, P10Error> {
+  File::open(input_filename).map_err(|e| P10Error::FileError {
+    when: "Opening file".to_string(),
+    details: e.to_string(),
+  })
+}
+
 fn perform_print(
   input_filename: &str,
   mut context: P10ReadContext,
   print_options: &DataSetPrintOptions,
 ) -> Result<(), P10Error> {
-  let mut file = match File::open(input_filename) {
-    Ok(file) => file,
-    Err(e) => {
-      return Err(P10Error::FileError {
-        when: "Opening file".to_string(),
-        details: e.to_string(),
-      })
-    }
-  };
+  let mut file = open_input_file(input_filename)?;
 
   let mut p10_print_transform = P10PrintTransform::new(print_options);
 
@@ -104,3 +140,131 @@ fn perform_print(
     }
   }
 }
+
+/// Reads a DICOM P10 file and returns a summary of its content. The parts are
+/// streamed so that memory usage stays low even for very large files.
+///
+fn perform_print_summary(
+  input_filename: &str,
+  mut context: P10ReadContext,
+) -> Result<String, P10Error> {
+  let mut file = open_input_file(input_filename)?;
+
+  let mut summary = PrintSummary::default();
+
+  loop {
+    let parts = dcmfx::p10::read_parts_from_stream(&mut file, &mut context)?;
+
+    for part in parts.iter() {
+      if *part == P10Part::End {
+        return Ok(summary.to_string());
+      }
+
+      summary.add_part(part);
+    }
+  }
+}
+
+/// Accumulates the statistics shown by `print --summary` from a stream of DICOM
+/// P10 parts.
+///
+#[derive(Default)]
+struct PrintSummary {
+  groups: BTreeMap<u16, (usize, u64)>,
+  sequence_count: usize,
+  max_sequence_depth: usize,
+
+  // The tag and VR of each sequence currently being read, which includes
+  // encapsulated pixel data sequences
+  sequence_stack: Vec<(DataElementTag, ValueRepresentation)>,
+}
+
+impl PrintSummary {
+  fn add_part(&mut self, part: &P10Part) {
+    match part {
+      P10Part::FileMetaInformation { data_set } => {
+        for (tag, value) in data_set.iter() {
+          self.add_data_element(*tag, value.byte_len() as u64);
+        }
+      }
+
+      P10Part::DataElementHeader { tag, length, .. } => {
+        self.add_data_element(*tag, *length as u64);
+      }
+
+      P10Part::SequenceStart { tag, vr } => {
+        self.add_data_element(*tag, 0);
+
+        if *vr == ValueRepresentation::Sequence {
+          self.sequence_count += 1;
+        }
+
+        self.sequence_stack.push((*tag, *vr));
+
+        let depth = self
+          .sequence_stack
+          .iter()
+          .filter(|(_, vr)| *vr == ValueRepresentation::Sequence)
+          .count();
+        self.max_sequence_depth = self.max_sequence_depth.max(depth);
+      }
+
+      P10Part::PixelDataItem { length } => {
+        if let Some((tag, _)) = self.sequence_stack.last() {
+          self.groups.entry(tag.group).or_default().1 += *length as u64;
+        }
+      }
+
+      P10Part::SequenceDelimiter => {
+        self.sequence_stack.pop();
+      }
+
+      _ => (),
+    }
+  }
+
+  fn add_data_element(&mut self, tag: DataElementTag, length: u64) {
+    let group = self.groups.entry(tag.group).or_default();
+    group.0 += 1;
+    group.1 += length;
+  }
+}
+
+impl std::fmt::Display for PrintSummary {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    writeln!(f, "{:<6}{:>10}{:>16}", "Group", "Elements", "Bytes")?;
+
+    for (group, (count, bytes)) in self.groups.iter() {
+      writeln!(f, "{:04X}  {:>10}{:>16}", group, count, bytes)?;
+    }
+
+    let total_count: usize = self.groups.values().map(|(count, _)| count).sum();
+    let total_bytes: u64 = self.groups.values().map(|(_, bytes)| bytes).sum();
+    writeln!(f, "{:<6}{:>10}{:>16}", "Total", total_count, total_bytes)?;
+
+    writeln!(f)?;
+    writeln!(f, "Sequences:          {}", self.sequence_count)?;
+    writeln!(f, "Max sequence depth: {}", self.max_sequence_depth)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn print_summary_test() {
+    let summary = perform_print_summary(
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm"),
+      P10ReadContext::new(),
+    )
+    .unwrap();
+
+    let lines: Vec<&str> = summary.lines().collect();
+
+    assert_eq!(lines[0], "Group   Elements           Bytes");
+    assert!(lines.contains(&"7FE0           1            8192"));
+    assert!(lines.contains(&"Total         79            8908"));
+    assert!(lines.contains(&"Sequences:          0"));
+  }
+}
@@ -0,0 +1,245 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use clap::Args;
+
+use dcmfx::core::*;
+use dcmfx::p10::*;
+
+pub const ABOUT: &str = "Reads the metadata of DICOM P10 files and writes the \
+  values of the specified data elements to a CSV file, with one row per file";
+
+#[derive(Args)]
+pub struct ToCsvArgs {
+  #[clap(
+    required = true,
+    help = "The names of the DICOM P10 files to read. Directories are searched \
+      recursively, and any files in them that aren't DICOM P10 files are \
+      ignored. Files that can't be read are reported and skipped. Glob \
+      patterns are expanded by the shell."
+  )]
+  inputs: Vec<String>,
+
+  #[arg(
+    long,
+    required = true,
+    help = "A comma-separated list of the data elements to include in the CSV \
+      file, specified by keyword or tag. The first row of the CSV file names \
+      these data elements by their keywords. E.g. \
+      --tags PatientID,StudyDate,00080060",
+    value_parser = validate_data_element_tag,
+    value_delimiter = ',',
+    value_name = "TAGS"
+  )]
+  tags: Vec<DataElementTag>,
+
+  #[arg(
+    long,
+    short,
+    help = "The name of the file to write CSV content to. Specify '-' to write \
+      to stdout.",
+    default_value = "-"
+  )]
+  output: String,
+}
+
+fn validate_data_element_tag(s: &str) -> Result<DataElementTag, String> {
+  match dictionary::find_by_keyword(s) {
+    Ok(item) => Ok(item.tag),
+    Err(()) => DataElementTag::from_hex_string(s)
+      .map_err(|_| format!("Invalid data element keyword or tag: {}", s)),
+  }
+}
+
+pub fn run(args: &ToCsvArgs) -> Result<(), ()> {
+  let input_filenames = match input_filenames(&args.inputs) {
+    Ok(input_filenames) => input_filenames,
+    Err(e) => {
+      e.print("finding input files");
+      return Err(());
+    }
+  };
+
+  let mut output_stream: Box<dyn Write> = match args.output.as_str() {
+    "-" => Box::new(BufWriter::new(std::io::stdout())),
+    _ => match File::create(&args.output) {
+      Ok(file) => Box::new(BufWriter::new(file)),
+      Err(e) => {
+        P10Error::FileError {
+          when: "Opening output file".to_string(),
+          details: e.to_string(),
+        }
+        .print(&format!("writing file \"{}\"", args.output));
+        return Err(());
+      }
+    },
+  };
+
+  match perform_to_csv(&input_filenames, &args.tags, &mut output_stream) {
+    Ok(0) => Ok(()),
+    Ok(_) => Err(()),
+    Err(e) => {
+      e.print(&format!("writing file \"{}\"", args.output));
+      Err(())
+    }
+  }
+}
+
+/// Expands the input arguments into a list of input files. Directories are
+/// searched recursively for DICOM P10 files.
+///
+fn input_filenames(inputs: &[String]) -> Result<Vec<String>, P10Error> {
+  let mut filenames = vec![];
+
+  for input in inputs {
+    let path = Path::new(input);
+
+    if path.is_dir() {
+      add_directory_files(path, &mut filenames)?;
+    } else {
+      filenames.push(input.clone());
+    }
+  }
+
+  Ok(filenames)
+}
+
+fn add_directory_files(
+  directory: &Path,
+  filenames: &mut Vec<String>,
+) -> Result<(), P10Error> {
+  let read_dir_error = |e: std::io::Error| P10Error::FileError {
+    when: format!("Reading directory \"{}\"", directory.display()),
+    details: e.to_string(),
+  };
+
+  // Sort the entries so that rows are written in a consistent order
+  let mut paths = std::fs::read_dir(directory)
+    .map_err(read_dir_error)?
+    .map(|entry| entry.map(|entry| entry.path()))
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(read_dir_error)?;
+  paths.sort();
+
+  for path in paths {
+    if path.is_dir() {
+      add_directory_files(&path, filenames)?;
+    } else {
+      let filename = path.to_string_lossy().to_string();
+      if dcmfx::p10::is_valid_file(filename.clone()) {
+        filenames.push(filename);
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Writes a CSV header row followed by one row for each input file to the
+/// output stream. Input files that can't be read have their error printed and
+/// are skipped, and the number of skipped files is returned.
+///
+fn perform_to_csv(
+  input_filenames: &[String],
+  tags: &[DataElementTag],
+  output_stream: &mut dyn Write,
+) -> Result<usize, P10Error> {
+  let write_error = |e: std::io::Error| P10Error::FileError {
+    when: "Writing CSV output".to_string(),
+    details: e.to_string(),
+  };
+
+  let mut skipped_count = 0;
+
+  output_stream
+    .write_all(csv_line(&DataSet::csv_header(tags)).as_bytes())
+    .map_err(write_error)?;
+
+  for input_filename in input_filenames {
+    let data_set = match dcmfx::p10::read_file_metadata_only(input_filename) {
+      Ok(data_set) => data_set,
+      Err(e) => {
+        e.print(&format!("reading file \"{}\"", input_filename));
+        skipped_count += 1;
+        continue;
+      }
+    };
+
+    output_stream
+      .write_all(csv_line(&data_set.to_csv_row(tags)).as_bytes())
+      .map_err(write_error)?;
+  }
+
+  output_stream.flush().map_err(write_error)?;
+
+  Ok(skipped_count)
+}
+
+/// Formats values as a line of CSV, quoting values that contain commas,
+/// quotes, or newlines.
+///
+fn csv_line(values: &[String]) -> String {
+  let fields: Vec<String> = values
+    .iter()
+    .map(|value| {
+      if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+      } else {
+        value.clone()
+      }
+    })
+    .collect();
+
+  format!("{}\n", fields.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn to_csv_test() {
+    let tags = ["PatientID", "StudyDate", "00080060"]
+      .map(|tag| validate_data_element_tag(tag).unwrap());
+
+    // Search a directory that holds a DICOM P10 file and a non-DICOM file
+    let directory = std::env::temp_dir()
+      .join(format!("dcmfx_to_csv_test_{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::copy(
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm"),
+      directory.join("example.dcm"),
+    )
+    .unwrap();
+    std::fs::write(directory.join("notes.txt"), "Not DICOM").unwrap();
+
+    let input_filenames =
+      input_filenames(&[directory.to_string_lossy().to_string()]).unwrap();
+    assert_eq!(input_filenames.len(), 1);
+
+    let mut output = vec![];
+    assert_eq!(perform_to_csv(&input_filenames, &tags, &mut output), Ok(0));
+
+    assert_eq!(
+      String::from_utf8(output).unwrap(),
+      "PatientID,StudyDate,Modality\n4MR1,20040826,MR\n"
+    );
+
+    // Files that can't be read are skipped
+    let input_filenames = [
+      directory.join("notes.txt").to_string_lossy().to_string(),
+      input_filenames[0].clone(),
+    ];
+
+    let mut output = vec![];
+    assert_eq!(perform_to_csv(&input_filenames, &tags, &mut output), Ok(1));
+
+    assert_eq!(
+      String::from_utf8(output).unwrap(),
+      "PatientID,StudyDate,Modality\n4MR1,20040826,MR\n"
+    );
+
+    std::fs::remove_dir_all(&directory).unwrap();
+  }
+}
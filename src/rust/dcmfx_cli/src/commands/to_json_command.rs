@@ -1,5 +1,5 @@
 use std::fs::File;
-use std::io::{Read, Write};
+use std::io::{BufWriter, Read, Write};
 
 use clap::Args;
 
@@ -39,12 +39,30 @@ pub struct ToJsonArgs {
     default_value_t = false
   )]
   store_encapsulated_pixel_data: bool,
+
+  #[arg(
+    long = "keywords",
+    help = "Whether to extend DICOM JSON to include the keyword of each data \
+      element, e.g. \"PatientName\"",
+    default_value_t = false
+  )]
+  include_keywords: bool,
+
+  #[arg(
+    long = "private-creators",
+    help = "Whether to extend DICOM JSON to include the private creator of \
+      each private data element",
+    default_value_t = false
+  )]
+  include_private_creators: bool,
 }
 
 pub fn run(args: &ToJsonArgs) -> Result<(), ()> {
   let config = DicomJsonConfig {
     pretty_print: args.pretty_print,
     store_encapsulated_pixel_data: args.store_encapsulated_pixel_data,
+    include_keywords: args.include_keywords,
+    include_private_creators: args.include_private_creators,
   };
 
   match perform_to_json(&args.input_filename, &args.output_filename, &config) {
@@ -75,11 +93,13 @@ fn perform_to_json(
     },
   };
 
-  // Open output stream
+  // Open output stream. JSON is written to it incrementally as each P10 part
+  // is converted, so memory use is bounded by the max part size set below
+  // rather than by the size of the JSON output.
   let mut output_stream: Box<dyn Write> = match output_filename {
-    "-" => Box::new(std::io::stdout()),
+    "-" => Box::new(BufWriter::new(std::io::stdout())),
     _ => match File::create(output_filename) {
-      Ok(file) => Box::new(file),
+      Ok(file) => Box::new(BufWriter::new(file)),
       Err(e) => {
         return Err(Box::new(P10Error::FileError {
           when: "Opening output file".to_string(),
@@ -2,10 +2,13 @@
 
 mod commands;
 
+use std::process::{ExitCode, Termination};
+
 use clap::{Parser, Subcommand};
 
 use commands::{
-  extract_pixel_data_command, modify_command, print_command, to_dcm_command,
+  diff_command, extract_document_command, extract_pixel_data_command,
+  modify_command, print_command, to_csv_command, to_dcm_command,
   to_json_command,
 };
 
@@ -31,6 +34,12 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
+  #[command(about = diff_command::ABOUT)]
+  Diff(diff_command::DiffArgs),
+
+  #[command(about = extract_document_command::ABOUT)]
+  ExtractDocument(extract_document_command::ExtractDocumentArgs),
+
   #[command(about = extract_pixel_data_command::ABOUT)]
   ExtractPixelData(extract_pixel_data_command::ExtractPixelDataArgs),
 
@@ -40,6 +49,9 @@ enum Commands {
   #[command(about = print_command::ABOUT)]
   Print(print_command::PrintArgs),
 
+  #[command(about = to_csv_command::ABOUT)]
+  ToCsv(to_csv_command::ToCsvArgs),
+
   #[command(about = to_dcm_command::ABOUT)]
   ToDcm(to_dcm_command::ToDcmArgs),
 
@@ -47,17 +59,24 @@ enum Commands {
   ToJson(to_json_command::ToJsonArgs),
 }
 
-fn main() -> Result<(), ()> {
+fn main() -> ExitCode {
   let cli = Cli::parse();
 
   let started_at = std::time::Instant::now();
 
-  let r = match &cli.command {
-    Commands::ExtractPixelData(args) => extract_pixel_data_command::run(args),
-    Commands::Modify(args) => modify_command::run(args),
-    Commands::Print(args) => print_command::run(args),
-    Commands::ToDcm(args) => to_dcm_command::run(args),
-    Commands::ToJson(args) => to_json_command::run(args),
+  let exit_code = match &cli.command {
+    Commands::Diff(args) => diff_command::run(args),
+    Commands::ExtractDocument(args) => {
+      extract_document_command::run(args).report()
+    }
+    Commands::ExtractPixelData(args) => {
+      extract_pixel_data_command::run(args).report()
+    }
+    Commands::Modify(args) => modify_command::run(args).report(),
+    Commands::Print(args) => print_command::run(args).report(),
+    Commands::ToCsv(args) => to_csv_command::run(args).report(),
+    Commands::ToDcm(args) => to_dcm_command::run(args).report(),
+    Commands::ToJson(args) => to_json_command::run(args).report(),
   };
 
   if cli.print_stats {
@@ -75,7 +94,7 @@ fn main() -> Result<(), ()> {
     eprintln!("Peak memory usage: {:.0} MiB", peak_memory_mb);
   }
 
-  r
+  exit_code
 }
 
 #[cfg(not(windows))]
@@ -78,6 +78,40 @@ enum RawDataElementValue {
   },
 }
 
+/// A typed view of the content of a data element value, as returned by
+/// [`DataElementValue::view()`]. Each variant holds the decoded content for a
+/// category of value representations.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValueView<'a> {
+  /// The values of a string VR. VRs that don't allow multiplicity always have
+  /// exactly one value.
+  Strings(Vec<&'a str>),
+
+  /// The values of an integer VR, including `IntegerString` and lookup table
+  /// descriptors.
+  Ints(Vec<i64>),
+
+  /// The values of a 64-bit integer VR.
+  BigInts(Vec<i128>),
+
+  /// The values of a floating point VR, including `DecimalString`.
+  Floats(Vec<f64>),
+
+  /// The values of an `AttributeTag` VR.
+  AttributeTags(Vec<DataElementTag>),
+
+  /// Raw bytes for VRs that hold binary data, and for values that fail to
+  /// decode.
+  Bytes(&'a [u8]),
+
+  /// The items of encapsulated pixel data.
+  EncapsulatedPixelData(&'a [Rc<Vec<u8>>]),
+
+  /// The items of a sequence.
+  Sequence(&'a [DataSet]),
+}
+
 impl DataElementValue {
   /// Formats a data element value as a human-readable single line of text.
   /// Values longer than the output width are truncated with a trailing
@@ -86,7 +120,7 @@ impl DataElementValue {
   pub fn to_string(&self, tag: DataElementTag, output_width: usize) -> String {
     // Maximum number of items needed in a comma-separated list of values before
     // reaching the output width
-    let output_list_max_size = (output_width + 2) / 3;
+    let output_list_max_size = output_width.div_ceil(3);
 
     let result = match &self.0 {
       RawDataElementValue::BinaryValue { vr, bytes } if vr.is_string() => {
@@ -323,6 +357,29 @@ impl DataElementValue {
   pub fn new_binary(
     vr: ValueRepresentation,
     bytes: Rc<Vec<u8>>,
+  ) -> Result<Self, DataError> {
+    Self::new_binary_with_string_validation(vr, bytes, true)
+  }
+
+  /// Constructs a new data element binary value similar to
+  /// [`Self::new_binary`], but does not validate the content of `bytes` when
+  /// the VR is a string type. This is intended for round-tripping raw source
+  /// bytes, e.g. ones in a legacy character set that will be decoded later.
+  ///
+  /// Functions that read string content, such as [`Self::get_string`], may
+  /// fail on values constructed this way.
+  ///
+  pub fn new_binary_lenient(
+    vr: ValueRepresentation,
+    bytes: Rc<Vec<u8>>,
+  ) -> Result<Self, DataError> {
+    Self::new_binary_with_string_validation(vr, bytes, false)
+  }
+
+  fn new_binary_with_string_validation(
+    vr: ValueRepresentation,
+    bytes: Rc<Vec<u8>>,
+    validate_strings: bool,
   ) -> Result<Self, DataError> {
     if vr == ValueRepresentation::Sequence {
       return Err(DataError::new_value_invalid(format!(
@@ -331,29 +388,31 @@ impl DataElementValue {
       )));
     }
 
-    if vr.is_encoded_string() {
-      if std::str::from_utf8(&bytes).is_err() {
-        return Err(DataError::new_value_invalid(format!(
-          "Bytes for '{}' are not valid UTF-8",
-          vr
-        )));
-      }
-    } else if vr.is_string() {
-      let invalid_byte = (*bytes).iter().find(|b| {
-        **b != 0x00
-          && **b != 0x09
-          && **b != 0x0A
-          && **b != 0x0C
-          && **b != 0x0D
-          && **b != 0x1B
-          && (**b < 0x20 || **b > 0x7E)
-      });
-
-      if let Some(invalid_byte) = invalid_byte {
-        return Err(DataError::new_value_invalid(format!(
-          "Bytes for '{}' has disallowed byte: 0x{:02X}",
-          vr, *invalid_byte
-        )));
+    if validate_strings {
+      if vr.is_encoded_string() {
+        if std::str::from_utf8(&bytes).is_err() {
+          return Err(DataError::new_value_invalid(format!(
+            "Bytes for '{}' are not valid UTF-8",
+            vr
+          )));
+        }
+      } else if vr.is_string() {
+        let invalid_byte = (*bytes).iter().find(|b| {
+          **b != 0x00
+            && **b != 0x09
+            && **b != 0x0A
+            && **b != 0x0C
+            && **b != 0x0D
+            && **b != 0x1B
+            && (**b < 0x20 || **b > 0x7E)
+        });
+
+        if let Some(invalid_byte) = invalid_byte {
+          return Err(DataError::new_value_invalid(format!(
+            "Bytes for '{}' has disallowed byte: 0x{:02X}",
+            vr, *invalid_byte
+          )));
+        }
       }
     }
 
@@ -841,6 +900,32 @@ impl DataElementValue {
     }
   }
 
+  /// Returns the length in bytes of the data held by a data element value. For
+  /// encapsulated pixel data this is the sum of the lengths of its items, and
+  /// for sequences it is the sum of the lengths of the values in all its items.
+  ///
+  /// Unlike [`Self::total_byte_size()`], no overhead for the value itself, or
+  /// for item headers in encapsulated pixel data, is included.
+  ///
+  pub fn byte_len(&self) -> usize {
+    match &self.0 {
+      RawDataElementValue::BinaryValue { bytes, .. }
+      | RawDataElementValue::LookupTableDescriptorValue { bytes, .. } => {
+        bytes.len()
+      }
+
+      RawDataElementValue::EncapsulatedPixelDataValue { items, .. } => {
+        items.iter().map(|item| item.len()).sum()
+      }
+
+      RawDataElementValue::SequenceValue { items } => items
+        .iter()
+        .flat_map(|item| item.iter())
+        .map(|(_, value)| value.byte_len())
+        .sum(),
+    }
+  }
+
   /// Returns the size in bytes of a data element value. This recurses through
   /// sequences and also includes a fixed per-value overhead, so never returns
   /// zero even for an empty data element value.
@@ -1156,7 +1241,6 @@ impl DataElementValue {
       } => decimal_string::from_bytes(bytes.as_slice()),
 
       RawDataElementValue::BinaryValue { vr, bytes }
-      | RawDataElementValue::BinaryValue { vr, bytes }
         if *vr == ValueRepresentation::FloatingPointDouble
           || *vr == ValueRepresentation::OtherDoubleString =>
       {
@@ -1296,6 +1380,88 @@ impl DataElementValue {
     }
   }
 
+  /// Returns a typed view of the content of a data element value. This saves
+  /// callers from having to try multiple `get_*()` functions when processing
+  /// values generically.
+  ///
+  /// Values that can't be decoded, e.g. due to invalid UTF-8 or a length that
+  /// isn't a multiple of the VR's data type size, are returned as
+  /// [`ValueView::Bytes`].
+  ///
+  pub fn view(&self) -> ValueView<'_> {
+    let bytes = match &self.0 {
+      RawDataElementValue::BinaryValue { bytes, .. }
+      | RawDataElementValue::LookupTableDescriptorValue { bytes, .. } => bytes,
+
+      RawDataElementValue::EncapsulatedPixelDataValue { items, .. } => {
+        return ValueView::EncapsulatedPixelData(items)
+      }
+
+      RawDataElementValue::SequenceValue { items } => {
+        return ValueView::Sequence(items)
+      }
+    };
+
+    let view = match self.value_representation() {
+      ValueRepresentation::ApplicationEntity
+      | ValueRepresentation::LongText
+      | ValueRepresentation::ShortText
+      | ValueRepresentation::UniversalResourceIdentifier
+      | ValueRepresentation::UnlimitedText => {
+        self.get_string().map(|s| ValueView::Strings(vec![s]))
+      }
+
+      ValueRepresentation::CodeString
+      | ValueRepresentation::LongString
+      | ValueRepresentation::ShortString
+      | ValueRepresentation::UniqueIdentifier
+      | ValueRepresentation::UnlimitedCharacters => {
+        self.get_strings().map(ValueView::Strings)
+      }
+
+      ValueRepresentation::AgeString
+      | ValueRepresentation::Date
+      | ValueRepresentation::DateTime
+      | ValueRepresentation::PersonName
+      | ValueRepresentation::Time => std::str::from_utf8(bytes)
+        .map(|s| {
+          ValueView::Strings(
+            s.split('\\')
+              .map(|s| s.trim_end_matches([' ', '\0']))
+              .collect(),
+          )
+        })
+        .map_err(|_| DataError::new_value_not_present()),
+
+      ValueRepresentation::IntegerString
+      | ValueRepresentation::SignedLong
+      | ValueRepresentation::SignedShort
+      | ValueRepresentation::UnsignedLong
+      | ValueRepresentation::UnsignedShort => {
+        self.get_ints().map(ValueView::Ints)
+      }
+
+      ValueRepresentation::SignedVeryLong
+      | ValueRepresentation::UnsignedVeryLong => {
+        self.get_big_ints().map(ValueView::BigInts)
+      }
+
+      ValueRepresentation::DecimalString
+      | ValueRepresentation::FloatingPointDouble
+      | ValueRepresentation::FloatingPointSingle => {
+        self.get_floats().map(ValueView::Floats)
+      }
+
+      ValueRepresentation::AttributeTag => {
+        self.get_attribute_tags().map(ValueView::AttributeTags)
+      }
+
+      _ => Err(DataError::new_value_not_present()),
+    };
+
+    view.unwrap_or(ValueView::Bytes(bytes))
+  }
+
   /// Checks that the number of bytes stored in a data element value is valid
   /// for its value representation.
   ///
@@ -1487,6 +1653,54 @@ mod tests {
     );
   }
 
+  #[test]
+  fn byte_len_test() {
+    assert_eq!(
+      DataElementValue::new_long_string(&["12"])
+        .unwrap()
+        .byte_len(),
+      2
+    );
+
+    assert_eq!(
+      DataElementValue::new_lookup_table_descriptor_unchecked(
+        ValueRepresentation::UnsignedShort,
+        Rc::new(vec![0, 1, 2, 3, 4, 5])
+      )
+      .byte_len(),
+      6
+    );
+
+    assert_eq!(
+      DataElementValue::new_encapsulated_pixel_data_unchecked(
+        ValueRepresentation::OtherByteString,
+        vec![
+          Rc::new(vec![]),
+          Rc::new(vec![1, 2]),
+          Rc::new(vec![3, 4, 5, 6])
+        ]
+      )
+      .byte_len(),
+      6
+    );
+
+    let mut item = DataSet::new();
+    item
+      .insert_string_value(&dictionary::PATIENT_ID, &["1234"])
+      .unwrap();
+    item.insert(
+      dictionary::REFERENCED_PATIENT_SEQUENCE.tag,
+      DataElementValue::new_sequence(vec![item.clone(), item.clone()]),
+    );
+
+    assert_eq!(
+      DataElementValue::new_sequence(vec![item.clone(), item]).byte_len(),
+      24
+    );
+
+    assert_eq!(DataElementValue::new_sequence(vec![]).byte_len(), 0);
+  }
+
   #[test]
   fn get_string_test() {
     assert_eq!(
@@ -1924,6 +2138,41 @@ mod tests {
       Ok(vec![1.2, 3.4])
     );
 
+    // Image Orientation (Patient) style DS value using exponential notation
+    assert_eq!(
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::DecimalString,
+        Rc::new(b"1\\-1.2246468E-16\\0\\1.2246468e-16\\1\\-0 ".to_vec())
+      )
+      .get_floats(),
+      Ok(vec![1.0, -1.2246468e-16, 0.0, 1.2246468e-16, 1.0, -0.0])
+    );
+
+    assert_eq!(
+      DataElementValue::new_decimal_string(&[2.5e-20, 1.0e30])
+        .unwrap()
+        .get_floats(),
+      Ok(vec![2.5e-20, 1.0e30])
+    );
+
+    assert_eq!(
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::DecimalString,
+        Rc::new(b"1.0\\NaN ".to_vec())
+      )
+      .get_floats(),
+      Err(DataError::new_value_invalid(
+        "DecimalString is invalid: '1.0\\NaN '".to_string()
+      ))
+    );
+
+    assert_eq!(
+      DataElementValue::new_floating_point_double(&[1.0e-300, -2.5e300])
+        .unwrap()
+        .get_floats(),
+      Ok(vec![1.0e-300, -2.5e300])
+    );
+
     assert_eq!(
       DataElementValue::new_floating_point_double(&[1.0, 2.0])
         .unwrap()
@@ -2142,6 +2391,97 @@ mod tests {
     );
   }
 
+  #[test]
+  fn view_test() {
+    assert_eq!(
+      DataElementValue::new_long_text("A ".to_string())
+        .unwrap()
+        .view(),
+      ValueView::Strings(vec!["A"])
+    );
+
+    assert_eq!(
+      DataElementValue::new_code_string(&["A", "B"])
+        .unwrap()
+        .view(),
+      ValueView::Strings(vec!["A", "B"])
+    );
+
+    assert_eq!(
+      DataElementValue::new_date(&StructuredDate {
+        year: 2024,
+        month: 1,
+        day: 2
+      })
+      .unwrap()
+      .view(),
+      ValueView::Strings(vec!["20240102"])
+    );
+
+    assert_eq!(
+      DataElementValue::new_unsigned_short(&[1, 2])
+        .unwrap()
+        .view(),
+      ValueView::Ints(vec![1, 2])
+    );
+
+    assert_eq!(
+      DataElementValue::new_integer_string(&[-3]).unwrap().view(),
+      ValueView::Ints(vec![-3])
+    );
+
+    assert_eq!(
+      DataElementValue::new_unsigned_very_long(&[4])
+        .unwrap()
+        .view(),
+      ValueView::BigInts(vec![4])
+    );
+
+    assert_eq!(
+      DataElementValue::new_floating_point_single(&[1.5])
+        .unwrap()
+        .view(),
+      ValueView::Floats(vec![1.5])
+    );
+
+    assert_eq!(
+      DataElementValue::new_attribute_tag(&[DataElementTag::new(1, 2)])
+        .unwrap()
+        .view(),
+      ValueView::AttributeTags(vec![DataElementTag::new(1, 2)])
+    );
+
+    assert_eq!(
+      DataElementValue::new_other_byte_string(vec![1, 2])
+        .unwrap()
+        .view(),
+      ValueView::Bytes(&[1, 2])
+    );
+
+    assert_eq!(
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::UnsignedLong,
+        Rc::new(vec![0, 1])
+      )
+      .view(),
+      ValueView::Bytes(&[0, 1])
+    );
+
+    assert_eq!(
+      DataElementValue::new_encapsulated_pixel_data_unchecked(
+        ValueRepresentation::OtherByteString,
+        vec![Rc::new(vec![])]
+      )
+      .view(),
+      ValueView::EncapsulatedPixelData(&[Rc::new(vec![])])
+    );
+
+    assert_eq!(
+      DataElementValue::new_sequence(vec![DataSet::new()]).view(),
+      ValueView::Sequence(&[DataSet::new()])
+    );
+  }
+
   #[test]
   fn to_string_test() {
     let tag = DataElementTag::new(0, 0);
@@ -2379,6 +2719,36 @@ mod tests {
     );
   }
 
+  #[test]
+  fn new_binary_lenient_test() {
+    let latin1_bytes = Rc::new(b"M\xFCller^Hans ".to_vec());
+
+    assert!(DataElementValue::new_binary(
+      ValueRepresentation::PersonName,
+      latin1_bytes.clone()
+    )
+    .is_err());
+
+    let value = DataElementValue::new_binary_lenient(
+      ValueRepresentation::PersonName,
+      latin1_bytes.clone(),
+    )
+    .unwrap();
+
+    assert_eq!(
+      value.value_representation(),
+      ValueRepresentation::PersonName
+    );
+    assert_eq!(value.bytes(), Ok(&latin1_bytes));
+    assert!(value.get_string().is_err());
+
+    assert!(DataElementValue::new_binary_lenient(
+      ValueRepresentation::Sequence,
+      latin1_bytes
+    )
+    .is_err());
+  }
+
   #[test]
   fn new_age_string_test() {
     assert_eq!(
@@ -43,7 +43,9 @@ static PARSE_AGE_STRING_REGEX: std::sync::LazyLock<Regex> =
   std::sync::LazyLock::new(|| Regex::new("^(\\d\\d\\d)([DWMY])$").unwrap());
 
 impl StructuredAge {
-  /// Converts an `AgeString` value into a structured age.
+  /// Converts an `AgeString` value into a structured age. The value must be
+  /// in the format `nnnU`, where `nnn` is a three digit number from 000 to 999
+  /// and `U` is one of the units 'D', 'W', 'M', or 'Y'.
   ///
   pub fn from_bytes(bytes: &[u8]) -> Result<Self, DataError> {
     let age_string = std::str::from_utf8(bytes).map_err(|_| {
@@ -67,14 +69,15 @@ impl StructuredAge {
         Ok(Self { number, unit })
       }
 
-      _ => Err(DataError::new_value_invalid(format!(
-        "AgeString is invalid: '{}'",
-        age_string
+      _ => Err(DataError::new_value_invalid(invalid_age_string_details(
+        age_string,
       ))),
     }
   }
 
-  /// Converts a structured age into an `AgeString` value.
+  /// Converts a structured age into an `AgeString` value. The returned value
+  /// is always four characters long, in the format `nnnU` with the number
+  /// zero-padded to three digits.
   ///
   pub fn to_bytes(&self) -> Result<Vec<u8>, DataError> {
     if self.number > 999 {
@@ -95,6 +98,34 @@ impl StructuredAge {
   }
 }
 
+/// Returns the details for the error returned when parsing an `AgeString`
+/// fails, describing the specific part of the value that is malformed.
+///
+fn invalid_age_string_details(age_string: &str) -> String {
+  let bytes = age_string.as_bytes();
+
+  if bytes.len() != 4 {
+    return format!(
+      "AgeString is invalid: '{}', the value must be four characters long",
+      age_string
+    );
+  }
+
+  if !bytes[0..3].iter().all(u8::is_ascii_digit) {
+    return format!(
+      "AgeString is invalid: '{}', the number must be three digits in the \
+       range 000-999",
+      age_string
+    );
+  }
+
+  format!(
+    "AgeString is invalid: '{}', the unit must be one of 'D', 'W', 'M', or \
+     'Y'",
+    age_string
+  )
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -175,7 +206,9 @@ mod tests {
     assert_eq!(
       StructuredAge::from_bytes(&[]),
       Err(DataError::new_value_invalid(
-        "AgeString is invalid: ''".to_string()
+        "AgeString is invalid: '', the value must be four characters \
+         long"
+          .to_string()
       ))
     );
 
@@ -189,7 +222,61 @@ mod tests {
     assert_eq!(
       StructuredAge::from_bytes(b"3 days"),
       Err(DataError::new_value_invalid(
-        "AgeString is invalid: '3 days'".to_string()
+        "AgeString is invalid: '3 days', the value must be four characters \
+         long"
+          .to_string()
+      ))
+    );
+
+    assert_eq!(
+      StructuredAge::from_bytes(b"000D "),
+      Ok(StructuredAge {
+        number: 0,
+        unit: AgeUnit::Days
+      })
+    );
+
+    assert_eq!(
+      StructuredAge::from_bytes(b"999Y"),
+      Ok(StructuredAge {
+        number: 999,
+        unit: AgeUnit::Years
+      })
+    );
+
+    assert_eq!(
+      StructuredAge::from_bytes(b"1000Y"),
+      Err(DataError::new_value_invalid(
+        "AgeString is invalid: '1000Y', the value must be four characters \
+         long"
+          .to_string()
+      ))
+    );
+
+    assert_eq!(
+      StructuredAge::from_bytes(b"12YY"),
+      Err(DataError::new_value_invalid(
+        "AgeString is invalid: '12YY', the number must be three digits in the \
+         range 000-999"
+          .to_string()
+      ))
+    );
+
+    assert_eq!(
+      StructuredAge::from_bytes(b"ABCD"),
+      Err(DataError::new_value_invalid(
+        "AgeString is invalid: 'ABCD', the number must be three digits in the \
+         range 000-999"
+          .to_string()
+      ))
+    );
+
+    assert_eq!(
+      StructuredAge::from_bytes(b"012y"),
+      Err(DataError::new_value_invalid(
+        "AgeString is invalid: '012y', the unit must be one of 'D', 'W', 'M', \
+         or 'Y'"
+          .to_string()
       ))
     );
   }
@@ -232,6 +319,15 @@ mod tests {
       Ok(b"101Y".to_vec())
     );
 
+    assert_eq!(
+      StructuredAge {
+        number: 0,
+        unit: AgeUnit::Years
+      }
+      .to_bytes(),
+      Ok(b"000Y".to_vec())
+    );
+
     assert_eq!(
       StructuredAge {
         number: 1000,
@@ -112,6 +112,28 @@ impl StructuredDate {
     Ok(format!("{}{}{}", year, month, day))
   }
 
+  /// Returns whether a structured date is a real calendar date, i.e. its month
+  /// is in the range 1-12 and its day exists in that month, taking leap years
+  /// into account.
+  ///
+  pub fn is_valid(&self) -> bool {
+    if self.year > 9999 || !(1..=12).contains(&self.month) {
+      return false;
+    }
+
+    let is_leap_year =
+      (self.year % 4 == 0 && self.year % 100 != 0) || self.year % 400 == 0;
+
+    let days_in_month = match self.month {
+      2 if is_leap_year => 29,
+      2 => 28,
+      4 | 6 | 9 | 11 => 30,
+      _ => 31,
+    };
+
+    (1..=days_in_month).contains(&self.day)
+  }
+
   /// Formats a structured date as an ISO 8601 date.
   ///
   pub fn to_iso8601(&self) -> String {
@@ -169,6 +191,22 @@ mod tests {
     );
   }
 
+  #[test]
+  fn is_valid_test() {
+    let date = |year, month, day| StructuredDate { year, month, day };
+
+    assert!(date(2024, 2, 29).is_valid());
+    assert!(date(2000, 2, 29).is_valid());
+    assert!(!date(2023, 2, 29).is_valid());
+    assert!(!date(1900, 2, 29).is_valid());
+    assert!(date(2023, 12, 31).is_valid());
+    assert!(!date(2023, 4, 31).is_valid());
+    assert!(!date(2023, 1, 0).is_valid());
+    assert!(!date(2023, 13, 1).is_valid());
+    assert!(!date(2023, 0, 1).is_valid());
+    assert!(!date(10000, 1, 1).is_valid());
+  }
+
   #[test]
   fn to_bytes_test() {
     assert_eq!(
@@ -2,7 +2,9 @@
 
 use crate::DataError;
 
-/// Converts a `DecimalString` value to a list of floats.
+/// Converts a `DecimalString` value to a list of floats. Values may use
+/// exponential notation, e.g. `"1.5E-3"`, but values such as `"NaN"` and
+/// `"inf"` are rejected as they aren't valid for this VR.
 ///
 pub fn from_bytes(bytes: &[u8]) -> Result<Vec<f64>, DataError> {
   let decimal_string = std::str::from_utf8(bytes).map_err(|_| {
@@ -15,8 +17,18 @@ pub fn from_bytes(bytes: &[u8]) -> Result<Vec<f64>, DataError> {
     .split('\\')
     .map(|s| s.trim())
     .filter(|s| !s.is_empty())
-    .map(|s| s.parse::<f64>())
-    .collect::<Result<Vec<f64>, _>>()
+    .map(|s| {
+      let is_valid = s
+        .bytes()
+        .all(|b| b.is_ascii_digit() || b"+-.Ee".contains(&b));
+
+      if is_valid {
+        s.parse::<f64>().map_err(|_| ())
+      } else {
+        Err(())
+      }
+    })
+    .collect::<Result<Vec<f64>, ()>>()
     .map_err(|_| {
       DataError::new_value_invalid(format!(
         "DecimalString is invalid: '{}'",
@@ -75,10 +87,42 @@ mod tests {
 
     assert_eq!(from_bytes(b"  1.2\\4.5"), Ok(vec![1.2, 4.5]));
 
+    assert_eq!(from_bytes(b"\t1.2\r\n\0"), Ok(vec![1.2]));
+
     assert_eq!(from_bytes(b"1.868344208e-10"), Ok(vec![1.868344208e-10]));
 
     assert_eq!(from_bytes(b"-0"), Ok(vec![-0.0]));
 
+    assert_eq!(from_bytes(b"1E3"), Ok(vec![1000.0]));
+
+    assert_eq!(from_bytes(b"+2.5E+02"), Ok(vec![250.0]));
+
+    assert_eq!(
+      from_bytes(b"1.0e-005\\-6.5E-1 \\.5e2"),
+      Ok(vec![1.0e-5, -0.65, 50.0])
+    );
+
+    assert_eq!(
+      from_bytes(b"NaN"),
+      Err(DataError::new_value_invalid(
+        "DecimalString is invalid: 'NaN'".to_string()
+      ))
+    );
+
+    assert_eq!(
+      from_bytes(b"1\\inf"),
+      Err(DataError::new_value_invalid(
+        "DecimalString is invalid: '1\\inf'".to_string()
+      ))
+    );
+
+    assert_eq!(
+      from_bytes(b"1E"),
+      Err(DataError::new_value_invalid(
+        "DecimalString is invalid: '1E'".to_string()
+      ))
+    );
+
     assert_eq!(
       from_bytes(&[0xD0]),
       Err(DataError::new_value_invalid(
@@ -56,6 +56,8 @@ mod tests {
 
     assert_eq!(from_bytes(b" 1\\2 "), Ok(vec![1, 2]));
 
+    assert_eq!(from_bytes(b"\t1\\2\n\0"), Ok(vec![1, 2]));
+
     assert_eq!(
       from_bytes(&[0xD0]),
       Err(DataError::new_value_invalid(
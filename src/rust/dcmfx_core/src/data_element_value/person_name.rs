@@ -4,7 +4,7 @@ use crate::DataError;
 
 /// The components of a single person name.
 ///
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct PersonNameComponents {
   pub last_name: String,
   pub first_name: String,
@@ -25,6 +25,33 @@ pub struct StructuredPersonName {
   pub phonetic: Option<PersonNameComponents>,
 }
 
+impl StructuredPersonName {
+  /// Returns this person name in a canonical form where all three component
+  /// groups are present, with absent groups having five empty components, and
+  /// trailing spaces are removed from each component. This means code that
+  /// formats person names doesn't need to handle missing components.
+  ///
+  pub fn normalized(&self) -> StructuredPersonName {
+    let normalize = |components: &Option<PersonNameComponents>| {
+      let components = components.clone().unwrap_or_default();
+
+      Some(PersonNameComponents {
+        last_name: components.last_name.trim_end_matches(' ').to_string(),
+        first_name: components.first_name.trim_end_matches(' ').to_string(),
+        middle_name: components.middle_name.trim_end_matches(' ').to_string(),
+        prefix: components.prefix.trim_end_matches(' ').to_string(),
+        suffix: components.suffix.trim_end_matches(' ').to_string(),
+      })
+    };
+
+    StructuredPersonName {
+      alphabetic: normalize(&self.alphabetic),
+      ideographic: normalize(&self.ideographic),
+      phonetic: normalize(&self.phonetic),
+    }
+  }
+}
+
 /// Converts a `PersonName` value to a list of structured person names.
 ///
 pub fn from_bytes(
@@ -257,6 +284,44 @@ mod tests {
     );
   }
 
+  #[test]
+  fn normalized_test() {
+    let person_name = from_bytes(b"Doe^John").unwrap()[0].normalized();
+
+    assert_eq!(
+      person_name,
+      StructuredPersonName {
+        alphabetic: Some(PersonNameComponents {
+          last_name: "Doe".to_string(),
+          first_name: "John".to_string(),
+          middle_name: "".to_string(),
+          prefix: "".to_string(),
+          suffix: "".to_string()
+        }),
+        ideographic: Some(PersonNameComponents::default()),
+        phonetic: Some(PersonNameComponents::default())
+      }
+    );
+
+    assert_eq!(
+      StructuredPersonName {
+        alphabetic: Some(PersonNameComponents {
+          last_name: "Doe  ".to_string(),
+          ..PersonNameComponents::default()
+        }),
+        ideographic: None,
+        phonetic: None
+      }
+      .normalized()
+      .alphabetic
+      .unwrap()
+      .last_name,
+      "Doe"
+    );
+
+    assert_eq!(to_bytes(&[person_name]), Ok(b"Doe^John".to_vec()));
+  }
+
   #[test]
   fn to_bytes_test() {
     assert_eq!(
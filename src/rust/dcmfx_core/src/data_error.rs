@@ -37,6 +37,18 @@ use crate::{dictionary, DataSetPath, ValueRepresentation};
 ///    constraint, e.g. the minimum or maximum length for the value
 ///    representation wasn't respected.
 ///
+/// 6. **Value external**.
+///
+///    When retrieving a value, it was not present in the data set because it
+///    is stored externally at the given URL. E.g. pixel data referenced by the
+///    *'(0028,7FE0) Pixel Data Provider URL'* data element.
+///
+/// 7. **Value not byte aligned**.
+///
+///    When retrieving a value, part of it wasn't aligned on a byte boundary and
+///    so couldn't be returned as a slice of bytes. E.g. frames of bit-packed
+///    pixel data with one bit allocated whose size isn't a multiple of 8 bits.
+///
 #[derive(Clone, Debug, PartialEq)]
 pub struct DataError(RawDataError);
 
@@ -61,6 +73,14 @@ enum RawDataError {
     details: String,
     path: Option<DataSetPath>,
   },
+  ValueExternal {
+    url: String,
+    path: Option<DataSetPath>,
+  },
+  ValueNotByteAligned {
+    details: String,
+    path: Option<DataSetPath>,
+  },
 }
 
 impl std::fmt::Display for DataError {
@@ -96,6 +116,20 @@ impl std::fmt::Display for DataError {
           details
         )
       }
+      RawDataError::ValueExternal { url, path } => {
+        format!(
+          "External value at {}, URL: {}",
+          optional_path_to_string(path),
+          url
+        )
+      }
+      RawDataError::ValueNotByteAligned { details, path } => {
+        format!(
+          "Value not byte aligned at {}, details: {}",
+          optional_path_to_string(path),
+          details
+        )
+      }
     };
 
     write!(f, "DICOM Data Error: {}", error)
@@ -147,6 +181,22 @@ impl DataError {
     })
   }
 
+  /// Constructs a new 'Value external' data error for a value that is stored
+  /// externally at the given URL.
+  ///
+  pub fn new_value_external(url: String) -> Self {
+    Self(RawDataError::ValueExternal { url, path: None })
+  }
+
+  /// Constructs a new 'Value not byte aligned' data error.
+  ///
+  pub fn new_value_not_byte_aligned(details: String) -> Self {
+    Self(RawDataError::ValueNotByteAligned {
+      details,
+      path: None,
+    })
+  }
+
   /// Returns the data set path for a data error.
   ///
   pub fn path(&self) -> Option<&DataSetPath> {
@@ -155,7 +205,9 @@ impl DataError {
       RawDataError::ValueNotPresent { path }
       | RawDataError::MultiplicityMismatch { path }
       | RawDataError::ValueInvalid { path, .. }
-      | RawDataError::ValueLengthInvalid { path, .. } => path.as_ref(),
+      | RawDataError::ValueLengthInvalid { path, .. }
+      | RawDataError::ValueExternal { path, .. }
+      | RawDataError::ValueNotByteAligned { path, .. } => path.as_ref(),
     }
   }
 
@@ -165,6 +217,18 @@ impl DataError {
     matches!(self.0, RawDataError::TagNotPresent { .. })
   }
 
+  /// Returns whether a data error is a 'Value external' error.
+  ///
+  pub fn is_value_external(&self) -> bool {
+    matches!(self.0, RawDataError::ValueExternal { .. })
+  }
+
+  /// Returns whether a data error is a 'Value not byte aligned' error.
+  ///
+  pub fn is_value_not_byte_aligned(&self) -> bool {
+    matches!(self.0, RawDataError::ValueNotByteAligned { .. })
+  }
+
   /// Adds a data set path to a data error. This indicates the exact location
   /// that a data error occurred in a data set, and should be included wherever
   /// possible to make troubleshooting easier.
@@ -201,6 +265,18 @@ impl DataError {
         details,
         path: Some(path.clone()),
       }),
+      RawDataError::ValueExternal { url, .. } => {
+        Self(RawDataError::ValueExternal {
+          url,
+          path: Some(path.clone()),
+        })
+      }
+      RawDataError::ValueNotByteAligned { details, .. } => {
+        Self(RawDataError::ValueNotByteAligned {
+          details,
+          path: Some(path.clone()),
+        })
+      }
     }
   }
 
@@ -213,6 +289,8 @@ impl DataError {
       RawDataError::MultiplicityMismatch { .. } => "Multiplicity mismatch",
       RawDataError::ValueInvalid { .. } => "Invalid value",
       RawDataError::ValueLengthInvalid { .. } => "Invalid value length",
+      RawDataError::ValueExternal { .. } => "External value",
+      RawDataError::ValueNotByteAligned { .. } => "Value not byte aligned",
     }
   }
 }
@@ -241,6 +319,12 @@ impl crate::DcmfxError for DataError {
       }
       | RawDataError::ValueLengthInvalid {
         path: Some(path), ..
+      }
+      | RawDataError::ValueExternal {
+        path: Some(path), ..
+      }
+      | RawDataError::ValueNotByteAligned {
+        path: Some(path), ..
       } => {
         if let Ok(tag) = path.final_data_element() {
           lines.push(format!("  Tag: {}", tag));
@@ -253,7 +337,8 @@ impl crate::DcmfxError for DataError {
     };
 
     match &self.0 {
-      RawDataError::ValueInvalid { details, .. } => {
+      RawDataError::ValueInvalid { details, .. }
+      | RawDataError::ValueNotByteAligned { details, .. } => {
         lines.push(format!("  Details: {}", details))
       }
       RawDataError::ValueLengthInvalid {
@@ -266,6 +351,9 @@ impl crate::DcmfxError for DataError {
         lines.push(format!("  Length: {} bytes", length));
         lines.push(format!("  Details: {}", details));
       }
+      RawDataError::ValueExternal { url, .. } => {
+        lines.push(format!("  URL: {}", url))
+      }
       _ => (),
     };
 
@@ -336,5 +424,25 @@ mod tests {
   Length: 5 bytes
   Details: Test 123"#
     );
+
+    assert_eq!(
+      DataError::new_value_external("https://example.com".to_string())
+        .to_lines("testing")
+        .join("\n"),
+      r#"DICOM data error testing
+
+  Error: External value
+  URL: https://example.com"#
+    );
+
+    assert_eq!(
+      DataError::new_value_not_byte_aligned("123".to_string())
+        .to_lines("testing")
+        .join("\n"),
+      r#"DICOM data error testing
+
+  Error: Value not byte aligned
+  Details: 123"#
+    );
   }
 }
@@ -10,17 +10,106 @@ use crate::data_element_value::{
   age_string, date, date_time, person_name, time,
 };
 use crate::data_set_path::DataSetPathEntry;
+use crate::transfer_syntax::VrSerialization;
 use crate::{
-  dictionary, DataElementTag, DataElementValue, DataError, DataSetPath,
+  dictionary, utils, DataElementTag, DataElementValue, DataError, DataSetPath,
   DataSetPrintOptions, TransferSyntax, ValueRepresentation,
 };
 
+/// The output width used when summarizing binary values in
+/// [`DataSet::to_flat_map()`] and [`DataSet::diff_report()`].
+///
+const SUMMARY_BINARY_VALUE_WIDTH: usize = 64;
+
 /// A DICOM data set that is a mapping of data element tags to data element
 /// values.
 ///
+/// Data elements are always stored in ascending tag order, so two data sets
+/// compare equal if they hold the same data elements regardless of the order
+/// in which those data elements were inserted. Sequence items are ordered
+/// though, so sequences compare equal only if their items are equal and in the
+/// same order.
+///
 #[derive(Clone, Debug, PartialEq)]
 pub struct DataSet(BTreeMap<DataElementTag, DataElementValue>);
 
+/// A single difference between two data sets, as returned by
+/// [`DataSet::diff()`]. The path is to the data element that differs.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataSetDifference {
+  /// A data element that is only present in the other data set.
+  Added {
+    path: DataSetPath,
+    value: DataElementValue,
+  },
+
+  /// A data element that is only present in this data set.
+  Removed {
+    path: DataSetPath,
+    value: DataElementValue,
+  },
+
+  /// A data element that is present in both data sets with different values.
+  Changed {
+    path: DataSetPath,
+    old_value: DataElementValue,
+    new_value: DataElementValue,
+  },
+}
+
+impl DataSetDifference {
+  /// Returns the path to the data element that differs.
+  ///
+  pub fn path(&self) -> &DataSetPath {
+    match self {
+      DataSetDifference::Added { path, .. }
+      | DataSetDifference::Removed { path, .. }
+      | DataSetDifference::Changed { path, .. } => path,
+    }
+  }
+}
+
+impl std::fmt::Display for DataSetDifference {
+  /// Formats a data set difference as a single line of text that includes the
+  /// data element's full path and tag names, and the values involved.
+  ///
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let path = self.path();
+    let tag = path
+      .final_data_element()
+      .unwrap_or(DataElementTag::new(0, 0));
+
+    match self {
+      DataSetDifference::Added { value, .. } => write!(
+        f,
+        "Added {}: {}",
+        path.to_detailed_string(),
+        value_summary(tag, value)
+      ),
+
+      DataSetDifference::Removed { value, .. } => write!(
+        f,
+        "Removed {}: {}",
+        path.to_detailed_string(),
+        value_summary(tag, value)
+      ),
+
+      DataSetDifference::Changed {
+        old_value,
+        new_value,
+        ..
+      } => write!(
+        f,
+        "Changed {}: {} -> {}",
+        path.to_detailed_string(),
+        value_summary(tag, old_value),
+        value_summary(tag, new_value)
+      ),
+    }
+  }
+}
+
 /// The successful result of looking up a [`DataSetPath`] in a data set.
 /// Depending on the path, the result will either be a specific data element
 /// value, or a specific sequence item in a sequence (i.e. a nested data set).
@@ -102,10 +191,15 @@ impl DataSet {
   }
 
   /// Inserts a data element tag and value into a data set. If there is already
-  /// a value for the tag then it is replaced with the new value.
+  /// a value for the tag then it is replaced with the new value, and the
+  /// previous value is returned.
   ///
-  pub fn insert(&mut self, tag: DataElementTag, value: DataElementValue) {
-    self.0.insert(tag, value);
+  pub fn insert(
+    &mut self,
+    tag: DataElementTag,
+    value: DataElementValue,
+  ) -> Option<DataElementValue> {
+    self.0.insert(tag, value)
   }
 
   /// Inserts a new binary value into a data set. If there is already a value
@@ -276,6 +370,8 @@ impl DataSet {
     item: &dictionary::Item,
     value: &[i64],
   ) -> Result<(), DataError> {
+    use ValueRepresentation as VR;
+
     fn convert_and_build<U>(
       value: &[i64],
       converter: fn(i64) -> Result<U, std::num::TryFromIntError>,
@@ -303,41 +399,61 @@ impl DataSet {
     }
 
     let value = match item.vrs {
-      [ValueRepresentation::IntegerString] => convert_and_build(
+      [VR::IntegerString] => convert_and_build(
         value,
         i32::try_from,
         DataElementValue::new_integer_string,
-        ValueRepresentation::IntegerString,
+        VR::IntegerString,
       ),
 
-      [ValueRepresentation::SignedLong] => convert_and_build(
+      [VR::SignedLong] => convert_and_build(
         value,
         i32::try_from,
         DataElementValue::new_signed_long,
-        ValueRepresentation::SignedLong,
+        VR::SignedLong,
       ),
 
-      [ValueRepresentation::SignedShort] => convert_and_build(
+      [VR::SignedShort] => convert_and_build(
         value,
         i16::try_from,
         DataElementValue::new_signed_short,
-        ValueRepresentation::SignedShort,
+        VR::SignedShort,
       ),
 
-      [ValueRepresentation::UnsignedLong] => convert_and_build(
+      [VR::UnsignedLong] => convert_and_build(
         value,
         u32::try_from,
         DataElementValue::new_unsigned_long,
-        ValueRepresentation::UnsignedLong,
+        VR::UnsignedLong,
       ),
 
-      [ValueRepresentation::UnsignedShort] => convert_and_build(
+      [VR::UnsignedShort] => convert_and_build(
         value,
         u16::try_from,
         DataElementValue::new_unsigned_short,
-        ValueRepresentation::UnsignedShort,
+        VR::UnsignedShort,
       ),
 
+      // Data elements that allow either the US or SS VR use SS only when there
+      // are negative values, as these can't be stored using the US VR
+      [VR::UnsignedShort, VR::SignedShort, ..] => {
+        if value.iter().any(|i| *i < 0) {
+          convert_and_build(
+            value,
+            i16::try_from,
+            DataElementValue::new_signed_short,
+            VR::SignedShort,
+          )
+        } else {
+          convert_and_build(
+            value,
+            u16::try_from,
+            DataElementValue::new_unsigned_short,
+            VR::UnsignedShort,
+          )
+        }
+      }
+
       _ => invalid_insert_error(item),
     }?;
 
@@ -594,6 +710,299 @@ impl DataSet {
     print::data_set_to_lines(self, print_options, &mut callback, 0);
   }
 
+  /// Converts a data set to a flat map of key-value strings, which is useful
+  /// for passing data set content to templating engines. Keys are the hex
+  /// digits of each data element's tag, with the data elements in sequence
+  /// items keyed by their full path, e.g. `"00100010"` or
+  /// `"00400275[0].00400009"`. Values are formatted using
+  /// [`DataElementValue::to_string()`].
+  ///
+  /// Sequences are recursed into, and are themselves included with a value
+  /// that gives their number of items. Binary values such as pixel data are
+  /// summarized rather than having all of their bytes included.
+  ///
+  pub fn to_flat_map(&self) -> BTreeMap<String, String> {
+    let mut flat_map = BTreeMap::new();
+
+    self.add_to_flat_map("", &mut flat_map);
+
+    flat_map
+  }
+
+  fn add_to_flat_map(
+    &self,
+    key_prefix: &str,
+    flat_map: &mut BTreeMap<String, String>,
+  ) {
+    for (tag, value) in self.iter() {
+      let key = format!("{}{}", key_prefix, tag.to_hex_string());
+
+      flat_map.insert(key.clone(), value_summary(*tag, value));
+
+      if let Ok(items) = value.sequence_items() {
+        for (index, item) in items.iter().enumerate() {
+          item.add_to_flat_map(&format!("{}[{}].", key, index), flat_map);
+        }
+      }
+    }
+  }
+
+  /// Converts the values of the specified data elements into a row of text
+  /// values for exporting to CSV, with one value per tag. String and numeric
+  /// values are output as-is, with multiple values separated by a backslash,
+  /// and other values are summarized in the same way as for
+  /// [`Self::to_flat_map()`]. Data elements that aren't present have an empty
+  /// value.
+  ///
+  /// Use [`Self::csv_header()`] to get a matching header row.
+  ///
+  pub fn to_csv_row(&self, tags: &[DataElementTag]) -> Vec<String> {
+    tags
+      .iter()
+      .map(|tag| match self.get_value(*tag) {
+        Ok(value) => csv_value(*tag, value),
+        Err(_) => "".to_string(),
+      })
+      .collect()
+  }
+
+  /// Returns the header row for CSV data produced by [`Self::to_csv_row()`].
+  /// Each column is named with its data element's keyword, e.g.
+  /// `"PatientID"`. Tags that have no keyword, such as private tags, are named
+  /// with the hex digits of their tag.
+  ///
+  pub fn csv_header(tags: &[DataElementTag]) -> Vec<String> {
+    tags
+      .iter()
+      .map(|tag| match dictionary::find(*tag, None) {
+        Ok(item) if !item.keyword.is_empty() => item.keyword.to_string(),
+        _ => tag.to_hex_string(),
+      })
+      .collect()
+  }
+
+  /// Returns the differences between this data set and another data set.
+  /// Data elements that are only present in this data set are returned as
+  /// removed, those only present in the other data set are returned as added,
+  /// and those present in both with different values are returned as changed.
+  /// Sequences present in both data sets are recursed into, and differences
+  /// inside them are returned with their full path. A sequence whose number of
+  /// items differs is also returned as changed.
+  ///
+  /// The differences are ordered by path. An empty list is returned if the
+  /// data sets are equal.
+  ///
+  pub fn diff(&self, other: &DataSet) -> Vec<DataSetDifference> {
+    let mut differences = vec![];
+
+    self.add_differences(other, &mut DataSetPath::new(), &mut differences);
+
+    differences
+  }
+
+  fn add_differences(
+    &self,
+    other: &DataSet,
+    path: &mut DataSetPath,
+    differences: &mut Vec<DataSetDifference>,
+  ) {
+    let mut tags = self.tags();
+    tags.extend(other.tags());
+    tags.sort();
+    tags.dedup();
+
+    for tag in tags {
+      path.add_data_element(tag).unwrap();
+
+      match (self.get_value(tag), other.get_value(tag)) {
+        (Ok(a), Err(_)) => differences.push(DataSetDifference::Removed {
+          path: path.clone(),
+          value: a.clone(),
+        }),
+
+        (Err(_), Ok(b)) => differences.push(DataSetDifference::Added {
+          path: path.clone(),
+          value: b.clone(),
+        }),
+
+        (Ok(a), Ok(b)) => {
+          let changed = DataSetDifference::Changed {
+            path: path.clone(),
+            old_value: a.clone(),
+            new_value: b.clone(),
+          };
+
+          if let (Ok(a_items), Ok(b_items)) =
+            (a.sequence_items(), b.sequence_items())
+          {
+            if a_items.len() != b_items.len() {
+              differences.push(changed);
+            }
+
+            for (index, (a_item, b_item)) in
+              a_items.iter().zip(b_items.iter()).enumerate()
+            {
+              path.add_sequence_item(index).unwrap();
+              a_item.add_differences(b_item, path, differences);
+              path.pop().unwrap();
+            }
+          } else if a != b {
+            differences.push(changed);
+          }
+        }
+
+        (Err(_), Err(_)) => (),
+      }
+
+      path.pop().unwrap();
+    }
+  }
+
+  /// Returns a human-readable report of the differences between this data set
+  /// and another data set as returned by [`Self::diff()`], with one line per
+  /// difference. Each line includes the full path and tag names of the data
+  /// element, and the values involved.
+  ///
+  /// An empty string is returned if the data sets are equal.
+  ///
+  pub fn diff_report(&self, other: &DataSet) -> String {
+    self
+      .diff(other)
+      .iter()
+      .map(|difference| format!("{}\n", difference))
+      .collect()
+  }
+
+  /// Returns every UID held by a `UniqueIdentifier` data element in a data
+  /// set, along with the path to the data element that holds it. Sequences are
+  /// recursed into, so UIDs that reference other instances are included. A
+  /// data element with multiple values results in one entry per value, and
+  /// empty values are not included.
+  ///
+  /// This is useful when validating the integrity of references between data
+  /// sets, and for determining the UIDs that need remapping.
+  ///
+  pub fn collect_uids(&self) -> Vec<(DataSetPath, String)> {
+    let mut uids = vec![];
+
+    self.add_uids(&mut DataSetPath::new(), &mut uids);
+
+    uids
+  }
+
+  fn add_uids(
+    &self,
+    path: &mut DataSetPath,
+    uids: &mut Vec<(DataSetPath, String)>,
+  ) {
+    for (tag, value) in self.iter() {
+      path.add_data_element(*tag).unwrap();
+
+      if value.value_representation() == ValueRepresentation::UniqueIdentifier {
+        if let Ok(strings) = value.get_strings() {
+          for uid in strings.into_iter().filter(|uid| !uid.is_empty()) {
+            uids.push((path.clone(), uid.to_string()));
+          }
+        }
+      }
+
+      if let Ok(items) = value.sequence_items() {
+        for (index, item) in items.iter().enumerate() {
+          path.add_sequence_item(index).unwrap();
+          item.add_uids(path, uids);
+          path.pop().unwrap();
+        }
+      }
+
+      path.pop().unwrap();
+    }
+  }
+
+  /// Cleans up the values of all data elements in a data set that have a
+  /// string VR, recursing into sequences. Bytes that aren't valid UTF-8 are
+  /// replaced with the U+FFFD replacement character, and control characters
+  /// are replaced with spaces. The control characters that DICOM allows in
+  /// string values, i.e. TAB, LF, FF, CR, and ESC, are left unchanged, as is
+  /// the trailing null padding of `UniqueIdentifier` values.
+  ///
+  /// This is useful as a cleanup pass on non-conformant data prior to
+  /// exporting it, e.g. to DICOM JSON.
+  ///
+  pub fn sanitize_strings(&mut self) {
+    for (_, value) in self.iter_mut() {
+      if let Ok(items) = value.sequence_items_mut() {
+        for item in items.iter_mut() {
+          item.sanitize_strings();
+        }
+
+        continue;
+      }
+
+      let vr = value.value_representation();
+      if !vr.is_string() {
+        continue;
+      }
+
+      if let Ok(bytes) = value.bytes() {
+        if let Some(bytes) = sanitize_string_bytes(vr, bytes) {
+          *value = DataElementValue::new_binary_unchecked(vr, Rc::new(bytes));
+        }
+      }
+    }
+  }
+
+  /// Truncates the values of all data elements in a data set that have a
+  /// string VR so that they fit within the length limits for their VR as
+  /// specified by [`ValueRepresentation::length_requirements()`], recursing
+  /// into sequences. The limit on the number of characters applies to each
+  /// individual value of a multi-valued string, and truncation never splits a
+  /// UTF-8 character. Truncated values are re-padded to an even length.
+  ///
+  /// String values are expected to be UTF-8, which is the case for data sets
+  /// read by `dcmfx_p10`. Values that aren't valid UTF-8 are left unchanged.
+  ///
+  /// Returns the paths of the data elements that were truncated.
+  ///
+  pub fn truncate_strings(&mut self) -> Vec<DataSetPath> {
+    let mut truncated_paths = vec![];
+
+    self.add_truncated_strings(&mut DataSetPath::new(), &mut truncated_paths);
+
+    truncated_paths
+  }
+
+  fn add_truncated_strings(
+    &mut self,
+    path: &mut DataSetPath,
+    truncated_paths: &mut Vec<DataSetPath>,
+  ) {
+    for (tag, value) in self.iter_mut() {
+      path.add_data_element(*tag).unwrap();
+
+      if let Ok(items) = value.sequence_items_mut() {
+        for (index, item) in items.iter_mut().enumerate() {
+          path.add_sequence_item(index).unwrap();
+          item.add_truncated_strings(path, truncated_paths);
+          path.pop().unwrap();
+        }
+      } else {
+        let vr = value.value_representation();
+
+        if vr.is_string() {
+          if let Ok(bytes) = value.bytes() {
+            if let Some(bytes) = truncate_string_bytes(vr, bytes) {
+              *value =
+                DataElementValue::new_binary_unchecked(vr, Rc::new(bytes));
+              truncated_paths.push(path.clone());
+            }
+          }
+        }
+      }
+
+      path.pop().unwrap();
+    }
+  }
+
   /// Looks up a data set path in a data set and returns the data element or
   /// data set that it specifies. If the path is invalid for the data set then
   /// an error is returned.
@@ -601,7 +1010,7 @@ impl DataSet {
   fn lookup(
     &self,
     path: &DataSetPath,
-  ) -> Result<DataSetLookupResult, DataError> {
+  ) -> Result<DataSetLookupResult<'_>, DataError> {
     let mut lookup_result = DataSetLookupResult::DataSet(self);
 
     for entry in path.entries().iter() {
@@ -722,6 +1131,19 @@ impl DataSet {
       .map_err(|e| e.with_path(&DataSetPath::new_with_data_element(tag)))
   }
 
+  /// Returns all of the string values for a data element in a data set joined
+  /// together with the given separator, e.g. `", "`. Unlike
+  /// [`Self::get_string()`] this doesn't error when there are multiple values,
+  /// which is useful when displaying or exporting multi-valued data elements.
+  ///
+  pub fn get_joined_string(
+    &self,
+    tag: DataElementTag,
+    separator: &str,
+  ) -> Result<String, DataError> {
+    Ok(self.get_strings(tag)?.join(separator))
+  }
+
   /// Returns the singular integer value for a data element in a data set. If
   /// the data element with the specified tag does not hold exactly one integer
   /// value then an error is returned.
@@ -817,6 +1239,30 @@ impl DataSet {
       .map_err(|e| e.with_path(&DataSetPath::new_with_data_element(tag)))
   }
 
+  /// Returns the date value for a data element in a data set in the same way
+  /// as [`Self::get_date()`], except that an error is also returned if the
+  /// date isn't a real calendar date, e.g. February 29 in a non-leap year. See
+  /// [`date::StructuredDate::is_valid()`].
+  ///
+  pub fn get_valid_date(
+    &self,
+    tag: DataElementTag,
+  ) -> Result<date::StructuredDate, DataError> {
+    let date = self.get_date(tag)?;
+
+    if date.is_valid() {
+      Ok(date)
+    } else {
+      Err(
+        DataError::new_value_invalid(format!(
+          "Date is not a valid calendar date: '{}'",
+          date.to_iso8601()
+        ))
+        .with_path(&DataSetPath::new_with_data_element(tag)),
+      )
+    }
+  }
+
   /// Returns the structured date/time value for a data element in a data set.
   /// If the data element does not hold a `DateTime` value then an error is
   /// returned.
@@ -890,6 +1336,65 @@ impl DataSet {
     })
   }
 
+  /// Returns the document stored in the *'(0042,0011) Encapsulated Document'*
+  /// data element, as used by the Encapsulated PDF and Encapsulated CDA SOP
+  /// classes.
+  ///
+  /// If the *'(0042,0015) Encapsulated Document Length'* data element is
+  /// present then it specifies the length of the document, and any padding
+  /// bytes following the document are not included in the returned bytes.
+  ///
+  pub fn get_encapsulated_document(&self) -> Result<&[u8], DataError> {
+    let bytes = self.get_value_bytes(
+      dictionary::ENCAPSULATED_DOCUMENT.tag,
+      ValueRepresentation::OtherByteString,
+    )?;
+
+    match self.get_int(dictionary::ENCAPSULATED_DOCUMENT_LENGTH.tag) {
+      Ok(length) if length >= 0 && length as usize <= bytes.len() => {
+        Ok(&bytes[..length as usize])
+      }
+
+      Ok(length) => Err(
+        DataError::new_value_invalid(format!(
+          "Encapsulated document length of {} is invalid for a document of \
+           {} bytes",
+          length,
+          bytes.len()
+        ))
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::ENCAPSULATED_DOCUMENT_LENGTH.tag,
+        )),
+      ),
+
+      Err(e) if e.is_tag_not_present() => Ok(bytes),
+
+      Err(e) => Err(e),
+    }
+  }
+
+  /// Returns the *'(0020,000D) Study Instance UID'*, *'(0020,000E) Series
+  /// Instance UID'*, and *'(0008,0018) SOP Instance UID'* values in this data
+  /// set. Together these uniquely identify a SOP instance and the series and
+  /// study it belongs to, and so are suitable for deduplicating and grouping
+  /// data sets.
+  ///
+  /// An error is returned if any of the three UIDs is not present.
+  ///
+  pub fn instance_key(&self) -> Result<(String, String, String), DataError> {
+    let study_instance_uid =
+      self.get_string(dictionary::STUDY_INSTANCE_UID.tag)?;
+    let series_instance_uid =
+      self.get_string(dictionary::SERIES_INSTANCE_UID.tag)?;
+    let sop_instance_uid = self.get_string(dictionary::SOP_INSTANCE_UID.tag)?;
+
+    Ok((
+      study_instance_uid.to_string(),
+      series_instance_uid.to_string(),
+      sop_instance_uid.to_string(),
+    ))
+  }
+
   /// Returns the size in bytes of all data elements in a data set.
   ///
   /// See [`DataElementValue::total_byte_size()`].
@@ -900,6 +1405,45 @@ impl DataSet {
       .fold(0, |acc, (_, value)| acc + value.total_byte_size())
   }
 
+  /// Computes the value of the group length data element, i.e. *'(gggg,0000)
+  /// Group Length'*, for each group of data elements in a data set. Each
+  /// group length is the number of bytes taken up by the data elements in
+  /// that group once they are serialized to DICOM P10.
+  ///
+  /// The transfer syntax specified in the File Meta Information is used to
+  /// determine whether VRs are explicit or implicit, and defaults to 'Implicit
+  /// VR Little Endian' if not specified. Sequences and items are assumed to
+  /// have undefined lengths. The File Meta Information group and any existing
+  /// group length data elements are ignored.
+  ///
+  pub fn compute_group_lengths(&self) -> Vec<(DataElementTag, u32)> {
+    let is_explicit_vr = self
+      .get_transfer_syntax()
+      .map(|ts| ts.vr_serialization == VrSerialization::VrExplicit)
+      .unwrap_or(false);
+
+    let mut group_lengths: Vec<(DataElementTag, u32)> = vec![];
+
+    for (tag, value) in self.iter() {
+      if tag.group == 2 || tag.element == 0 {
+        continue;
+      }
+
+      let size = serialized_data_element_size(value, is_explicit_vr);
+      let size = u32::try_from(size).unwrap_or(u32::MAX);
+
+      match group_lengths.last_mut() {
+        Some((group_tag, length)) if group_tag.group == tag.group => {
+          *length = length.saturating_add(size);
+        }
+
+        _ => group_lengths.push((DataElementTag::new(tag.group, 0), size)),
+      }
+    }
+
+    group_lengths
+  }
+
   /// Returns the human-readable name for a data element tag in a data set,
   /// using its data elements to determine the private creator if the tag is
   /// private.
@@ -1072,5 +1616,787 @@ fn invalid_insert_error<T>(item: &dictionary::Item) -> Result<T, DataError> {
   }
 }
 
+/// Formats a data element value as a single line of text for use in a summary
+/// of a data set. Binary values are truncated to a short summary, and all
+/// other values are formatted in full.
+///
+fn value_summary(tag: DataElementTag, value: &DataElementValue) -> String {
+  let output_width = match value.value_representation() {
+    ValueRepresentation::OtherByteString
+    | ValueRepresentation::OtherDoubleString
+    | ValueRepresentation::OtherFloatString
+    | ValueRepresentation::OtherLongString
+    | ValueRepresentation::OtherVeryLongString
+    | ValueRepresentation::OtherWordString
+    | ValueRepresentation::Unknown => SUMMARY_BINARY_VALUE_WIDTH,
+    _ => usize::MAX,
+  };
+
+  value.to_string(tag, output_width)
+}
+
+/// Formats a data element value as text for use in CSV data. Unlike
+/// [`value_summary()`], string values aren't quoted and have no descriptions
+/// added, and numeric values are formatted in full.
+///
+fn csv_value(tag: DataElementTag, value: &DataElementValue) -> String {
+  if value.value_representation().is_string() {
+    if let Ok(strings) = value.get_strings() {
+      return strings.join("\\");
+    }
+
+    if let Ok(string) = value.get_string() {
+      return string.to_string();
+    }
+
+    // String VRs that have no string accessor, such as dates and numbers
+    // stored as text, are output with their padding removed
+    if let Ok(bytes) = value.bytes() {
+      return String::from_utf8_lossy(bytes)
+        .split('\\')
+        .map(utils::trim_dicom_whitespace)
+        .collect::<Vec<_>>()
+        .join("\\");
+    }
+  }
+
+  let numbers: Vec<String> = if let Ok(ints) = value.get_ints() {
+    ints.iter().map(|i| i.to_string()).collect()
+  } else if let Ok(big_ints) = value.get_big_ints() {
+    big_ints.iter().map(|i| i.to_string()).collect()
+  } else if let Ok(floats) = value.get_floats() {
+    floats.iter().map(|f| f.to_string()).collect()
+  } else if let Ok(tags) = value.get_attribute_tags() {
+    tags.iter().map(|tag| tag.to_hex_string()).collect()
+  } else {
+    return value_summary(tag, value);
+  };
+
+  numbers.join("\\")
+}
+
+/// Returns the sanitized version of the bytes of a string value as described
+/// by [`DataSet::sanitize_strings()`], or `None` if no changes are needed.
+///
+fn sanitize_string_bytes(
+  vr: ValueRepresentation,
+  bytes: &[u8],
+) -> Option<Vec<u8>> {
+  let mut sanitized = String::from_utf8_lossy(bytes).into_owned().into_bytes();
+
+  // Trailing null padding is allowed for UIDs
+  let mut content_length = sanitized.len();
+  if vr == ValueRepresentation::UniqueIdentifier {
+    while content_length > 0 && sanitized[content_length - 1] == 0 {
+      content_length -= 1;
+    }
+  }
+
+  for byte in sanitized[0..content_length].iter_mut() {
+    let is_disallowed_control_character = (*byte < 0x20
+      && !matches!(*byte, 0x09 | 0x0A | 0x0C | 0x0D | 0x1B))
+      || *byte == 0x7F;
+
+    if is_disallowed_control_character {
+      *byte = b' ';
+    }
+  }
+
+  vr.pad_bytes_to_even_length(&mut sanitized);
+
+  if sanitized == bytes {
+    None
+  } else {
+    Some(sanitized)
+  }
+}
+
+/// Returns the truncated version of the bytes of a string value as described
+/// by [`DataSet::truncate_strings()`], or `None` if no truncation is needed.
+///
+fn truncate_string_bytes(
+  vr: ValueRepresentation,
+  bytes: &[u8],
+) -> Option<Vec<u8>> {
+  let string = std::str::from_utf8(bytes).ok()?;
+
+  let length_requirements = vr.length_requirements();
+
+  // Truncates a string to a maximum number of bytes, or characters if
+  // `is_chars` is true, without splitting a UTF-8 character
+  fn truncate(s: &str, max: usize, is_chars: bool) -> &str {
+    let end = s
+      .char_indices()
+      .enumerate()
+      .find(|(i, (byte_index, c))| {
+        if is_chars {
+          *i >= max
+        } else {
+          byte_index + c.len_utf8() > max
+        }
+      })
+      .map(|(_, (byte_index, _))| byte_index)
+      .unwrap_or(s.len());
+
+    &s[..end]
+  }
+
+  // Remove the trailing padding prior to truncating
+  let padding = vr.padding_byte() as char;
+  let unpadded = string.trim_end_matches(padding);
+
+  let mut truncated = match length_requirements.string_characters_max {
+    Some(string_characters_max) => {
+      // These VRs don't have multiple values, so a backslash isn't a delimiter
+      let is_single_valued = matches!(
+        vr,
+        ValueRepresentation::LongText
+          | ValueRepresentation::ShortText
+          | ValueRepresentation::UnlimitedText
+          | ValueRepresentation::UniversalResourceIdentifier
+      );
+
+      if is_single_valued {
+        truncate(unpadded, string_characters_max, true).to_string()
+      } else {
+        unpadded
+          .split('\\')
+          .map(|value| truncate(value, string_characters_max, true))
+          .collect::<Vec<_>>()
+          .join("\\")
+      }
+    }
+
+    None => unpadded.to_string(),
+  };
+
+  truncated =
+    truncate(&truncated, length_requirements.bytes_max, false).to_string();
+
+  if truncated.len() == unpadded.len() {
+    return None;
+  }
+
+  let mut bytes = truncated.into_bytes();
+  vr.pad_bytes_to_even_length(&mut bytes);
+
+  Some(bytes)
+}
+
+/// Returns the number of bytes used by a data element when it is serialized to
+/// DICOM P10, including its header. Sequences, sequence items, and
+/// encapsulated pixel data are sized as having undefined lengths.
+///
+fn serialized_data_element_size(
+  value: &DataElementValue,
+  is_explicit_vr: bool,
+) -> u64 {
+  let vr = value.value_representation();
+
+  let header_size = if !is_explicit_vr {
+    8
+  } else {
+    match vr {
+      ValueRepresentation::AgeString
+      | ValueRepresentation::ApplicationEntity
+      | ValueRepresentation::AttributeTag
+      | ValueRepresentation::CodeString
+      | ValueRepresentation::Date
+      | ValueRepresentation::DateTime
+      | ValueRepresentation::DecimalString
+      | ValueRepresentation::FloatingPointDouble
+      | ValueRepresentation::FloatingPointSingle
+      | ValueRepresentation::IntegerString
+      | ValueRepresentation::LongString
+      | ValueRepresentation::LongText
+      | ValueRepresentation::PersonName
+      | ValueRepresentation::ShortString
+      | ValueRepresentation::ShortText
+      | ValueRepresentation::SignedLong
+      | ValueRepresentation::SignedShort
+      | ValueRepresentation::Time
+      | ValueRepresentation::UniqueIdentifier
+      | ValueRepresentation::UnsignedLong
+      | ValueRepresentation::UnsignedShort => 8,
+
+      _ => 12,
+    }
+  };
+
+  if let Ok(items) = value.sequence_items() {
+    let items_size = items.iter().fold(0, |acc, item| {
+      acc
+        + 16
+        + item.iter().fold(0, |acc, (_, value)| {
+          acc + serialized_data_element_size(value, is_explicit_vr)
+        })
+    });
+
+    header_size + items_size + 8
+  } else if let Ok(items) = value.encapsulated_pixel_data() {
+    let items_size = items
+      .iter()
+      .fold(0, |acc, item| acc + 8 + item.len() as u64);
+
+    header_size + items_size + 8
+  } else {
+    header_size + value.bytes().map(|bytes| bytes.len()).unwrap_or(0) as u64
+  }
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+  use super::*;
+
+  #[test]
+  fn insert_test() {
+    let mut ds = DataSet::new();
+
+    let value = DataElementValue::new_long_string(&["A"]).unwrap();
+    assert_eq!(ds.insert(dictionary::PATIENT_ID.tag, value.clone()), None);
+
+    assert_eq!(
+      ds.insert(
+        dictionary::PATIENT_ID.tag,
+        DataElementValue::new_long_string(&["B"]).unwrap()
+      ),
+      Some(value)
+    );
+    assert_eq!(ds.get_string(dictionary::PATIENT_ID.tag), Ok("B"));
+  }
+
+  #[test]
+  fn insert_int_value_ambiguous_vr_test() {
+    let mut ds = DataSet::new();
+
+    ds.insert_int_value(&dictionary::SMALLEST_IMAGE_PIXEL_VALUE, &[-5])
+      .unwrap();
+    assert_eq!(
+      ds.get_value(dictionary::SMALLEST_IMAGE_PIXEL_VALUE.tag),
+      Ok(&DataElementValue::new_signed_short(&[-5]).unwrap())
+    );
+
+    ds.insert_int_value(&dictionary::SMALLEST_IMAGE_PIXEL_VALUE, &[40000])
+      .unwrap();
+    assert_eq!(
+      ds.get_value(dictionary::SMALLEST_IMAGE_PIXEL_VALUE.tag),
+      Ok(&DataElementValue::new_unsigned_short(&[40000]).unwrap())
+    );
+
+    assert!(ds
+      .insert_int_value(&dictionary::SMALLEST_IMAGE_PIXEL_VALUE, &[-40000])
+      .is_err());
+  }
+
+  #[test]
+  fn equality_test() {
+    let patient_id = DataElementValue::new_long_string(&["123"]).unwrap();
+    let study_id = DataElementValue::new_short_string(&["456"]).unwrap();
+
+    // Insertion order of data elements doesn't affect equality
+    let mut ds_a = DataSet::new();
+    ds_a.insert(dictionary::PATIENT_ID.tag, patient_id.clone());
+    ds_a.insert(dictionary::STUDY_ID.tag, study_id.clone());
+
+    let mut ds_b = DataSet::new();
+    ds_b.insert(dictionary::STUDY_ID.tag, study_id);
+    ds_b.insert(dictionary::PATIENT_ID.tag, patient_id);
+
+    assert_eq!(ds_a, ds_b);
+
+    // The order of sequence items does affect equality
+    let mut seq_a = DataSet::new();
+    seq_a
+      .insert_sequence_value(
+        &dictionary::REFERENCED_IMAGE_SEQUENCE,
+        vec![ds_a.clone(), DataSet::new()],
+      )
+      .unwrap();
+
+    let mut seq_b = DataSet::new();
+    seq_b
+      .insert_sequence_value(
+        &dictionary::REFERENCED_IMAGE_SEQUENCE,
+        vec![DataSet::new(), ds_b.clone()],
+      )
+      .unwrap();
+
+    assert_ne!(seq_a, seq_b);
+
+    seq_b
+      .insert_sequence_value(
+        &dictionary::REFERENCED_IMAGE_SEQUENCE,
+        vec![ds_b, DataSet::new()],
+      )
+      .unwrap();
+
+    assert_eq!(seq_a, seq_b);
+  }
+
+  #[test]
+  fn get_joined_string_test() {
+    let mut ds = DataSet::new();
+    ds.insert_string_value(&dictionary::IMAGE_TYPE, &["ORIGINAL", "PRIMARY"])
+      .unwrap();
+
+    assert_eq!(
+      ds.get_joined_string(dictionary::IMAGE_TYPE.tag, ", "),
+      Ok("ORIGINAL, PRIMARY".to_string())
+    );
+
+    assert_eq!(
+      ds.get_joined_string(dictionary::PATIENT_ID.tag, ", "),
+      Err(DataError::new_tag_not_present().with_path(
+        &DataSetPath::new_with_data_element(dictionary::PATIENT_ID.tag)
+      ))
+    );
+  }
+
+  #[test]
+  fn get_valid_date_test() {
+    let mut data_set = DataSet::new();
+
+    for (date, is_valid) in
+      [("20240229", true), ("20230229", false), ("20231301", false)]
+    {
+      data_set
+        .insert_binary_value(
+          dictionary::STUDY_DATE.tag,
+          ValueRepresentation::Date,
+          Rc::new(date.as_bytes().to_vec()),
+        )
+        .unwrap();
+
+      assert!(data_set.get_date(dictionary::STUDY_DATE.tag).is_ok());
+      assert_eq!(
+        data_set.get_valid_date(dictionary::STUDY_DATE.tag).is_ok(),
+        is_valid
+      );
+    }
+
+    assert_eq!(
+      data_set.get_valid_date(dictionary::STUDY_DATE.tag),
+      Err(
+        DataError::new_value_invalid(
+          "Date is not a valid calendar date: '2023-13-01'".to_string()
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::STUDY_DATE.tag
+        ))
+      )
+    );
+  }
+
+  #[test]
+  fn get_encapsulated_document_test() {
+    let mut ds = DataSet::new();
+    ds.insert_binary_value(
+      dictionary::ENCAPSULATED_DOCUMENT.tag,
+      ValueRepresentation::OtherByteString,
+      Rc::new(b"%PDF-1.4\n\0".to_vec()),
+    )
+    .unwrap();
+
+    assert_eq!(ds.get_encapsulated_document(), Ok(&b"%PDF-1.4\n\0"[..]));
+
+    ds.insert_int_value(&dictionary::ENCAPSULATED_DOCUMENT_LENGTH, &[9])
+      .unwrap();
+    assert_eq!(ds.get_encapsulated_document(), Ok(&b"%PDF-1.4\n"[..]));
+
+    ds.insert_int_value(&dictionary::ENCAPSULATED_DOCUMENT_LENGTH, &[11])
+      .unwrap();
+    assert_eq!(
+      ds.get_encapsulated_document(),
+      Err(
+        DataError::new_value_invalid(
+          "Encapsulated document length of 11 is invalid for a document of \
+           10 bytes"
+            .to_string()
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::ENCAPSULATED_DOCUMENT_LENGTH.tag
+        ))
+      )
+    );
+
+    assert_eq!(
+      DataSet::new().get_encapsulated_document(),
+      Err(DataError::new_tag_not_present().with_path(
+        &DataSetPath::new_with_data_element(
+          dictionary::ENCAPSULATED_DOCUMENT.tag
+        )
+      ))
+    );
+  }
+
+  #[test]
+  fn instance_key_test() {
+    let mut ds = DataSet::new();
+    ds.insert_string_value(&dictionary::STUDY_INSTANCE_UID, &["1.2.3"])
+      .unwrap();
+    ds.insert_string_value(&dictionary::SERIES_INSTANCE_UID, &["1.2.3.4"])
+      .unwrap();
+
+    assert_eq!(
+      ds.instance_key(),
+      Err(DataError::new_tag_not_present().with_path(
+        &DataSetPath::new_with_data_element(dictionary::SOP_INSTANCE_UID.tag)
+      ))
+    );
+
+    ds.insert_string_value(&dictionary::SOP_INSTANCE_UID, &["1.2.3.4.5"])
+      .unwrap();
+
+    assert_eq!(
+      ds.instance_key(),
+      Ok((
+        "1.2.3".to_string(),
+        "1.2.3.4".to_string(),
+        "1.2.3.4.5".to_string()
+      ))
+    );
+  }
+
+  #[test]
+  fn compute_group_lengths_test() {
+    let mut item = DataSet::new();
+    item
+      .insert_string_value(&dictionary::REFERENCED_SOP_INSTANCE_UID, &["1.2"])
+      .unwrap();
+
+    let mut ds = DataSet::new();
+    ds.insert_sequence_value(
+      &dictionary::REFERENCED_IMAGE_SEQUENCE,
+      vec![item],
+    )
+    .unwrap();
+    ds.insert_string_value(&dictionary::PATIENT_SEX, &["M"])
+      .unwrap();
+    ds.insert_string_value(&dictionary::PATIENT_ID, &["12"])
+      .unwrap();
+
+    assert_eq!(
+      ds.compute_group_lengths(),
+      vec![
+        (DataElementTag::new(0x0008, 0), 44),
+        (DataElementTag::new(0x0010, 0), 20)
+      ]
+    );
+
+    ds.insert_string_value(
+      &dictionary::TRANSFER_SYNTAX_UID,
+      &[crate::transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN.uid],
+    )
+    .unwrap();
+
+    assert_eq!(
+      ds.compute_group_lengths(),
+      vec![
+        (DataElementTag::new(0x0008, 0), 48),
+        (DataElementTag::new(0x0010, 0), 20)
+      ]
+    );
+  }
+
+  #[test]
+  fn to_flat_map_test() {
+    let mut item = DataSet::new();
+    item
+      .insert_string_value(&dictionary::SCHEDULED_PROCEDURE_STEP_ID, &["STEP1"])
+      .unwrap();
+
+    let mut ds = DataSet::new();
+    ds.insert_string_value(&dictionary::PATIENT_ID, &["123"])
+      .unwrap();
+    ds.insert_sequence_value(
+      &dictionary::REQUEST_ATTRIBUTES_SEQUENCE,
+      vec![item],
+    )
+    .unwrap();
+    ds.insert_binary_value(
+      dictionary::PIXEL_DATA.tag,
+      ValueRepresentation::OtherByteString,
+      Rc::new(vec![0; 1024]),
+    )
+    .unwrap();
+
+    let flat_map = ds.to_flat_map();
+
+    assert_eq!(flat_map.len(), 4);
+    assert_eq!(flat_map["00100020"], "\"123\"");
+    assert_eq!(flat_map["00400275"], "Items: 1");
+    assert_eq!(flat_map["00400275[0].00400009"], "\"STEP1\"");
+    assert!(flat_map["7FE00010"].starts_with("[00 00 00"));
+    assert!(flat_map["7FE00010"].chars().count() <= SUMMARY_BINARY_VALUE_WIDTH);
+  }
+
+  #[test]
+  fn to_csv_row_test() {
+    let mut ds = DataSet::new();
+    ds.insert_string_value(&dictionary::PATIENT_ID, &["123"])
+      .unwrap();
+    ds.insert_string_value(&dictionary::MODALITY, &["MR"])
+      .unwrap();
+    ds.insert_string_value(&dictionary::IMAGE_TYPE, &["ORIGINAL", "PRIMARY"])
+      .unwrap();
+    ds.insert_float_value(&dictionary::PIXEL_SPACING, &[0.5, 1.25])
+      .unwrap();
+    ds.insert_int_value(&dictionary::ROWS, &[512]).unwrap();
+
+    let tags = [
+      dictionary::PATIENT_ID.tag,
+      dictionary::STUDY_DATE.tag,
+      dictionary::MODALITY.tag,
+      dictionary::IMAGE_TYPE.tag,
+      dictionary::PIXEL_SPACING.tag,
+      dictionary::ROWS.tag,
+    ];
+
+    assert_eq!(
+      DataSet::csv_header(&tags),
+      vec![
+        "PatientID",
+        "StudyDate",
+        "Modality",
+        "ImageType",
+        "PixelSpacing",
+        "Rows"
+      ]
+    );
+    assert_eq!(
+      ds.to_csv_row(&tags),
+      vec!["123", "", "MR", "ORIGINAL\\PRIMARY", "0.5\\1.25", "512"]
+    );
+
+    assert_eq!(
+      DataSet::csv_header(&[DataElementTag::new(0x0009, 0x1001)]),
+      vec!["00091001"]
+    );
+  }
+
+  #[test]
+  fn collect_uids_test() {
+    let mut item = DataSet::new();
+    item
+      .insert_string_value(
+        &dictionary::REFERENCED_SOP_CLASS_UID,
+        &["1.2.840.10008.5.1.4.1.1.4"],
+      )
+      .unwrap();
+    item
+      .insert_string_value(&dictionary::REFERENCED_SOP_INSTANCE_UID, &["1.2.3"])
+      .unwrap();
+
+    let mut ds = DataSet::new();
+    ds.insert_string_value(&dictionary::SOP_INSTANCE_UID, &["1.2.4"])
+      .unwrap();
+    ds.insert_string_value(&dictionary::PATIENT_ID, &["1.2.5"])
+      .unwrap();
+    ds.insert_sequence_value(
+      &dictionary::REFERENCED_IMAGE_SEQUENCE,
+      vec![DataSet::new(), item],
+    )
+    .unwrap();
+
+    let item_path = |tag: DataElementTag| {
+      let mut path = DataSetPath::new_with_data_element(
+        dictionary::REFERENCED_IMAGE_SEQUENCE.tag,
+      );
+      path.add_sequence_item(1).unwrap();
+      path.add_data_element(tag).unwrap();
+      path
+    };
+
+    assert_eq!(
+      ds.collect_uids(),
+      vec![
+        (
+          DataSetPath::new_with_data_element(dictionary::SOP_INSTANCE_UID.tag),
+          "1.2.4".to_string()
+        ),
+        (
+          item_path(dictionary::REFERENCED_SOP_CLASS_UID.tag),
+          "1.2.840.10008.5.1.4.1.1.4".to_string()
+        ),
+        (
+          item_path(dictionary::REFERENCED_SOP_INSTANCE_UID.tag),
+          "1.2.3".to_string()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn sanitize_strings_test() {
+    let mut item = DataSet::new();
+    item.insert(
+      dictionary::INSTITUTION_NAME.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::LongString,
+        Rc::new(b"A\x01B\xFF".to_vec()),
+      ),
+    );
+
+    let mut ds = DataSet::new();
+    ds.insert(
+      dictionary::PATIENT_COMMENTS.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::LongText,
+        Rc::new(b"Line 1\r\nLine\x002\t\x1B\x7F".to_vec()),
+      ),
+    );
+    ds.insert_string_value(&dictionary::SOP_INSTANCE_UID, &["1.2.3"])
+      .unwrap();
+    ds.insert_sequence_value(
+      &dictionary::REFERENCED_STUDY_SEQUENCE,
+      vec![item],
+    )
+    .unwrap();
+
+    ds.sanitize_strings();
+
+    assert_eq!(
+      ds.get_value_bytes(
+        dictionary::PATIENT_COMMENTS.tag,
+        ValueRepresentation::LongText
+      ),
+      Ok(&Rc::new(b"Line 1\r\nLine 2\t\x1B  ".to_vec()))
+    );
+
+    assert_eq!(ds.get_string(dictionary::SOP_INSTANCE_UID.tag), Ok("1.2.3"));
+
+    let item = &ds
+      .get_value(dictionary::REFERENCED_STUDY_SEQUENCE.tag)
+      .unwrap()
+      .sequence_items()
+      .unwrap()[0];
+    assert_eq!(
+      item.get_string(dictionary::INSTITUTION_NAME.tag),
+      Ok("A B\u{FFFD}")
+    );
+  }
+
+  #[test]
+  fn diff_report_test() {
+    let person_name = |name: &str| {
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::PersonName,
+        Rc::new(name.as_bytes().to_vec()),
+      )
+    };
+
+    let mut item = DataSet::new();
+    item
+      .insert_string_value(&dictionary::SCHEDULED_PROCEDURE_STEP_ID, &["STEP1"])
+      .unwrap();
+
+    let mut a = DataSet::new();
+    a.insert(dictionary::PATIENT_NAME.tag, person_name("Doe^John"));
+    a.insert_string_value(&dictionary::PATIENT_ID, &["123"])
+      .unwrap();
+    a.insert_sequence_value(
+      &dictionary::REQUEST_ATTRIBUTES_SEQUENCE,
+      vec![item.clone()],
+    )
+    .unwrap();
+
+    assert_eq!(a.diff_report(&a), "");
+
+    let mut b = a.clone();
+    b.insert(dictionary::PATIENT_NAME.tag, person_name("Doe^Jane"));
+    b.delete(dictionary::PATIENT_ID.tag);
+    b.insert_string_value(&dictionary::PATIENT_SEX, &["F"])
+      .unwrap();
+    item
+      .insert_string_value(&dictionary::SCHEDULED_PROCEDURE_STEP_ID, &["STEP2"])
+      .unwrap();
+    b.insert_sequence_value(
+      &dictionary::REQUEST_ATTRIBUTES_SEQUENCE,
+      vec![item],
+    )
+    .unwrap();
+
+    assert_eq!(
+      a.diff_report(&b),
+      "Changed (0010,0010) Patient's Name: \"Doe^John\" -> \"Doe^Jane\"\n\
+       Removed (0010,0020) Patient ID: \"123\"\n\
+       Added (0010,0040) Patient's Sex: \"F\" (Female)\n\
+       Changed (0040,0275) Request Attributes Sequence / Item 0 / (0040,0009) \
+       Scheduled Procedure Step ID: \"STEP1\" -> \"STEP2\"\n"
+    );
+  }
+
+  #[test]
+  fn truncate_strings_test() {
+    let mut ds = DataSet::new();
+    ds.insert(
+      dictionary::STATION_NAME.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::ShortString,
+        Rc::new(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ".to_vec()),
+      ),
+    );
+    ds.insert(
+      dictionary::IMAGE_TYPE.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::CodeString,
+        Rc::new(b"ORIGINAL\\PRIMARY_WITH_A_LONG_VALUE".to_vec()),
+      ),
+    );
+    ds.insert_string_value(&dictionary::PATIENT_ID, &["123"])
+      .unwrap();
+
+    let mut item = DataSet::new();
+    item.insert(
+      dictionary::INSTITUTION_NAME.tag,
+      DataElementValue::new_binary_unchecked(
+        ValueRepresentation::LongString,
+        Rc::new("\u{e9}".repeat(65).into_bytes()),
+      ),
+    );
+    ds.insert_sequence_value(
+      &dictionary::REFERENCED_STUDY_SEQUENCE,
+      vec![item],
+    )
+    .unwrap();
+
+    let mut item_path = DataSetPath::new_with_data_element(
+      dictionary::REFERENCED_STUDY_SEQUENCE.tag,
+    );
+    item_path.add_sequence_item(0).unwrap();
+    item_path
+      .add_data_element(dictionary::INSTITUTION_NAME.tag)
+      .unwrap();
+
+    assert_eq!(
+      ds.truncate_strings(),
+      vec![
+        DataSetPath::new_with_data_element(dictionary::IMAGE_TYPE.tag),
+        DataSetPath::new_with_data_element(dictionary::STATION_NAME.tag),
+        item_path,
+      ]
+    );
+
+    assert_eq!(
+      ds.get_string(dictionary::STATION_NAME.tag),
+      Ok("ABCDEFGHIJKLMNOP")
+    );
+    assert_eq!(
+      ds.get_strings(dictionary::IMAGE_TYPE.tag),
+      Ok(vec!["ORIGINAL", "PRIMARY_WITH_A_L"])
+    );
+    assert_eq!(ds.get_string(dictionary::PATIENT_ID.tag), Ok("123"));
+
+    let item = &ds
+      .get_value(dictionary::REFERENCED_STUDY_SEQUENCE.tag)
+      .unwrap()
+      .sequence_items()
+      .unwrap()[0];
+    assert_eq!(
+      item.get_string(dictionary::INSTITUTION_NAME.tag),
+      Ok("\u{e9}".repeat(64).as_str())
+    );
+
+    assert_eq!(ds.truncate_strings(), vec![]);
+  }
+}
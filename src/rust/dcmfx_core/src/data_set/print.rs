@@ -19,6 +19,14 @@ pub struct DataSetPrintOptions {
   /// By default this is set based on automatically detecting the stdout
   /// terminal's width.
   pub max_width: usize,
+
+  /// The maximum number of items to print for each sequence and each
+  /// encapsulated pixel data value. When there are more items than this, the
+  /// first items are printed followed by a line stating how many more items
+  /// there are.
+  ///
+  /// By default all items are printed.
+  pub max_sequence_items: Option<usize>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -48,6 +56,7 @@ impl DataSetPrintOptions {
     Self {
       styled: is_terminal && color_support,
       max_width: terminal_width().unwrap_or(80),
+      max_sequence_items: None,
     }
   }
 
@@ -62,6 +71,15 @@ impl DataSetPrintOptions {
   pub fn max_width(self, max_width: usize) -> Self {
     Self { max_width, ..self }
   }
+
+  /// Sets the [`DataSetPrintOptions::max_sequence_items`] value.
+  ///
+  pub fn max_sequence_items(self, max_sequence_items: Option<usize>) -> Self {
+    Self {
+      max_sequence_items,
+      ..self
+    }
+  }
 }
 
 impl Default for DataSetPrintOptions {
@@ -92,7 +110,7 @@ pub fn data_set_to_lines(
     if let Ok(items) = value.sequence_items() {
       callback(header);
 
-      for item in items.iter() {
+      for item in items.iter().take(item_limit(items.len(), print_options)) {
         callback(
           format_data_element_prefix(
             dictionary::ITEM.tag,
@@ -120,6 +138,8 @@ pub fn data_set_to_lines(
         );
       }
 
+      items_omitted_line(items.len(), indent + 1, print_options, callback);
+
       callback(
         format_data_element_prefix(
           dictionary::SEQUENCE_DELIMITATION_ITEM.tag,
@@ -134,7 +154,7 @@ pub fn data_set_to_lines(
     } else if let Ok(items) = value.encapsulated_pixel_data() {
       callback(header.to_string());
 
-      for item in items {
+      for item in items.iter().take(item_limit(items.len(), print_options)) {
         callback(
           format_data_element_prefix(
             dictionary::ITEM.tag,
@@ -148,6 +168,8 @@ pub fn data_set_to_lines(
         );
       }
 
+      items_omitted_line(items.len(), indent + 1, print_options, callback);
+
       callback(
         format_data_element_prefix(
           dictionary::SEQUENCE_DELIMITATION_ITEM.tag,
@@ -171,6 +193,40 @@ pub fn data_set_to_lines(
   }
 }
 
+/// Returns the number of items to print out of the given number of items.
+///
+pub fn item_limit(
+  item_count: usize,
+  print_options: &DataSetPrintOptions,
+) -> usize {
+  match print_options.max_sequence_items {
+    Some(max_sequence_items) => std::cmp::min(item_count, max_sequence_items),
+    None => item_count,
+  }
+}
+
+/// Emits a line stating how many items weren't printed because of the
+/// [`DataSetPrintOptions::max_sequence_items`] limit. Nothing is emitted if
+/// all items were printed.
+///
+pub fn items_omitted_line(
+  item_count: usize,
+  indent: usize,
+  print_options: &DataSetPrintOptions,
+  callback: &mut impl FnMut(String),
+) {
+  let omitted_count = item_count - item_limit(item_count, print_options);
+  if omitted_count == 0 {
+    return;
+  }
+
+  let empty = "";
+  callback(format!(
+    "{empty:indent$}... and {omitted_count} more",
+    indent = indent * 2
+  ));
+}
+
 /// Formats details for a data element for display on stdout, excluding its
 /// value. Returns the string to display along with the number of printable
 /// characters.
@@ -281,3 +337,42 @@ fn text_reset_to_bold(s: &str) -> String {
 fn text_green(s: &str) -> String {
   format!("\u{001b}[32m{}", s)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn max_sequence_items_test() {
+    let items = (0..10)
+      .map(|i| {
+        let mut item = DataSet::new();
+        item.insert_int_value(&dictionary::ROWS, &[i]).unwrap();
+        item
+      })
+      .collect();
+
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_sequence_value(&dictionary::REFERENCED_IMAGE_SEQUENCE, items)
+      .unwrap();
+
+    let print_options = DataSetPrintOptions::new()
+      .styled(false)
+      .max_width(80)
+      .max_sequence_items(Some(3));
+
+    let mut lines = vec![];
+    data_set_to_lines(
+      &data_set,
+      &print_options,
+      &mut |line| lines.push(line),
+      0,
+    );
+
+    assert_eq!(lines.len(), 12);
+    assert_eq!(lines.iter().filter(|line| line.contains("Rows")).count(), 3);
+    assert_eq!(lines[10], "  ... and 7 more");
+    assert!(lines[11].contains("Sequence Delimitation Item"));
+  }
+}
@@ -127,6 +127,17 @@ impl DataSetPath {
     }
   }
 
+  /// Returns an iterator over a data set path and each of its ancestors. The
+  /// first path yielded is the full path, and each subsequent path has one
+  /// fewer entry, with the final path yielded being the path to the root data
+  /// set.
+  ///
+  pub fn ancestors(&self) -> impl Iterator<Item = DataSetPath> + '_ {
+    (0..=self.0.len())
+      .rev()
+      .map(|len| DataSetPath(self.0[0..len].to_vec()))
+  }
+
   /// Parses a data set path from a string.
   ///
   pub fn from_string(s: &str) -> Result<Self, String> {
@@ -229,6 +240,29 @@ mod tests {
     assert_eq!(&path.to_string(), "12345678/[2]/11223344");
   }
 
+  #[test]
+  fn ancestors_test() {
+    let path =
+      DataSetPath::from_string("12345678/[2]/11223344/[0]/55667788").unwrap();
+
+    assert_eq!(
+      path.ancestors().map(|p| p.to_string()).collect::<Vec<_>>(),
+      vec![
+        "12345678/[2]/11223344/[0]/55667788",
+        "12345678/[2]/11223344/[0]",
+        "12345678/[2]/11223344",
+        "12345678/[2]",
+        "12345678",
+        "",
+      ]
+    );
+
+    assert_eq!(
+      DataSetPath::new().ancestors().collect::<Vec<_>>(),
+      vec![DataSetPath::new()]
+    );
+  }
+
   #[test]
   fn from_string_test() {
     let mut path = DataSetPath::new();
@@ -10,6 +10,7 @@ pub mod data_set;
 pub mod data_set_path;
 pub mod dictionary;
 pub mod error;
+pub mod lookup_table;
 pub mod transfer_syntax;
 pub mod utils;
 pub mod value_multiplicity;
@@ -23,12 +24,12 @@ pub use data_element_value::person_name::{
   PersonNameComponents, StructuredPersonName,
 };
 pub use data_element_value::time::StructuredTime;
-pub use data_element_value::DataElementValue;
+pub use data_element_value::{DataElementValue, ValueView};
 pub use data_error::DataError;
 pub use data_set::print::DataSetPrintOptions;
-pub use data_set::DataSet;
+pub use data_set::{DataSet, DataSetDifference};
 pub use data_set_path::DataSetPath;
 pub use error::DcmfxError;
 pub use transfer_syntax::TransferSyntax;
 pub use value_multiplicity::ValueMultiplicity;
-pub use value_representation::ValueRepresentation;
+pub use value_representation::{LengthRequirements, ValueRepresentation};
@@ -0,0 +1,163 @@
+//! Interprets the data of lookup tables, such as Modality LUTs, VOI LUTs, and
+//! Palette Color LUTs, using their lookup table descriptor.
+//!
+//! Ref: PS3.3 C.11.1.1, PS3.3 C.11.2.1.
+
+use byteorder::ByteOrder;
+
+use crate::{DataElementValue, DataError};
+
+/// Reads the entries of a lookup table from its *'LUT Data'* value, using its
+/// *'LUT Descriptor'* value to determine how the data is interpreted.
+///
+/// The descriptor holds three values: the number of entries in the lookup
+/// table, where zero means 2^16 entries, the first stored pixel value that is
+/// mapped, and the number of bits in each entry. The first stored pixel value
+/// doesn't affect how the data is interpreted, so callers that need it should
+/// read it from the descriptor directly.
+///
+/// Entries are stored as 16-bit words. When there are 8 bits per entry the
+/// entries may instead be packed as individual bytes, and this is detected
+/// based on the length of the data. Any high bits of 8-bit entries stored as
+/// 16-bit words are discarded.
+///
+pub fn parse_lut(
+  descriptor: &DataElementValue,
+  data: &DataElementValue,
+) -> Result<Vec<u16>, DataError> {
+  let (entry_count, bits_per_entry) = match descriptor.get_ints()?.as_slice() {
+    [entry_count, _first_input_value, bits_per_entry] => {
+      let entry_count = match *entry_count {
+        0 => 65536,
+        n => n as usize,
+      };
+
+      (entry_count, *bits_per_entry)
+    }
+
+    _ => {
+      return Err(DataError::new_value_invalid(
+        "Lookup table descriptor does not have three values".to_string(),
+      ))
+    }
+  };
+
+  if !(1..=16).contains(&bits_per_entry) {
+    return Err(DataError::new_value_invalid(format!(
+      "Lookup table descriptor bits per entry of {} is not in the range 1-16",
+      bits_per_entry
+    )));
+  }
+
+  let bytes = data.bytes()?;
+
+  // 8-bit entries are usually stored one per 16-bit word, but some data sets
+  // store them packed as individual bytes, followed by a padding byte if the
+  // number of entries is odd
+  if bits_per_entry <= 8 && bytes.len() == entry_count + entry_count % 2 {
+    return Ok(bytes[0..entry_count].iter().map(|b| *b as u16).collect());
+  }
+
+  if bytes.len() != entry_count * 2 {
+    return Err(DataError::new_value_invalid(format!(
+      "Lookup table data has {} bytes but its descriptor specifies {} entries",
+      bytes.len(),
+      entry_count
+    )));
+  }
+
+  let mut entries = vec![0u16; entry_count];
+  byteorder::LittleEndian::read_u16_into(bytes, &mut entries);
+
+  if bits_per_entry <= 8 {
+    for entry in entries.iter_mut() {
+      *entry &= 0xFF;
+    }
+  }
+
+  Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::rc::Rc;
+
+  use super::*;
+  use crate::ValueRepresentation;
+
+  fn descriptor(entry_count: u16, first: u16, bits: u16) -> DataElementValue {
+    DataElementValue::new_lookup_table_descriptor(
+      ValueRepresentation::UnsignedShort,
+      Rc::new(
+        [
+          entry_count.to_le_bytes(),
+          first.to_le_bytes(),
+          bits.to_le_bytes(),
+        ]
+        .concat(),
+      ),
+    )
+    .unwrap()
+  }
+
+  fn lut_data(bytes: Vec<u8>) -> DataElementValue {
+    DataElementValue::new_binary(
+      ValueRepresentation::OtherWordString,
+      Rc::new(bytes),
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn parse_lut_test() {
+    // A small 12-bit VOI LUT
+    assert_eq!(
+      parse_lut(
+        &descriptor(4, 100, 12),
+        &lut_data(vec![0x00, 0x00, 0x00, 0x04, 0x00, 0x08, 0xFF, 0x0F])
+      ),
+      Ok(vec![0, 0x400, 0x800, 0xFFF])
+    );
+
+    // 8-bit entries stored one per word, with their high bits discarded
+    assert_eq!(
+      parse_lut(&descriptor(2, 0, 8), &lut_data(vec![10, 0, 20, 1])),
+      Ok(vec![10, 20])
+    );
+
+    // 8-bit entries packed as bytes
+    assert_eq!(
+      parse_lut(&descriptor(4, 0, 8), &lut_data(vec![10, 20, 30, 40])),
+      Ok(vec![10, 20, 30, 40])
+    );
+
+    // 8-bit entries packed as bytes with a trailing padding byte
+    assert_eq!(
+      parse_lut(&descriptor(3, 0, 8), &lut_data(vec![10, 20, 30, 0])),
+      Ok(vec![10, 20, 30])
+    );
+
+    // An entry count of zero means 2^16 entries
+    assert_eq!(
+      parse_lut(&descriptor(0, 0, 16), &lut_data(vec![0; 131072]))
+        .map(|entries| entries.len()),
+      Ok(65536)
+    );
+
+    assert_eq!(
+      parse_lut(&descriptor(4, 0, 16), &lut_data(vec![0; 6])),
+      Err(DataError::new_value_invalid(
+        "Lookup table data has 6 bytes but its descriptor specifies 4 entries"
+          .to_string()
+      ))
+    );
+
+    assert_eq!(
+      parse_lut(&descriptor(1, 0, 17), &lut_data(vec![0; 2])),
+      Err(DataError::new_value_invalid(
+        "Lookup table descriptor bits per entry of 17 is not in the range 1-16"
+          .to_string()
+      ))
+    );
+  }
+}
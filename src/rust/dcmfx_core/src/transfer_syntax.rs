@@ -1,5 +1,7 @@
 //! Defines all supported DICOM transfer syntaxes.
 
+use crate::ValueRepresentation;
+
 /// The value representation serialization mode of a transfer syntax. This is
 /// either implicit or explicit.
 ///
@@ -661,6 +663,37 @@ impl TransferSyntax {
       _ => Err(()),
     }
   }
+
+  /// Returns whether a transfer syntax uses little endian byte ordering.
+  ///
+  pub fn is_little_endian(&self) -> bool {
+    self.endianness == Endianness::LittleEndian
+  }
+
+  /// Returns whether a transfer syntax serializes value representations
+  /// explicitly.
+  ///
+  pub fn is_explicit_vr(&self) -> bool {
+    self.vr_serialization == VrSerialization::VrExplicit
+  }
+}
+
+/// Returns the VR that *'(7FE0,0010) Pixel Data'* should use when it is stored
+/// in the given transfer syntax. Encapsulated pixel data always uses
+/// [`ValueRepresentation::OtherByteString`]. Native pixel data uses
+/// [`ValueRepresentation::OtherWordString`] when the *'(0028,0100) Bits
+/// Allocated'* value is greater than 8, and
+/// [`ValueRepresentation::OtherByteString`] otherwise.
+///
+pub fn expected_pixel_data_vr(
+  ts: &TransferSyntax,
+  bits_allocated: u16,
+) -> ValueRepresentation {
+  if ts.is_encapsulated || bits_allocated <= 8 {
+    ValueRepresentation::OtherByteString
+  } else {
+    ValueRepresentation::OtherWordString
+  }
 }
 
 #[cfg(test)]
@@ -725,4 +758,34 @@ mod tests {
 
     assert!(TransferSyntax::from_uid("1.2.3.4").is_err());
   }
+
+  #[test]
+  pub fn is_little_endian_test() {
+    assert!(IMPLICIT_VR_LITTLE_ENDIAN.is_little_endian());
+    assert!(!EXPLICIT_VR_BIG_ENDIAN.is_little_endian());
+  }
+
+  #[test]
+  pub fn is_explicit_vr_test() {
+    assert!(!IMPLICIT_VR_LITTLE_ENDIAN.is_explicit_vr());
+    assert!(EXPLICIT_VR_BIG_ENDIAN.is_explicit_vr());
+  }
+
+  #[test]
+  pub fn expected_pixel_data_vr_test() {
+    assert_eq!(
+      expected_pixel_data_vr(&EXPLICIT_VR_LITTLE_ENDIAN, 8),
+      ValueRepresentation::OtherByteString
+    );
+
+    assert_eq!(
+      expected_pixel_data_vr(&EXPLICIT_VR_LITTLE_ENDIAN, 16),
+      ValueRepresentation::OtherWordString
+    );
+
+    assert_eq!(
+      expected_pixel_data_vr(&JPEG_2K_LOSSLESS_ONLY, 16),
+      ValueRepresentation::OtherByteString
+    );
+  }
 }
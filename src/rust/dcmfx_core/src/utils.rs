@@ -18,6 +18,18 @@ pub fn inspect_u8_slice(bytes: &[u8], max_length: usize) -> String {
   }
 }
 
+/// Trims leading and trailing whitespace from a string value as defined by
+/// DICOM, i.e. the space character and the null character used to pad values
+/// to an even length. Other whitespace characters such as tabs and newlines are
+/// not trimmed.
+///
+/// Note that the parsing of numeric, date, and time values is more lenient
+/// and trims all whitespace.
+///
+pub fn trim_dicom_whitespace(s: &str) -> &str {
+  s.trim_matches([' ', '\0'])
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -34,4 +46,13 @@ mod tests {
       "[D1 96 33 ...]".to_string()
     );
   }
+
+  #[test]
+  fn trim_dicom_whitespace_test() {
+    assert_eq!(trim_dicom_whitespace(""), "");
+    assert_eq!(trim_dicom_whitespace("  12"), "12");
+    assert_eq!(trim_dicom_whitespace("1.5\0\0"), "1.5");
+    assert_eq!(trim_dicom_whitespace(" \0 -3 4 \0 "), "-3 4");
+    assert_eq!(trim_dicom_whitespace("\t7\n"), "\t7\n");
+  }
 }
@@ -45,7 +45,9 @@ pub enum ValueRepresentation {
 
 /// The restrictions that apply to the length of a value representation's data.
 /// These restrictions are defined by the DICOM specification, and are only
-/// enforced when creating new values.
+/// enforced when creating new values. They are returned by
+/// [`ValueRepresentation::length_requirements()`] so that external code can
+/// also check or enforce them, e.g. to truncate user input to fit.
 ///
 /// The restrictions are:
 ///
@@ -58,10 +60,16 @@ pub enum ValueRepresentation {
 ///    (not bytes) in the string. In multi-valued string VRs this limit applies
 ///    to each value individually.
 ///
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct LengthRequirements {
+  /// The maximum number of bytes a value can have.
   pub bytes_max: usize,
+
+  /// The number that the number of bytes in a value must be an exact multiple
+  /// of, if any.
   pub bytes_multiple_of: Option<usize>,
+
+  /// The maximum number of characters in each string value, if any.
   pub string_characters_max: Option<usize>,
 }
 
@@ -236,21 +244,38 @@ impl ValueRepresentation {
       || self == ValueRepresentation::UnlimitedText
   }
 
+  /// Returns the byte used to pad values of this value representation to an
+  /// even length. [`ValueRepresentation::UniqueIdentifier`] and binary VRs such
+  /// as [`ValueRepresentation::OtherByteString`] pad with a null byte (0x00),
+  /// and all other string VRs pad with a space (0x20).
+  ///
+  /// Ref: PS3.5 6.2.
+  ///
+  pub fn padding_byte(self) -> u8 {
+    if self.is_string() && self != ValueRepresentation::UniqueIdentifier {
+      0x20
+    } else {
+      0x00
+    }
+  }
+
   /// Appends the correct padding byte for the given value representation if the
-  /// bytes are not of even length.
+  /// bytes are not of even length. DICOM requires that all data element values
+  /// have even length.
+  ///
+  /// String VRs are padded with the byte returned by [`Self::padding_byte()`].
+  /// Values of binary VRs are not padded as their length is expected to be
+  /// even already.
   ///
   pub fn pad_bytes_to_even_length(self, bytes: &mut Vec<u8>) {
     if bytes.len() % 2 == 0 {
       return;
     }
 
-    // UI uses a zero byte as padding
-    if self == ValueRepresentation::UniqueIdentifier {
-      bytes.push(0);
-    }
-    // String values use a space as padding. The rest do not use any padding.
-    else if self.is_string() {
-      bytes.push(0x20);
+    // String values are padded, with UI using a zero byte and the rest using a
+    // space. The rest do not use any padding.
+    if self.is_string() {
+      bytes.push(self.padding_byte());
     }
   }
 
@@ -478,7 +503,7 @@ impl ValueRepresentation {
 mod tests {
   use super::*;
 
-  const ALL_VRS: [(ValueRepresentation, &'static str, &'static str); 34] = [
+  const ALL_VRS: [(ValueRepresentation, &str, &str); 34] = [
     (ValueRepresentation::AgeString, "AS", "AgeString"),
     (
       ValueRepresentation::ApplicationEntity,
@@ -632,6 +657,14 @@ mod tests {
     }
   }
 
+  #[test]
+  fn padding_byte_test() {
+    assert_eq!(ValueRepresentation::UniqueIdentifier.padding_byte(), 0x00);
+    assert_eq!(ValueRepresentation::OtherByteString.padding_byte(), 0x00);
+    assert_eq!(ValueRepresentation::CodeString.padding_byte(), 0x20);
+    assert_eq!(ValueRepresentation::PersonName.padding_byte(), 0x20);
+  }
+
   #[test]
   fn pad_bytes_to_even_length_test() {
     let mut bytes = vec![];
@@ -671,6 +704,22 @@ mod tests {
       }
     );
 
+    assert_eq!(
+      ValueRepresentation::ShortString
+        .length_requirements()
+        .string_characters_max,
+      Some(16)
+    );
+
+    assert_eq!(
+      ValueRepresentation::LongText.length_requirements(),
+      LengthRequirements {
+        bytes_max: 0xFFFE,
+        bytes_multiple_of: None,
+        string_characters_max: Some(10_240),
+      }
+    );
+
     assert_eq!(
       ValueRepresentation::PersonName.length_requirements(),
       LengthRequirements {
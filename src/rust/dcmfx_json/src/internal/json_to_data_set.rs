@@ -82,7 +82,7 @@ fn convert_json_to_data_element(
   };
 
   // Read the VR for this value
-  let vr = read_dicom_json_vr(&raw_value, path)?;
+  let vr = read_dicom_json_vr(&raw_value, tag, path)?;
 
   // To read the data element value, first look for a "Value" property, then
   // look for an "InlineBinary" property, then finally look for a "BulkDataURI"
@@ -112,20 +112,27 @@ fn convert_json_to_data_element(
   }
 }
 
-/// Reads a native value representation from a DICOM JSON "vr" property.
+/// Reads a native value representation from a DICOM JSON "vr" property. The
+/// VR is case-insensitive, and if the "vr" property is absent then the VR is
+/// taken from the dictionary when it defines a single VR for the tag.
 ///
 fn read_dicom_json_vr(
   raw_value: &serde_json::Map<String, serde_json::Value>,
+  tag: DataElementTag,
   path: &mut DataSetPath,
 ) -> Result<ValueRepresentation, JsonDeserializeError> {
-  // Read the VR
+  // Read the VR, falling back to the dictionary if it isn't specified
   let raw_vr = if let Some(raw_vr) = raw_value.get("vr") {
     raw_vr
   } else {
-    return Err(JsonDeserializeError::JsonInvalid {
-      details: "VR is missing".to_string(),
-      path: path.clone(),
-    });
+    return match dictionary::find(tag, None).map(|item| item.vrs) {
+      Ok([vr]) => Ok(*vr),
+      _ => Err(JsonDeserializeError::JsonInvalid {
+        details: "VR is missing and can't be determined from the dictionary"
+          .to_string(),
+        path: path.clone(),
+      }),
+    };
   };
 
   // Get the VR string value
@@ -139,7 +146,9 @@ fn read_dicom_json_vr(
   };
 
   // Convert to a native VR
-  if let Ok(vr) = ValueRepresentation::from_bytes(vr_string.as_bytes()) {
+  if let Ok(vr) =
+    ValueRepresentation::from_bytes(vr_string.to_ascii_uppercase().as_bytes())
+  {
     Ok(vr)
   } else {
     Err(JsonDeserializeError::JsonInvalid {
@@ -186,7 +195,7 @@ fn read_dicom_json_primitive_value(
       let mut bytes = Vec::with_capacity(
         strings
           .iter()
-          .map(|s| s.as_ref().map(|s| s.as_bytes().len()).unwrap_or(0) + 1)
+          .map(|s| s.as_ref().map(|s| s.len()).unwrap_or(0) + 1)
           .sum(),
       );
 
@@ -21,4 +21,24 @@ pub struct DicomJsonConfig {
   /// directly inspect.
   ///
   pub pretty_print: bool,
+
+  /// Whether to add a `"keyword"` member to each data element's JSON object
+  /// that holds its keyword from the data element dictionary, e.g.
+  /// `"PatientName"`. This makes the output easier for humans to read.
+  ///
+  /// This option is disabled by default as it's not a part of the DICOM JSON
+  /// standard. Data elements that aren't in the dictionary, which includes all
+  /// private data elements, don't have a keyword added.
+  ///
+  pub include_keywords: bool,
+
+  /// Whether to add a `"PrivateCreator"` member to each private data element's
+  /// JSON object that holds the value of the *'(gggg,00xx) Private Creator'*
+  /// data element that reserves its block, e.g. `"ACME"`.
+  ///
+  /// This option is disabled by default as it's not a part of the DICOM JSON
+  /// standard. Private data elements whose private creator hasn't been read
+  /// don't have a private creator added.
+  ///
+  pub include_private_creators: bool,
 }
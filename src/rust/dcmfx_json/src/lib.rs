@@ -96,6 +96,8 @@ mod tests {
   const JSON_CONFIG: DicomJsonConfig = DicomJsonConfig {
     store_encapsulated_pixel_data: true,
     pretty_print: false,
+    include_keywords: false,
+    include_private_creators: false,
   };
 
   #[test]
@@ -122,6 +124,255 @@ mod tests {
     }
   }
 
+  #[test]
+  fn json_to_data_set_nested_error_path_test() {
+    let json = serde_json::json!({
+      "0040A730": {
+        "vr": "SQ",
+        "Value": [
+          { "0040A160": { "vr": "UT", "Value": ["1a"] } },
+          {
+            "0040A730": {
+              "vr": "SQ",
+              "Value": [{
+                "0040A730": {
+                  "vr": "SQ",
+                  "Value": [{ "0040A160": { "vr": "UT", "Value": [3] } }]
+                }
+              }]
+            }
+          }
+        ]
+      }
+    });
+
+    let mut expected_path = DataSetPath::new();
+    for i in [1, 0, 0] {
+      expected_path
+        .add_data_element(dictionary::CONTENT_SEQUENCE.tag)
+        .unwrap();
+      expected_path.add_sequence_item(i).unwrap();
+    }
+    expected_path
+      .add_data_element(dictionary::TEXT_VALUE.tag)
+      .unwrap();
+
+    match DataSet::from_json(&json.to_string()) {
+      Err(JsonDeserializeError::JsonInvalid { details, path }) => {
+        assert_eq!(details, "String value is invalid");
+        assert_eq!(path, expected_path);
+      }
+
+      result => panic!("Unexpected result: {:?}", result),
+    }
+  }
+
+  #[test]
+  fn json_to_data_set_vr_tolerance_test() {
+    let json = serde_json::json!({
+      "00100010": { "vr": "pn", "Value": [{ "Alphabetic": "A^B" }] },
+      "00100020": { "Value": ["123"] },
+      "00280010": { "vr": "us", "Value": [64] }
+    });
+
+    let ds = DataSet::from_json(&json.to_string()).unwrap();
+
+    assert_eq!(
+      ds.get_value(dictionary::PATIENT_NAME.tag)
+        .map(|value| value.value_representation()),
+      Ok(ValueRepresentation::PersonName)
+    );
+    assert_eq!(ds.get_string(dictionary::PATIENT_ID.tag), Ok("123"));
+    assert_eq!(
+      ds.get_value(dictionary::PATIENT_ID.tag)
+        .map(|value| value.value_representation()),
+      Ok(ValueRepresentation::LongString)
+    );
+    assert_eq!(ds.get_int(dictionary::ROWS.tag), Ok(64));
+
+    // The VR can't be determined for a tag that allows multiple VRs
+    let json = serde_json::json!({ "00280106": { "Value": [0] } });
+
+    match DataSet::from_json(&json.to_string()) {
+      Err(JsonDeserializeError::JsonInvalid { details, path }) => {
+        assert_eq!(
+          details,
+          "VR is missing and can't be determined from the dictionary"
+        );
+        assert_eq!(
+          path,
+          DataSetPath::new_with_data_element(
+            dictionary::SMALLEST_IMAGE_PIXEL_VALUE.tag
+          )
+        );
+      }
+
+      result => panic!("Unexpected result: {:?}", result),
+    }
+  }
+
+  #[test]
+  fn p10_json_transform_streams_inline_binary_test() {
+    let mut json_transform = P10JsonTransform::new(&JSON_CONFIG);
+    let mut stream: Vec<u8> = vec![];
+
+    let parts = [
+      P10Part::FileMetaInformation {
+        data_set: DataSet::new(),
+      },
+      P10Part::DataElementHeader {
+        tag: dictionary::PIXEL_DATA.tag,
+        vr: ValueRepresentation::OtherByteString,
+        length: 3 * 48 * 1024,
+      },
+    ];
+    for part in parts.iter() {
+      json_transform.add_part(part, &mut stream).unwrap();
+    }
+
+    // Each chunk of value bytes should be written out as Base64 as soon as it
+    // is received rather than being held until the data element is complete
+    for bytes_remaining in [2 * 48 * 1024, 48 * 1024, 0] {
+      let length_before = stream.len();
+
+      json_transform
+        .add_part(
+          &P10Part::DataElementValueBytes {
+            vr: ValueRepresentation::OtherByteString,
+            data: Rc::new(vec![0; 48 * 1024]),
+            bytes_remaining,
+          },
+          &mut stream,
+        )
+        .unwrap();
+
+      assert!(stream.len() - length_before >= 48 * 1024 * 4 / 3);
+    }
+
+    json_transform.add_part(&P10Part::End, &mut stream).unwrap();
+
+    let json: serde_json::Value = serde_json::from_slice(&stream).unwrap();
+    assert_eq!(
+      json["7FE00010"]["InlineBinary"].as_str().unwrap().len(),
+      3 * 48 * 1024 * 4 / 3
+    );
+  }
+
+  #[test]
+  fn data_set_to_json_include_keywords_test() {
+    let mut ds = DataSet::new();
+    ds.insert_string_value(&dictionary::PATIENT_ID, &["123"])
+      .unwrap();
+    ds.insert_sequence_value(
+      &dictionary::REFERENCED_IMAGE_SEQUENCE,
+      vec![[(
+        dictionary::REFERENCED_SOP_INSTANCE_UID.tag,
+        DataElementValue::new_unique_identifier(&["1.2"]).unwrap(),
+      )]
+      .into_iter()
+      .collect()],
+    )
+    .unwrap();
+    ds.insert(
+      DataElementTag::new(0x0009, 0x0010),
+      DataElementValue::new_long_string(&["ACME"]).unwrap(),
+    );
+
+    for pretty_print in [false, true] {
+      let config = DicomJsonConfig {
+        pretty_print,
+        include_keywords: true,
+        ..JSON_CONFIG
+      };
+
+      assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&ds.to_json(config).unwrap())
+          .unwrap(),
+        serde_json::json!({
+          "00081140": {
+            "keyword": "ReferencedImageSequence",
+            "vr": "SQ",
+            "Value": [{
+              "00081155": {
+                "keyword": "ReferencedSOPInstanceUID",
+                "vr": "UI",
+                "Value": ["1.2"]
+              }
+            }]
+          },
+          "00090010": { "vr": "LO", "Value": ["ACME"] },
+          "00100020": { "keyword": "PatientID", "vr": "LO", "Value": ["123"] }
+        })
+      );
+    }
+
+    let json: serde_json::Value =
+      serde_json::from_str(&ds.to_json(JSON_CONFIG).unwrap()).unwrap();
+    assert_eq!(json["00100020"].get("keyword"), None);
+    assert_eq!(json["00081140"].get("keyword"), None);
+  }
+
+  #[test]
+  fn data_set_to_json_include_private_creators_test() {
+    let ds: DataSet = vec![
+      (
+        DataElementTag::new(0x0009, 0x0010),
+        DataElementValue::new_long_string(&["VENDOR"]).unwrap(),
+      ),
+      (
+        DataElementTag::new(0x0009, 0x1001),
+        DataElementValue::new_long_string(&["123"]).unwrap(),
+      ),
+      (
+        DataElementTag::new(0x0009, 0x1002),
+        DataElementValue::new_sequence(vec![[(
+          DataElementTag::new(0x0011, 0x1101),
+          DataElementValue::new_long_string(&["456"]).unwrap(),
+        )]
+        .into_iter()
+        .collect()]),
+      ),
+      (
+        DataElementTag::new(0x0009, 0x1101),
+        DataElementValue::new_long_string(&["789"]).unwrap(),
+      ),
+    ]
+    .into_iter()
+    .collect();
+
+    for pretty_print in [false, true] {
+      let config = DicomJsonConfig {
+        pretty_print,
+        include_private_creators: true,
+        ..JSON_CONFIG
+      };
+
+      assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&ds.to_json(config).unwrap())
+          .unwrap(),
+        serde_json::json!({
+          "00090010": { "vr": "LO", "Value": ["VENDOR"] },
+          "00091001": {
+            "vr": "LO",
+            "PrivateCreator": "VENDOR",
+            "Value": ["123"]
+          },
+          "00091002": {
+            "vr": "SQ",
+            "PrivateCreator": "VENDOR",
+            "Value": [{ "00111101": { "vr": "LO", "Value": ["456"] } }]
+          },
+          "00091101": { "vr": "LO", "Value": ["789"] }
+        })
+      );
+    }
+
+    let json: serde_json::Value =
+      serde_json::from_str(&ds.to_json(JSON_CONFIG).unwrap()).unwrap();
+    assert_eq!(json["00091001"].get("PrivateCreator"), None);
+    assert_eq!(json["00091002"].get("PrivateCreator"), None);
+  }
+
   /// Returns pairs of data sets and their corresponding DICOM JSON string.
   /// These are used to test conversion both to and from DICOM JSON.
   ///
@@ -202,6 +453,25 @@ mod tests {
         )],
         serde_json::json!({ "7FE00010": { "vr": "OW", "InlineBinary": "AwQ=" } }),
       ),
+      (
+        vec![(
+          dictionary::ENCAPSULATED_DOCUMENT.tag,
+          DataElementValue::new_other_byte_string(vec![1, 2, 3, 0]).unwrap(),
+        )],
+        serde_json::json!({
+          "00420011": { "vr": "OB", "InlineBinary": "AQIDAA==" }
+        }),
+      ),
+      (
+        vec![(
+          dictionary::PIXEL_DATA.tag,
+          DataElementValue::new_other_word_string(vec![0x03, 0x04, 0, 0])
+            .unwrap(),
+        )],
+        serde_json::json!({
+          "7FE00010": { "vr": "OW", "InlineBinary": "AwQAAA==" }
+        }),
+      ),
       (
         vec![
           (
@@ -255,6 +525,75 @@ mod tests {
         )],
         serde_json::json!({ "0008041D": { "vr": "SQ", "Value": [] } }),
       ),
+      (
+        vec![
+          (
+            dictionary::PATIENT_ID.tag,
+            DataElementValue::new_long_string(&["0"]).unwrap(),
+          ),
+          (
+            dictionary::CONTENT_SEQUENCE.tag,
+            DataElementValue::new_sequence(vec![
+              [
+                (
+                  dictionary::TEXT_VALUE.tag,
+                  DataElementValue::new_unlimited_text("1a").unwrap(),
+                ),
+                (
+                  dictionary::CONTENT_SEQUENCE.tag,
+                  DataElementValue::new_sequence(vec![[
+                    (
+                      dictionary::TEXT_VALUE.tag,
+                      DataElementValue::new_unlimited_text("2").unwrap(),
+                    ),
+                    (
+                      dictionary::CONTENT_SEQUENCE.tag,
+                      DataElementValue::new_sequence(vec![[(
+                        dictionary::TEXT_VALUE.tag,
+                        DataElementValue::new_unlimited_text("3").unwrap(),
+                      )]
+                      .into_iter()
+                      .collect()]),
+                    ),
+                  ]
+                  .into_iter()
+                  .collect()]),
+                ),
+              ]
+              .into_iter()
+              .collect(),
+              [(
+                dictionary::TEXT_VALUE.tag,
+                DataElementValue::new_unlimited_text("1b").unwrap(),
+              )]
+              .into_iter()
+              .collect(),
+            ]),
+          ),
+        ],
+        serde_json::json!({
+          "00100020": { "vr": "LO", "Value": ["0"] },
+          "0040A730": {
+            "vr": "SQ",
+            "Value": [
+              {
+                "0040A160": { "vr": "UT", "Value": ["1a"] },
+                "0040A730": {
+                  "vr": "SQ",
+                  "Value": [{
+                    "0040A160": { "vr": "UT", "Value": ["2"] },
+                    "0040A730": {
+                      "vr": "SQ",
+                      "Value": [{ "0040A160": { "vr": "UT", "Value": ["3"] } }]
+                    }
+                  }]
+                }
+              },
+              { "0040A160": { "vr": "UT", "Value": ["1b"] } }
+            ]
+          }
+        }),
+      ),
     ]
   }
 }
@@ -2,7 +2,7 @@
 //! stream of DICOM JSON data.
 
 use core::str;
-use std::{io::Write, rc::Rc};
+use std::{collections::HashMap, io::Write, rc::Rc};
 
 use base64::prelude::*;
 
@@ -47,6 +47,12 @@ pub struct P10JsonTransform {
   /// The number of items in each active sequence in the data set path. This is
   /// used to provide precise location information when an error occurs.
   sequence_item_counts: Vec<usize>,
+
+  /// The private creator values that have been received in each active data
+  /// set, keyed by the tag of their *'(gggg,00xx) Private Creator'* data
+  /// element. This is used to emit a "PrivateCreator" member for private data
+  /// elements when this is enabled in the config.
+  private_creators: Vec<HashMap<DataElementTag, String>>,
 }
 
 impl P10JsonTransform {
@@ -62,6 +68,7 @@ impl P10JsonTransform {
       pending_base64_input: vec![],
       data_set_path: DataSetPath::new(),
       sequence_item_counts: Vec::new(),
+      private_creators: vec![HashMap::new()],
     }
   }
 
@@ -160,6 +167,8 @@ impl P10JsonTransform {
           *sequence_item_count += 1;
         }
 
+        self.private_creators.push(HashMap::new());
+
         self
           .write_sequence_item_start(stream)
           .map_err(JsonSerializeError::IOError)
@@ -170,6 +179,8 @@ impl P10JsonTransform {
           .write_sequence_item_end(stream)
           .map_err(JsonSerializeError::IOError)?;
 
+        self.private_creators.pop();
+
         self
           .data_set_path
           .pop()
@@ -211,6 +222,76 @@ impl P10JsonTransform {
     stream.write_all(self.indent(offset).as_bytes())
   }
 
+  /// Writes a "PrivateCreator" member for a private data element if this is
+  /// enabled in the config and the value of its *'(gggg,00xx) Private
+  /// Creator'* data element has been received.
+  ///
+  fn write_private_creator(
+    &self,
+    tag: DataElementTag,
+    stream: &mut dyn std::io::Write,
+  ) -> Result<(), std::io::Error> {
+    if !self.config.include_private_creators
+      || !tag.is_private()
+      || tag.is_private_creator()
+    {
+      return Ok(());
+    }
+
+    let private_creator_tag = DataElementTag::new(tag.group, tag.element >> 8);
+
+    let private_creator = match self
+      .private_creators
+      .last()
+      .and_then(|private_creators| private_creators.get(&private_creator_tag))
+    {
+      Some(private_creator) => private_creator,
+      None => return Ok(()),
+    };
+
+    let json = serde_json::to_string(private_creator).unwrap();
+
+    if self.config.pretty_print {
+      self.write_indent(stream, 1)?;
+      stream.write_all(b"\"PrivateCreator\": ")?;
+      stream.write_all(json.as_bytes())?;
+      stream.write_all(b",\n")
+    } else {
+      stream.write_all(br#""PrivateCreator":"#)?;
+      stream.write_all(json.as_bytes())?;
+      stream.write_all(b",")
+    }
+  }
+
+  /// Writes a "keyword" member for a data element if this is enabled in the
+  /// config and the data element's tag is in the dictionary.
+  ///
+  fn write_keyword(
+    &self,
+    tag: DataElementTag,
+    stream: &mut dyn std::io::Write,
+  ) -> Result<(), std::io::Error> {
+    if !self.config.include_keywords || tag.is_private() {
+      return Ok(());
+    }
+
+    let keyword = match dictionary::find(tag, None) {
+      Ok(item) if !item.keyword.is_empty() => item.keyword,
+      _ => return Ok(()),
+    };
+
+    if self.config.pretty_print {
+      self.write_indent(stream, 1)?;
+      stream.write_all(b"\"keyword\": \"")?;
+      stream.write_all(keyword.as_bytes())?;
+      stream.write_all(b"\",\n")
+    } else {
+      stream.write_all(br#""keyword":""#)?;
+      stream.write_all(keyword.as_bytes())?;
+      stream.write_all(b"\",")
+    }
+  }
+
   fn begin(
     &mut self,
     file_meta_information: &DataSet,
@@ -281,15 +362,24 @@ impl P10JsonTransform {
       json[1..9].copy_from_slice(&tag.to_hex_digits());
       stream.write_all(json.as_slice())?;
 
+      self.write_keyword(tag, stream)?;
+      self.write_private_creator(tag, stream)?;
+
       self.write_indent(stream, 1)?;
 
       let mut json = *b"\"vr\": \"__\"";
       json[7..9].copy_from_slice(&vr.to_bytes());
       stream.write_all(json.as_slice())?;
     } else {
-      let mut json = *br#""________":{"vr":"__""#;
+      let mut json = *br#""________":{"#;
       json[1..9].copy_from_slice(&tag.to_hex_digits());
-      json[18..20].copy_from_slice(&vr.to_bytes());
+      stream.write_all(json.as_slice())?;
+
+      self.write_keyword(tag, stream)?;
+      self.write_private_creator(tag, stream)?;
+
+      let mut json = *br#""vr":"__""#;
+      json[6..8].copy_from_slice(&vr.to_bytes());
       stream.write_all(json.as_slice())?;
     }
 
@@ -386,7 +476,7 @@ impl P10JsonTransform {
 
     // If this data element value is not an inline binary and has no data then
     // there's nothing to do
-    if data.len() == 0 && bytes_remaining == 0 {
+    if data.is_empty() && bytes_remaining == 0 {
       return Ok(());
     }
 
@@ -415,6 +505,17 @@ impl P10JsonTransform {
 
     let value = DataElementValue::new_binary_unchecked(vr, bytes.clone());
 
+    // Record private creator values so they can be included on the private
+    // data elements that follow
+    let tag = self.current_data_element.0;
+    if tag.is_private_creator() {
+      if let (Ok(private_creator), Some(private_creators)) =
+        (value.get_string(), self.private_creators.last_mut())
+      {
+        private_creators.insert(tag, private_creator.to_string());
+      }
+    }
+
     let json_values = self
       .convert_binary_value_to_json(&value, bytes)
       .map_err(|e| {
@@ -485,15 +586,25 @@ impl P10JsonTransform {
           json[1..9].copy_from_slice(&tag.to_hex_digits());
           stream.write_all(json.as_slice())?;
 
+          self.write_keyword(tag, stream)?;
+          self.write_private_creator(tag, stream)?;
+
           self.write_indent(stream, 0)?;
           stream.write_all(b"\"vr\": \"SQ\",\n")?;
           self.write_indent(stream, 1)?;
           stream.write_all(b"\"Value\": [")
         })()
       } else {
-        let mut json = *br#""________":{"vr":"SQ","Value":["#;
-        json[1..9].copy_from_slice(&tag.to_hex_digits());
-        stream.write_all(json.as_slice())
+        (|| {
+          let mut json = *br#""________":{"#;
+          json[1..9].copy_from_slice(&tag.to_hex_digits());
+          stream.write_all(json.as_slice())?;
+
+          self.write_keyword(tag, stream)?;
+          self.write_private_creator(tag, stream)?;
+
+          stream.write_all(br#""vr":"SQ","Value":["#)
+        })()
       }
     } else {
       if !self.config.store_encapsulated_pixel_data {
@@ -517,6 +628,8 @@ impl P10JsonTransform {
           json[1..9].copy_from_slice(&tag.to_hex_digits());
           stream.write_all(json.as_slice())?;
 
+          self.write_keyword(tag, stream)?;
+
           self.write_indent(stream, 1)?;
 
           let mut json = *b"\"vr\": \"__\",\n";
@@ -527,11 +640,17 @@ impl P10JsonTransform {
           stream.write_all(b"\"InlineBinary\": \"")
         })()
       } else {
-        let mut json = *br#""________":{"vr":"__","InlineBinary":""#;
-        json[1..9].copy_from_slice(&tag.to_hex_digits());
-        json[18..20].copy_from_slice(&vr.to_bytes());
+        (|| {
+          let mut json = *br#""________":{"#;
+          json[1..9].copy_from_slice(&tag.to_hex_digits());
+          stream.write_all(json.as_slice())?;
+
+          self.write_keyword(tag, stream)?;
 
-        stream.write_all(json.as_slice())
+          let mut json = *br#""vr":"__","InlineBinary":""#;
+          json[6..8].copy_from_slice(&vr.to_bytes());
+          stream.write_all(json.as_slice())
+        })()
       }
     }
     .map_err(JsonSerializeError::IOError)
@@ -1,4 +1,6 @@
 pub mod byte_stream;
 pub mod data_element_header;
 pub mod p10_location;
+#[cfg(test)]
+pub mod test_utils;
 pub mod value_length;
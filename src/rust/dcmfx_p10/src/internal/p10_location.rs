@@ -63,6 +63,11 @@ enum LocationEntry {
 ///
 #[derive(Clone, Debug)]
 struct ClarifyingDataElements {
+  /// The active specific character set. This is parsed once when the
+  /// *'(0008,0005) SpecificCharacterSet'* data element is read and then reused
+  /// to decode all subsequent string values, including in nested items, so
+  /// the value is never re-parsed for each string that's decoded.
+  ///
   specific_character_set: SpecificCharacterSet,
   bits_allocated: Option<u16>,
   pixel_representation: Option<u16>,
@@ -151,6 +156,19 @@ impl P10Location {
     })
   }
 
+  /// Returns whether the location is inside a sequence or item, i.e. whether
+  /// there is anything other than the root data set that still needs to be
+  /// ended.
+  ///
+  pub fn has_open_sequence_or_item(&self) -> bool {
+    self.entries.iter().any(|entry| {
+      matches!(
+        entry,
+        LocationEntry::Sequence { .. } | LocationEntry::Item { .. }
+      )
+    })
+  }
+
   /// Returns the next delimiter part for a location. This checks the `ends_at`
   /// value of the entry at the head of the location to see if the bytes read
   /// has met or exceeded it, and if it has then the relevant delimiter part is
@@ -600,3 +618,77 @@ impl P10Location {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn specific_character_set_is_cached_test() {
+    let mut location = P10Location::new();
+
+    let mut value_bytes = b"ISO_IR 101".to_vec();
+    location
+      .add_clarifying_data_element(
+        dictionary::SPECIFIC_CHARACTER_SET.tag,
+        ValueRepresentation::CodeString,
+        &mut value_bytes,
+      )
+      .unwrap();
+
+    // The emitted value is changed to UTF-8, so the active character set must
+    // come from the parsed value held in the location, not from these bytes
+    assert_eq!(value_bytes, b"ISO_IR 192");
+
+    let expected_specific_character_set =
+      SpecificCharacterSet::from_string("ISO_IR 101").unwrap();
+    assert_eq!(
+      location
+        .active_clarifying_data_elements()
+        .specific_character_set,
+      expected_specific_character_set
+    );
+
+    for _ in 0..3 {
+      assert_eq!(
+        location.decode_string_bytes(ValueRepresentation::LongString, &[0xB1]),
+        "ą".as_bytes()
+      );
+    }
+
+    // Check nested items inherit the parsed character set
+    location
+      .add_sequence(dictionary::REFERENCED_PATIENT_SEQUENCE.tag, false, None)
+      .unwrap();
+    location.add_item(None, ValueLength::Undefined).unwrap();
+
+    assert_eq!(
+      location
+        .active_clarifying_data_elements()
+        .specific_character_set,
+      expected_specific_character_set
+    );
+    assert_eq!(
+      location.decode_string_bytes(ValueRepresentation::LongString, &[0xB1]),
+      "ą".as_bytes()
+    );
+  }
+
+  #[test]
+  fn has_open_sequence_or_item_test() {
+    let mut location = P10Location::new();
+    assert!(!location.has_open_sequence_or_item());
+
+    location
+      .add_sequence(dictionary::REFERENCED_PATIENT_SEQUENCE.tag, false, None)
+      .unwrap();
+    assert!(location.has_open_sequence_or_item());
+
+    location.add_item(None, ValueLength::Undefined).unwrap();
+    assert!(location.has_open_sequence_or_item());
+
+    location.end_item().unwrap();
+    location.end_sequence().unwrap();
+    assert!(!location.has_open_sequence_or_item());
+  }
+}
@@ -0,0 +1,59 @@
+//! Helpers shared by the tests in this crate.
+
+use dcmfx_core::DataSet;
+
+use crate::{DataSetBuilder, P10Error, P10Part, P10ReadContext};
+
+/// Returns the bytes of a DICOM P10 File Preamble and `DICM` prefix followed by
+/// File Meta Information that holds only the given *'(0002,0010) Transfer
+/// Syntax UID'*. Data elements for the main data set can then be appended to
+/// the returned bytes.
+///
+pub fn p10_bytes_with_transfer_syntax_uid(
+  transfer_syntax_uid: &str,
+) -> Vec<u8> {
+  let mut uid = transfer_syntax_uid.as_bytes().to_vec();
+  if uid.len() % 2 == 1 {
+    uid.push(0);
+  }
+
+  let mut bytes = vec![0u8; 128];
+  bytes.extend_from_slice(b"DICM");
+  bytes.extend_from_slice(&[0x02, 0x00, 0x10, 0x00, b'U', b'I']);
+  bytes.extend_from_slice(&(uid.len() as u16).to_le_bytes());
+  bytes.extend_from_slice(&uid);
+
+  bytes
+}
+
+/// Writes the given bytes to a read context as its final bytes, then reads
+/// parts from it until [`P10Part::End`] is reached.
+///
+pub fn read_all_parts(
+  context: &mut P10ReadContext,
+  bytes: Vec<u8>,
+) -> Result<Vec<P10Part>, P10Error> {
+  context.write_bytes(bytes, true)?;
+
+  let mut parts = vec![];
+  while parts.last() != Some(&P10Part::End) {
+    parts.extend(context.read_parts()?);
+  }
+
+  Ok(parts)
+}
+
+/// Reads the given bytes into a data set using a read context, in the same
+/// way as [`read_all_parts()`].
+///
+pub fn read_data_set(
+  context: &mut P10ReadContext,
+  bytes: Vec<u8>,
+) -> Result<DataSet, P10Error> {
+  let mut builder = DataSetBuilder::new();
+  for part in read_all_parts(context, bytes)? {
+    builder.add_part(&part)?;
+  }
+
+  Ok(builder.final_data_set().unwrap())
+}
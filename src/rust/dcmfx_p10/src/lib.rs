@@ -5,6 +5,7 @@ pub mod data_set_builder;
 pub mod p10_error;
 pub mod p10_part;
 pub mod p10_read;
+pub mod p10_warning;
 pub mod p10_write;
 pub mod transforms;
 pub mod uids;
@@ -15,16 +16,19 @@ use std::fs::File;
 use std::io::Read;
 use std::rc::Rc;
 
-use dcmfx_core::DataSet;
+use dcmfx_core::{dictionary, DataSet, TransferSyntax};
 
 pub use data_set_builder::DataSetBuilder;
 pub use p10_error::P10Error;
 pub use p10_part::P10Part;
 pub use p10_read::{P10ReadConfig, P10ReadContext};
-pub use p10_write::{P10WriteConfig, P10WriteContext};
+pub use p10_warning::P10Warning;
+pub use p10_write::{P10WriteConfig, P10WriteContext, SequenceLengthMode};
 pub use transforms::p10_filter_transform::P10FilterTransform;
 pub use transforms::p10_insert_transform::P10InsertTransform;
 pub use transforms::p10_print_transform::P10PrintTransform;
+pub use transforms::p10_retag_transform::P10RetagTransform;
+pub use transforms::p10_transcode_transform::P10TranscodeTransform;
 
 /// Returns whether a file contains DICOM P10 data by checking for the presence
 /// of the DICOM P10 header and the start of a File Meta Information Group
@@ -33,7 +37,7 @@ pub use transforms::p10_print_transform::P10PrintTransform;
 pub fn is_valid_file(filename: String) -> bool {
   match File::open(filename) {
     Ok(mut file) => {
-      let mut buffer = vec![0u8, 138];
+      let mut buffer = vec![0u8; 138];
       match file.read_exact(&mut buffer) {
         Ok(_) => is_valid_bytes(&buffer),
         Err(_) => false,
@@ -64,6 +68,73 @@ pub fn read_file(filename: &str) -> Result<DataSet, P10Error> {
   }
 }
 
+/// Reads DICOM P10 data from a file into an in-memory data set using the given
+/// read config. This allows reading to be customized, e.g. to stop after a
+/// specific data element using [`P10ReadConfig::stop_after_tag`].
+///
+pub fn read_file_with_config(
+  filename: &str,
+  config: &P10ReadConfig,
+) -> Result<DataSet, P10Error> {
+  let mut file = File::open(filename).map_err(|e| P10Error::FileError {
+    when: "Opening file".to_string(),
+    details: e.to_string(),
+  })?;
+
+  match read_stream_with_config(&mut file, config) {
+    Ok(data_set) => Ok(data_set),
+    Err((e, _)) => Err(e),
+  }
+}
+
+/// Reads DICOM P10 data from a file into an in-memory data set, and also returns
+/// the transfer syntax that was used to read it. This is the transfer syntax
+/// specified in the File Meta Information, or 'Implicit VR Little Endian' if
+/// the File Meta Information doesn't specify one.
+///
+pub fn read_file_with_transfer_syntax(
+  filename: &str,
+) -> Result<(DataSet, &'static TransferSyntax), P10Error> {
+  let mut file = File::open(filename).map_err(|e| P10Error::FileError {
+    when: "Opening file".to_string(),
+    details: e.to_string(),
+  })?;
+
+  let mut context = P10ReadContext::new();
+
+  match read_stream_with_context(&mut file, &mut context, |_| ()) {
+    Ok(data_set) => Ok((data_set, context.transfer_syntax())),
+    Err((e, _)) => Err(e),
+  }
+}
+
+/// Reads DICOM P10 data from a file into an in-memory data set, skipping the
+/// value of all *'(7FE0,0010) Pixel Data'* data elements. This is for use when
+/// only the metadata in a DICOM P10 file is needed, e.g. when bulk processing
+/// large numbers of files.
+///
+/// The pixel data value bytes are still read from the file, but are skipped
+/// over by the read context rather than being held in memory, see
+/// [`P10ReadConfig::skip_value_tags`]. The pixel data data element is present
+/// in the returned data set so that its existence can be detected, but its
+/// value is empty and its original length isn't kept. If the pixel data is
+/// encapsulated then each of its items is present but empty.
+///
+/// To get the original length of skipped values, read the DICOM P10 parts with
+/// a [`P10ReadContext`] using [`P10ReadConfig::skip_value_tags`] directly.
+///
+pub fn read_file_metadata_only(filename: &str) -> Result<DataSet, P10Error> {
+  // Limit the part size so that pixel data is never fully held in memory
+  read_file_with_config(
+    filename,
+    &P10ReadConfig {
+      max_part_size: 256 * 1024,
+      skip_value_tags: vec![dictionary::PIXEL_DATA.tag],
+      ..P10ReadConfig::default()
+    },
+  )
+}
+
 /// Reads DICOM P10 data from a file into an in-memory data set. In the case of
 /// an error occurring during the read both the error and the data set builder
 /// at the time of the error are returned.
@@ -91,13 +162,56 @@ pub fn read_file_returning_builder_on_error(
 ///
 pub fn read_stream(
   stream: &mut dyn std::io::Read,
+) -> Result<DataSet, (P10Error, Box<DataSetBuilder>)> {
+  read_stream_with_progress(stream, |_| ())
+}
+
+/// Reads DICOM P10 data from a read stream into an in-memory data set in the
+/// same way as [`read_stream()`], using the given read config.
+///
+pub fn read_stream_with_config(
+  stream: &mut dyn std::io::Read,
+  config: &P10ReadConfig,
 ) -> Result<DataSet, (P10Error, Box<DataSetBuilder>)> {
   let mut context = P10ReadContext::new();
+  context.set_config(config);
+
+  read_stream_with_context(stream, &mut context, |_| ())
+}
+
+/// Reads DICOM P10 data from a read stream into an in-memory data set in the
+/// same way as [`read_stream()`], calling the passed progress callback with
+/// the total number of bytes read so far after each chunk of data is read from
+/// the stream. This can be used to report progress when reading large amounts
+/// of data.
+///
+pub fn read_stream_with_progress(
+  stream: &mut dyn std::io::Read,
+  progress: impl FnMut(u64),
+) -> Result<DataSet, (P10Error, Box<DataSetBuilder>)> {
+  read_stream_with_context(stream, &mut P10ReadContext::new(), progress)
+}
+
+/// Reads DICOM P10 data from a read stream into an in-memory data set using
+/// the passed read context, which can then be queried once reading completes.
+///
+fn read_stream_with_context(
+  stream: &mut dyn std::io::Read,
+  context: &mut P10ReadContext,
+  progress: impl FnMut(u64),
+) -> Result<DataSet, (P10Error, Box<DataSetBuilder>)> {
+  let mut stream = ProgressReader {
+    stream,
+    bytes_read: 0,
+    progress,
+  };
+  let stream = &mut stream;
+
   let mut builder = Box::new(DataSetBuilder::new());
 
   loop {
     // Read the next parts from the stream
-    let parts = match read_parts_from_stream(stream, &mut context) {
+    let parts = match read_parts_from_stream(stream, context) {
       Ok(parts) => parts,
       Err(e) => return Err((e, builder)),
     };
@@ -117,10 +231,35 @@ pub fn read_stream(
   }
 }
 
+/// A read stream adapter that reports the total number of bytes read through it
+/// to a progress callback.
+///
+struct ProgressReader<'a, F: FnMut(u64)> {
+  stream: &'a mut dyn std::io::Read,
+  bytes_read: u64,
+  progress: F,
+}
+
+impl<F: FnMut(u64)> std::io::Read for ProgressReader<'_, F> {
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    let bytes_count = self.stream.read(buf)?;
+
+    if bytes_count > 0 {
+      self.bytes_read += bytes_count as u64;
+      (self.progress)(self.bytes_read);
+    }
+
+    Ok(bytes_count)
+  }
+}
+
 /// Reads the next DICOM P10 parts from a read stream. This repeatedly reads
 /// bytes from the read stream in 256 KiB chunks until at least one DICOM P10
 /// part is made available by the read context or an error occurs.
 ///
+/// The read context's [`P10ReadContext::bytes_read()`] can be checked after
+/// each call to report how far through the stream reading has progressed.
+///
 pub fn read_parts_from_stream(
   stream: &mut dyn std::io::Read,
   context: &mut P10ReadContext,
@@ -161,6 +300,93 @@ pub fn read_parts_from_stream(
   }
 }
 
+/// Reads DICOM P10 data from an asynchronous read stream into an in-memory
+/// data set in the same way as [`read_stream()`], without blocking while
+/// waiting for more data to arrive, e.g. when receiving DICOM P10 data over a
+/// network connection.
+///
+/// The returned future isn't `Send` because data sets aren't, so it needs to
+/// be run on the current thread, e.g. using `tokio::task::spawn_local()`.
+///
+/// This function requires the `async` feature.
+///
+#[cfg(feature = "async")]
+pub async fn read_stream_async<R: tokio::io::AsyncRead + Unpin>(
+  stream: &mut R,
+) -> Result<DataSet, (P10Error, Box<DataSetBuilder>)> {
+  let mut context = P10ReadContext::new();
+  let mut builder = Box::new(DataSetBuilder::new());
+
+  loop {
+    // Read the next parts from the stream
+    let parts = match read_parts_from_stream_async(stream, &mut context).await {
+      Ok(parts) => parts,
+      Err(e) => return Err((e, builder)),
+    };
+
+    // Add the new parts to the data set builder
+    for part in parts {
+      match builder.add_part(&part) {
+        Ok(_) => (),
+        Err(e) => return Err((e, builder)),
+      };
+    }
+
+    // If the data set builder is now complete then return the final data set
+    if let Ok(final_data_set) = builder.final_data_set() {
+      return Ok(final_data_set);
+    }
+  }
+}
+
+/// Reads the next DICOM P10 parts from an asynchronous read stream in the same
+/// way as [`read_parts_from_stream()`].
+///
+/// This function requires the `async` feature.
+///
+#[cfg(feature = "async")]
+pub async fn read_parts_from_stream_async<R: tokio::io::AsyncRead + Unpin>(
+  stream: &mut R,
+  context: &mut P10ReadContext,
+) -> Result<Vec<P10Part>, P10Error> {
+  use tokio::io::AsyncReadExt;
+
+  loop {
+    match context.read_parts() {
+      Ok(parts) => {
+        if parts.is_empty() {
+          continue;
+        } else {
+          return Ok(parts);
+        }
+      }
+
+      // If the read context needs more data then read bytes from the stream,
+      // write them to the read context, and try again
+      Err(P10Error::DataRequired { .. }) => {
+        let mut buffer = vec![0u8; 256 * 1024];
+        match stream.read(&mut buffer).await {
+          Ok(0) => context.write_bytes(vec![], true)?,
+
+          Ok(bytes_count) => {
+            buffer.resize(bytes_count, 0);
+            context.write_bytes(buffer, false)?;
+          }
+
+          Err(e) => {
+            return Err(P10Error::FileError {
+              when: "Reading from stream".to_string(),
+              details: e.to_string(),
+            })
+          }
+        }
+      }
+
+      e => return e,
+    }
+  }
+}
+
 /// Reads DICOM P10 data from an in-memory vector of bytes into an in-memory
 /// data set.
 ///
@@ -374,3 +600,170 @@ impl DataSetP10Extensions for DataSet {
     p10_write::data_set_to_bytes(self, bytes_callback, config)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A read stream that returns at most 1000 bytes from each read.
+  ///
+  struct ChunkedReader(std::io::Cursor<Vec<u8>>);
+
+  impl Read for ChunkedReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+      let length = buf.len().min(1000);
+      self.0.read(&mut buf[..length])
+    }
+  }
+
+  #[test]
+  fn read_file_metadata_only_test() {
+    for filename in [
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm"),
+      concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../../test/assets/pydicom/test_files/JPEG-lossy.dcm"
+      ),
+    ] {
+      let mut data_set = read_file(filename).unwrap();
+      let mut metadata = read_file_metadata_only(filename).unwrap();
+
+      // Only the presence of the pixel data is kept, along with the number of
+      // items when it's encapsulated. Its value and length are not kept.
+      let pixel_data = metadata.get_value(dictionary::PIXEL_DATA.tag).unwrap();
+      let full_pixel_data =
+        data_set.get_value(dictionary::PIXEL_DATA.tag).unwrap();
+      match pixel_data.encapsulated_pixel_data() {
+        Ok(items) => {
+          assert!(items.iter().all(|item| item.is_empty()));
+          assert_eq!(
+            items.len(),
+            full_pixel_data.encapsulated_pixel_data().unwrap().len()
+          );
+        }
+        Err(_) => assert!(pixel_data.bytes().unwrap().is_empty()),
+      }
+
+      data_set.delete(dictionary::PIXEL_DATA.tag);
+      metadata.delete(dictionary::PIXEL_DATA.tag);
+      assert_eq!(metadata, data_set);
+    }
+  }
+
+  #[test]
+  fn read_file_with_config_test() {
+    let filename =
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm");
+    let config = P10ReadConfig {
+      stop_after_tag: Some(dictionary::STUDY_DATE.tag),
+      ..P10ReadConfig::default()
+    };
+
+    let data_set = read_file_with_config(filename, &config).unwrap();
+
+    let mut expected_data_set = read_file(filename).unwrap();
+    for tag in expected_data_set.tags() {
+      if tag > dictionary::STUDY_DATE.tag {
+        expected_data_set.delete(tag);
+      }
+    }
+    assert_eq!(data_set, expected_data_set);
+
+    let mut file = File::open(filename).unwrap();
+    assert_eq!(
+      read_stream_with_config(&mut file, &config).map_err(|e| e.0),
+      Ok(expected_data_set)
+    );
+  }
+
+  #[test]
+  fn read_file_with_transfer_syntax_test() {
+    for filename in [
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm"),
+      concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../../test/assets/pydicom/test_files/JPEG-lossy.dcm"
+      ),
+    ] {
+      let (data_set, transfer_syntax) =
+        read_file_with_transfer_syntax(filename).unwrap();
+
+      assert_eq!(
+        Ok(transfer_syntax),
+        data_set.file_meta_information().get_transfer_syntax()
+      );
+      assert_eq!(data_set, read_file(filename).unwrap());
+    }
+  }
+
+  #[test]
+  fn read_stream_with_progress_test() {
+    let bytes = std::fs::read(concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/../../../examples/example.dcm"
+    ))
+    .unwrap();
+    let mut stream = ChunkedReader(std::io::Cursor::new(bytes.clone()));
+
+    let mut progress_values = vec![];
+    let data_set = read_stream_with_progress(&mut stream, |bytes_read| {
+      progress_values.push(bytes_read)
+    })
+    .map_err(|e| e.0)
+    .unwrap();
+
+    assert_eq!(
+      data_set,
+      read_bytes(bytes.clone()).map_err(|e| e.0).unwrap()
+    );
+
+    assert!(progress_values.len() > 1);
+    assert!(progress_values.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(progress_values.last(), Some(&(bytes.len() as u64)));
+  }
+
+  #[cfg(feature = "async")]
+  #[tokio::test]
+  async fn read_stream_async_test() {
+    use tokio::io::AsyncWriteExt;
+
+    for filename in [
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm"),
+      concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../../test/assets/pydicom/test_files/rtplan.dcm"
+      ),
+    ] {
+      let bytes = std::fs::read(filename).unwrap();
+
+      // Deliver the bytes in small chunks so that reading is spread over many
+      // reads from the stream
+      let (mut reader, mut writer) = tokio::io::simplex(1000);
+      let write_bytes = async {
+        writer.write_all(&bytes).await.unwrap();
+        writer.shutdown().await.unwrap();
+      };
+
+      let (data_set, ()) =
+        tokio::join!(read_stream_async(&mut reader), write_bytes);
+
+      assert_eq!(data_set.map_err(|e| e.0), Ok(read_file(filename).unwrap()));
+    }
+
+    // A truncated stream returns the partially read data set
+    let bytes = std::fs::read(concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/../../../examples/example.dcm"
+    ))
+    .unwrap();
+    let (error, mut builder) =
+      read_stream_async(&mut &bytes[..1000]).await.unwrap_err();
+
+    assert!(matches!(error, P10Error::DataEndedUnexpectedly { .. }));
+    builder.force_end();
+    assert!(builder
+      .final_data_set()
+      .unwrap()
+      .has(dictionary::PATIENT_ID.tag));
+  }
+}
@@ -105,13 +105,36 @@ impl P10Error {
       P10Error::PartStreamInvalid { .. } => {
         "P10 part stream invalid".to_string()
       }
-      P10Error::WriteAfterCompletion { .. } => {
-        "Write after completion".to_string()
-      }
+      P10Error::WriteAfterCompletion => "Write after completion".to_string(),
       P10Error::FileError { .. } => "File I/O failure".to_string(),
       P10Error::OtherError { error_type, .. } => error_type.clone(),
     }
   }
+
+  /// Returns the data set path where the error occurred, if the error has one.
+  ///
+  pub fn path(&self) -> Option<&DataSetPath> {
+    match self {
+      P10Error::DataEndedUnexpectedly { path, .. }
+      | P10Error::DataInvalid { path, .. }
+      | P10Error::MaximumExceeded { path, .. } => Some(path),
+
+      _ => None,
+    }
+  }
+
+  /// Returns the byte offset into the DICOM P10 data where the error
+  /// occurred, if the error has one.
+  ///
+  pub fn offset(&self) -> Option<u64> {
+    match self {
+      P10Error::DataEndedUnexpectedly { offset, .. }
+      | P10Error::DataInvalid { offset, .. }
+      | P10Error::MaximumExceeded { offset, .. } => Some(*offset),
+
+      _ => None,
+    }
+  }
 }
 
 impl dcmfx_core::DcmfxError for P10Error {
@@ -175,17 +198,40 @@ impl dcmfx_core::DcmfxError for P10Error {
     };
 
     // Add the path and offset if present
-    match self {
-      P10Error::DataEndedUnexpectedly { offset, path, .. }
-      | P10Error::DataInvalid { path, offset, .. }
-      | P10Error::MaximumExceeded { offset, path, .. } => {
-        lines.push(format!("  Path: {}", path.to_detailed_string()));
-        lines.push(format!("  Offset: 0x{:X}", offset));
-      }
+    if let (Some(path), Some(offset)) = (self.path(), self.offset()) {
+      lines.push(format!("  Path: {}", path.to_detailed_string()));
+      lines.push(format!("  Offset: 0x{:X}", offset));
+    }
 
-      _ => (),
+    lines
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use dcmfx_core::dictionary;
+
+  #[test]
+  fn path_and_offset_test() {
+    let path = DataSetPath::new_with_data_element(dictionary::PATIENT_ID.tag);
+
+    let error = P10Error::DataInvalid {
+      when: "Reading data element value".to_string(),
+      details: "Invalid".to_string(),
+      path: path.clone(),
+      offset: 0x1234,
     };
 
-    lines
+    assert_eq!(error.path(), Some(&path));
+    assert_eq!(error.offset(), Some(0x1234));
+
+    let error = P10Error::DataRequired {
+      when: "Reading data element header".to_string(),
+    };
+
+    assert_eq!(error.path(), None);
+    assert_eq!(error.offset(), None);
   }
 }
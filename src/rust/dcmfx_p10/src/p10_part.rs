@@ -79,6 +79,39 @@ pub enum P10Part {
   End,
 }
 
+impl P10Part {
+  /// Returns the value of the *'(0002,0010) Transfer Syntax UID'* data element
+  /// in a [`P10Part::FileMetaInformation`] part. This allows the transfer syntax
+  /// to be determined while streaming without building a data set.
+  ///
+  /// Returns `None` if this is not a File Meta Information part, or if the
+  /// data element is not present or invalid.
+  ///
+  pub fn transfer_syntax_uid(&self) -> Option<&str> {
+    self.file_meta_information_string(dictionary::TRANSFER_SYNTAX_UID.tag)
+  }
+
+  /// Returns the value of the *'(0002,0002) Media Storage SOP Class UID'* data
+  /// element in a [`P10Part::FileMetaInformation`] part.
+  ///
+  /// Returns `None` if this is not a File Meta Information part, or if the
+  /// data element is not present or invalid.
+  ///
+  pub fn media_storage_sop_class_uid(&self) -> Option<&str> {
+    self
+      .file_meta_information_string(dictionary::MEDIA_STORAGE_SOP_CLASS_UID.tag)
+  }
+
+  fn file_meta_information_string(&self, tag: DataElementTag) -> Option<&str> {
+    match self {
+      P10Part::FileMetaInformation { data_set } => {
+        data_set.get_string(tag).ok()
+      }
+      _ => None,
+    }
+  }
+}
+
 impl std::fmt::Display for P10Part {
   /// Converts a DICOM P10 part to a human-readable string.
   ///
@@ -246,3 +279,349 @@ pub fn data_element_to_parts<E>(
   // have been taken
   unreachable!();
 }
+
+/// Checks that a stream of DICOM P10 parts is well-formed. This is intended for
+/// use when testing custom transforms that emit P10 parts. The following
+/// requirements are checked:
+///
+/// 1. The File Preamble and File Meta Information parts, if present, come
+///    before all other parts, and the end part, if present, is last.
+/// 2. Every data element header and pixel data item is followed by value bytes
+///    parts that have the same VR and total the declared length, and whose
+///    `bytes_remaining` values count down to zero.
+/// 3. Sequences, sequence items, and encapsulated pixel data are correctly
+///    nested and balanced, with every sequence and item that is started also
+///    being ended.
+///
+/// On failure, the returned error describes the first part that violates these
+/// requirements.
+///
+pub fn validate_stream(parts: &[P10Part]) -> Result<(), String> {
+  /// The location in the part stream that the next part is part of.
+  ///
+  enum Location {
+    Sequence,
+    SequenceItem,
+    EncapsulatedPixelData(ValueRepresentation),
+  }
+
+  let mut locations: Vec<Location> = vec![];
+
+  // The VR and number of bytes of value data still expected for the current
+  // data element or pixel data item
+  let mut pending_value: Option<(ValueRepresentation, u32)> = None;
+
+  let mut is_file_header_allowed = true;
+
+  for (index, part) in parts.iter().enumerate() {
+    let error =
+      |details: &str| Err(format!("Part {} {}: {}", index, details, part));
+
+    // Value bytes must follow until the declared length has been provided
+    if let Some((vr, remaining)) = pending_value {
+      match part {
+        P10Part::DataElementValueBytes {
+          vr: part_vr,
+          data,
+          bytes_remaining,
+        } => {
+          if *part_vr != vr {
+            return error(&format!("has a VR that isn't {}", vr));
+          }
+
+          if data.len() > remaining as usize {
+            return error(&format!(
+              "has more value bytes than the {} bytes remaining",
+              remaining
+            ));
+          }
+
+          let remaining = remaining - data.len() as u32;
+          if *bytes_remaining != remaining {
+            return error(&format!(
+              "has an incorrect bytes remaining value, {} bytes remain",
+              remaining
+            ));
+          }
+
+          if remaining == 0 {
+            pending_value = None;
+          } else {
+            pending_value = Some((vr, remaining));
+          }
+
+          continue;
+        }
+
+        _ => return error("was expected to be value bytes"),
+      }
+    }
+
+    let is_data_set_location =
+      matches!(locations.last(), None | Some(Location::SequenceItem));
+
+    match part {
+      P10Part::FilePreambleAndDICMPrefix { .. } if index == 0 => (),
+
+      P10Part::FileMetaInformation { .. } if is_file_header_allowed => (),
+
+      P10Part::DataElementHeader { vr, length, .. } if is_data_set_location => {
+        pending_value = Some((*vr, *length));
+      }
+
+      P10Part::SequenceStart { vr, .. } if is_data_set_location => {
+        if *vr == ValueRepresentation::Sequence {
+          locations.push(Location::Sequence);
+        } else {
+          locations.push(Location::EncapsulatedPixelData(*vr));
+        }
+      }
+
+      P10Part::SequenceItemStart
+        if matches!(locations.last(), Some(Location::Sequence)) =>
+      {
+        locations.push(Location::SequenceItem);
+      }
+
+      P10Part::SequenceItemDelimiter
+        if matches!(locations.last(), Some(Location::SequenceItem)) =>
+      {
+        locations.pop();
+      }
+
+      P10Part::SequenceDelimiter
+        if matches!(
+          locations.last(),
+          Some(Location::Sequence | Location::EncapsulatedPixelData(_))
+        ) =>
+      {
+        locations.pop();
+      }
+
+      P10Part::PixelDataItem { length } => match locations.last() {
+        Some(Location::EncapsulatedPixelData(vr)) => {
+          pending_value = Some((*vr, *length));
+        }
+        _ => return error("is outside of encapsulated pixel data"),
+      },
+
+      P10Part::End if index + 1 == parts.len() => {
+        if !locations.is_empty() {
+          return error("occurs before all sequences and items have ended");
+        }
+      }
+
+      _ => return error("is not valid at this location"),
+    }
+
+    is_file_header_allowed =
+      matches!(part, P10Part::FilePreambleAndDICMPrefix { .. });
+  }
+
+  if pending_value.is_some() {
+    return Err(
+      "Part stream ended before all value bytes were provided".to_string(),
+    );
+  }
+
+  if !locations.is_empty() {
+    return Err(
+      "Part stream ended before all sequences and items ended".to_string(),
+    );
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn file_meta_information_accessors_test() {
+    let mut file = std::fs::File::open(concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/../../../examples/example.dcm"
+    ))
+    .unwrap();
+    let mut context = crate::P10ReadContext::new();
+
+    let fmi_part = loop {
+      let parts = crate::read_parts_from_stream(&mut file, &mut context)
+        .unwrap()
+        .into_iter()
+        .find(|part| matches!(part, P10Part::FileMetaInformation { .. }));
+
+      if let Some(part) = parts {
+        break part;
+      }
+    };
+
+    assert_eq!(fmi_part.transfer_syntax_uid(), Some("1.2.840.10008.1.2.1"));
+    assert_eq!(
+      fmi_part.media_storage_sop_class_uid(),
+      Some("1.2.840.10008.5.1.4.1.1.4")
+    );
+
+    assert_eq!(P10Part::End.transfer_syntax_uid(), None);
+    assert_eq!(
+      P10Part::FileMetaInformation {
+        data_set: DataSet::new()
+      }
+      .media_storage_sop_class_uid(),
+      None
+    );
+  }
+
+  #[test]
+  fn validate_stream_test() {
+    let mut item = DataSet::new();
+    item
+      .insert_string_value(&dictionary::REFERENCED_SOP_INSTANCE_UID, &["1.2"])
+      .unwrap();
+
+    let mut ds = DataSet::new();
+    ds.insert_string_value(&dictionary::PATIENT_ID, &["123"])
+      .unwrap();
+    ds.insert_sequence_value(
+      &dictionary::REFERENCED_IMAGE_SEQUENCE,
+      vec![item.clone(), item],
+    )
+    .unwrap();
+    ds.insert(
+      dictionary::PIXEL_DATA.tag,
+      DataElementValue::new_encapsulated_pixel_data(
+        ValueRepresentation::OtherByteString,
+        vec![Rc::new(vec![]), Rc::new(vec![1, 2])],
+      )
+      .unwrap(),
+    );
+
+    let mut parts = vec![];
+    data_elements_to_parts::<()>(&ds, &mut |part| {
+      parts.push(part.clone());
+      Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(validate_stream(&parts), Ok(()));
+
+    // A full P10 stream with a preamble, File Meta Information, and end
+    let mut full_parts = vec![
+      P10Part::FilePreambleAndDICMPrefix {
+        preamble: Box::new([0; 128]),
+      },
+      P10Part::FileMetaInformation {
+        data_set: DataSet::new(),
+      },
+    ];
+    full_parts.extend(parts.iter().cloned());
+    full_parts.push(P10Part::End);
+    assert_eq!(validate_stream(&full_parts), Ok(()));
+
+    // Value bytes split across multiple parts
+    let header = P10Part::DataElementHeader {
+      tag: dictionary::PATIENT_ID.tag,
+      vr: ValueRepresentation::LongString,
+      length: 4,
+    };
+    let value_bytes =
+      |data: Vec<u8>, bytes_remaining: u32| P10Part::DataElementValueBytes {
+        vr: ValueRepresentation::LongString,
+        data: Rc::new(data),
+        bytes_remaining,
+      };
+    assert_eq!(
+      validate_stream(&[
+        header.clone(),
+        value_bytes(vec![1, 2], 2),
+        value_bytes(vec![3, 4], 0)
+      ]),
+      Ok(())
+    );
+  }
+
+  #[test]
+  fn validate_stream_malformed_test() {
+    let header = P10Part::DataElementHeader {
+      tag: dictionary::PATIENT_ID.tag,
+      vr: ValueRepresentation::LongString,
+      length: 2,
+    };
+    let sequence_start = P10Part::SequenceStart {
+      tag: dictionary::REFERENCED_IMAGE_SEQUENCE.tag,
+      vr: ValueRepresentation::Sequence,
+    };
+
+    // Value bytes exceeding the header's length
+    assert_eq!(
+      validate_stream(&[
+        header.clone(),
+        P10Part::DataElementValueBytes {
+          vr: ValueRepresentation::LongString,
+          data: Rc::new(vec![1, 2, 3, 4]),
+          bytes_remaining: 0,
+        },
+      ]),
+      Err(
+        "Part 1 has more value bytes than the 2 bytes remaining: \
+         DataElementValueBytes: 4 bytes of data, 0 bytes remaining"
+          .to_string()
+      )
+    );
+
+    // Missing value bytes
+    assert_eq!(
+      validate_stream(std::slice::from_ref(&header)),
+      Err("Part stream ended before all value bytes were provided".to_string())
+    );
+    assert!(validate_stream(&[header.clone(), header.clone()]).is_err());
+
+    // Unbalanced sequences and items
+    assert_eq!(
+      validate_stream(&[sequence_start.clone(), P10Part::SequenceItemStart]),
+      Err("Part stream ended before all sequences and items ended".to_string())
+    );
+    assert_eq!(
+      validate_stream(&[P10Part::SequenceDelimiter]),
+      Err(
+        "Part 0 is not valid at this location: SequenceDelimiter".to_string()
+      )
+    );
+    assert!(validate_stream(&[
+      sequence_start.clone(),
+      P10Part::SequenceItemDelimiter
+    ])
+    .is_err());
+    assert!(validate_stream(&[
+      sequence_start.clone(),
+      P10Part::SequenceItemStart,
+      P10Part::SequenceDelimiter
+    ])
+    .is_err());
+
+    // Data elements directly inside a sequence
+    assert!(validate_stream(&[sequence_start.clone(), header.clone()]).is_err());
+
+    // Pixel data items outside of encapsulated pixel data
+    assert!(validate_stream(&[
+      sequence_start,
+      P10Part::PixelDataItem { length: 0 }
+    ])
+    .is_err());
+
+    // File Meta Information after data elements, and parts after the end
+    assert!(validate_stream(&[
+      P10Part::SequenceStart {
+        tag: dictionary::PIXEL_DATA.tag,
+        vr: ValueRepresentation::OtherByteString
+      },
+      P10Part::SequenceDelimiter,
+      P10Part::FileMetaInformation {
+        data_set: DataSet::new()
+      }
+    ])
+    .is_err());
+    assert!(validate_stream(&[P10Part::End, P10Part::End]).is_err());
+  }
+}
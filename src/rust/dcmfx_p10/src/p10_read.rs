@@ -28,7 +28,9 @@ use crate::internal::data_element_header::{
   DataElementHeader, ValueLengthSize,
 };
 use crate::internal::p10_location::{self, P10Location};
-use crate::{internal::value_length::ValueLength, P10Error, P10Part};
+use crate::{
+  internal::value_length::ValueLength, P10Error, P10Part, P10Warning,
+};
 
 /// Configuration used when reading DICOM P10 data.
 ///
@@ -90,6 +92,81 @@ pub struct P10ReadConfig {
   /// meaningful maximum is enforced.
   ///
   pub max_sequence_depth: u32,
+
+  /// The transfer syntax to read with when the File Meta Information specifies
+  /// a transfer syntax UID that isn't recognized. This allows reading DICOM P10
+  /// data that uses a private or experimental transfer syntax that is actually
+  /// encoded using a standard transfer syntax, e.g. 'Implicit VR Little
+  /// Endian'.
+  ///
+  /// When the fallback is used a warning is added to the read context, see
+  /// [`P10ReadContext::warnings()`], and the *'(0002,0010) Transfer Syntax
+  /// UID'* in the emitted File Meta Information is set to the fallback
+  /// transfer syntax's UID.
+  ///
+  /// By default there is no fallback, and an unrecognized transfer syntax
+  /// results in a [`P10Error::TransferSyntaxNotSupported`] error.
+  ///
+  pub unknown_transfer_syntax_fallback: Option<&'static TransferSyntax>,
+
+  /// Data elements that have their value bytes emitted exactly as read, even
+  /// when they have a string VR that would normally be converted to UTF-8.
+  /// This allows reading data elements that are known to hold binary data
+  /// despite having a string VR, which is sometimes seen in vendor-specific
+  /// data elements.
+  ///
+  /// Clarifying data elements such as *'(0008,0005) Specific Character Set'*
+  /// are always converted because they're needed to read the rest of the data.
+  ///
+  /// By default this is empty.
+  ///
+  pub raw_value_tags: Vec<DataElementTag>,
+
+  /// Data elements that have their value bytes skipped over rather than
+  /// emitted. The [`P10Part::DataElementHeader`] for such a data element is
+  /// emitted with the original value length, and is followed by a single
+  /// empty [`P10Part::DataElementValueBytes`] part. In the case of
+  /// encapsulated pixel data, each [`P10Part::PixelDataItem`] part is emitted
+  /// with its original length and followed by a single empty
+  /// [`P10Part::DataElementValueBytes`] part.
+  ///
+  /// This is useful when the size of large values such as *'(7FE0,0010) Pixel
+  /// Data'* is of interest but their content isn't, as the skipped value bytes
+  /// are never held in memory. The skipped value bytes are still read from the
+  /// input. Note that the parts emitted for skipped data elements can't be
+  /// written out as valid DICOM P10 data, and that a
+  /// [`crate::DataSetBuilder`] given them stores an empty value that doesn't
+  /// record the original length.
+  ///
+  /// Clarifying data elements such as *'(0008,0005) Specific Character Set'*
+  /// are never skipped because they're needed to read the rest of the data.
+  ///
+  /// By default this is empty.
+  ///
+  pub skip_value_tags: Vec<DataElementTag>,
+
+  /// The tag of the last data element of interest in the root data set. When
+  /// set, reading stops as soon as a data element in the root data set with a
+  /// greater tag is reached, and [`P10Part::End`] is emitted. None of the data
+  /// following that point is read, including any pixel data.
+  ///
+  /// This makes reading only the initial data elements of DICOM P10 data, such
+  /// as patient and study details, much faster when done across many files.
+  ///
+  /// By default there is no stop tag and all data is read.
+  ///
+  pub stop_after_tag: Option<DataElementTag>,
+
+  /// Whether to require that DICOM P10 data is well-formed. When set, an error
+  /// is returned if the data ends while any sequences or items are still open,
+  /// or if a sequence delimiter occurs outside of a sequence. This is useful
+  /// when checking the integrity of archived DICOM P10 data.
+  ///
+  /// By default this is `false`, and such data is read as though the missing
+  /// delimiters were present and rogue sequence delimiters are ignored, which
+  /// means DICOM P10 data truncated on a data element boundary is readable.
+  ///
+  pub require_well_formed: bool,
 }
 
 impl Default for P10ReadConfig {
@@ -98,6 +175,11 @@ impl Default for P10ReadConfig {
       max_part_size: 0xFFFFFFFE,
       max_string_size: 0xFFFFFFFE,
       max_sequence_depth: 10_000,
+      unknown_transfer_syntax_fallback: None,
+      raw_value_tags: vec![],
+      skip_value_tags: vec![],
+      stop_after_tag: None,
+      require_well_formed: false,
     }
   }
 }
@@ -118,6 +200,7 @@ pub struct P10ReadContext {
   path: DataSetPath,
   location: P10Location,
   sequence_depth: u32,
+  warnings: Vec<P10Warning>,
 }
 
 /// The next action specifies what will be attempted to be read next from a read
@@ -138,13 +221,32 @@ enum NextAction {
     vr: ValueRepresentation,
     length: u32,
     bytes_remaining: u32,
-    emit_parts: bool,
+    value_bytes_handling: ValueBytesHandling,
   },
   ReadPixelDataItem {
     vr: ValueRepresentation,
+    value_bytes_handling: ValueBytesHandling,
   },
 }
 
+/// Specifies what is done with the value bytes of a data element as they are
+/// read.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ValueBytesHandling {
+  /// The value bytes are emitted in [`P10Part::DataElementValueBytes`] parts.
+  Emit,
+
+  /// The value bytes are discarded, and a single empty
+  /// [`P10Part::DataElementValueBytes`] part is emitted once all of them have
+  /// been read. See [`P10ReadConfig::skip_value_tags`].
+  Skip,
+
+  /// The value bytes are discarded and no parts are emitted for the data
+  /// element.
+  Swallow,
+}
+
 impl P10ReadContext {
   /// Creates a new read context for reading DICOM P10 data.
   ///
@@ -157,6 +259,7 @@ impl P10ReadContext {
       path: DataSetPath::new(),
       location: P10Location::new(),
       sequence_depth: 0,
+      warnings: vec![],
     }
   }
 
@@ -170,10 +273,25 @@ impl P10ReadContext {
     self.config = P10ReadConfig {
       max_part_size,
       max_string_size,
-      ..*config
+      ..config.clone()
     };
   }
 
+  /// Returns a read context to its initial state so that it can be reused to
+  /// read further DICOM P10 data. All bytes and state from the current read
+  /// are discarded, and the transfer syntax is reset to 'Implicit VR Little
+  /// Endian'. The read context's config is kept.
+  ///
+  pub fn reset(&mut self) {
+    self.stream = ByteStream::new();
+    self.next_action = NextAction::ReadFilePreambleAndDICMPrefix;
+    self.transfer_syntax = &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN;
+    self.path = DataSetPath::new();
+    self.location = P10Location::new();
+    self.sequence_depth = 0;
+    self.warnings.clear();
+  }
+
   /// Sets the transfer syntax to use when reading DICOM P10 data that doesn't
   /// specify a transfer syntax in its File Meta Information, or doesn't have
   /// any File Meta Information.
@@ -197,10 +315,31 @@ impl P10ReadContext {
   /// The default transfer syntax can be set using
   /// [`Self::set_fallback_transfer_syntax()`].
   ///
-  pub fn transfer_syntax(&self) -> &TransferSyntax {
+  pub fn transfer_syntax(&self) -> &'static TransferSyntax {
     self.transfer_syntax
   }
 
+  /// Returns the number of bytes of DICOM P10 data that have been read so far
+  /// by a read context, i.e. the byte offset of the data that will be read
+  /// next. Combined with the total size of the data, this can be used to report
+  /// progress when reading large amounts of DICOM P10 data.
+  ///
+  /// When the transfer syntax is 'Deflated Explicit VR Little Endian' the bytes
+  /// following the File Meta Information are counted after they've been
+  /// inflated.
+  ///
+  pub fn bytes_read(&self) -> u64 {
+    self.stream.bytes_read()
+  }
+
+  /// Returns the warnings that have occurred so far when reading DICOM P10
+  /// data with a read context. Warnings describe non-fatal issues with the
+  /// DICOM P10 data that were worked around in order to continue reading.
+  ///
+  pub fn warnings(&self) -> &[P10Warning] {
+    &self.warnings
+  }
+
   /// Writes raw DICOM P10 bytes to a read context that will be parsed into
   /// DICOM P10 parts by subsequent calls to [`Self::read_parts()`]. If `done`
   /// is true this indicates the end of the incoming DICOM P10 data to be
@@ -230,40 +369,81 @@ impl P10ReadContext {
   /// read.
   ///
   pub fn read_parts(&mut self) -> Result<Vec<P10Part>, P10Error> {
-    match self.next_action {
+    let mut parts = vec![];
+    self.read_parts_into(&mut parts)?;
+
+    Ok(parts)
+  }
+
+  /// Reads the next DICOM P10 parts from a read context into the provided
+  /// buffer. This is the same as [`Self::read_parts()`], except that the
+  /// caller owns the buffer and can reuse it across calls in order to avoid
+  /// allocating a new [`Vec`] for every read.
+  ///
+  /// The buffer is cleared before any parts are added to it. On error, the
+  /// buffer is left empty.
+  ///
+  pub fn read_parts_into(
+    &mut self,
+    out: &mut Vec<P10Part>,
+  ) -> Result<(), P10Error> {
+    out.clear();
+
+    let result = match self.next_action {
       NextAction::ReadFilePreambleAndDICMPrefix => {
-        self.read_file_preamble_and_dicm_prefix_part()
+        self.read_file_preamble_and_dicm_prefix_part(out)
       }
 
       NextAction::ReadFileMetaInformation { .. } => {
-        self.read_file_meta_information_part()
+        self.read_file_meta_information_part(out)
       }
 
       NextAction::ReadDataElementHeader => {
         // If there is a delimiter part for a defined-length sequence or item
         // that needs to be emitted then return that as the next part
-        let delimiter_part = self.next_delimiter_part();
-        if !delimiter_part.is_empty() {
-          return Ok(delimiter_part);
+        if let Some(delimiter_part) = self.next_delimiter_part() {
+          out.push(delimiter_part);
+          return Ok(());
         }
 
         // Detect the end of the DICOM data
         if self.stream.is_fully_consumed() {
           // Return the parts required to end any active sequences and items.
           //
-          // This means there is no check that all items and sequences have been
-          // ended as should occur in well-formed P10 data, i.e. P10 data can be
-          // truncated on a data element boundary and no error will be thrown.
-          //
-          // If there's a desire to error on truncated data then add a check
-          // that context.location has exactly one entry.
+          // Unless well-formed data is required, this means there is no check
+          // that all items and sequences have been ended as should occur in
+          // well-formed P10 data, i.e. P10 data can be truncated on a data
+          // element boundary and no error will be thrown.
+          if self.config.require_well_formed
+            && self.location.has_open_sequence_or_item()
+          {
+            return Err(P10Error::DataInvalid {
+              when: "Reading data element header".to_string(),
+              details: "DICOM P10 data ended while a sequence or item was \
+                still open"
+                .to_string(),
+              path: self.path.clone(),
+              offset: self.stream.bytes_read(),
+            });
+          }
 
-          let parts = self.location.pending_delimiter_parts();
+          out.extend(self.location.pending_delimiter_parts());
 
-          Ok(parts)
+          Ok(())
         } else {
-          // There is more data so start reading the next data element
-          self.read_data_element_header_part()
+          match self.is_past_stop_after_tag() {
+            // End reading if the configured stop tag has been passed. The
+            // stream isn't consumed so subsequent reads also end here.
+            Ok(true) => {
+              out.extend(self.location.pending_delimiter_parts());
+              Ok(())
+            }
+
+            // There is more data so start reading the next data element
+            Ok(false) => self.read_data_element_header_part(out),
+
+            Err(e) => Err(e),
+          }
         }
       }
 
@@ -272,25 +452,46 @@ impl P10ReadContext {
         vr,
         length,
         bytes_remaining,
-        emit_parts,
+        value_bytes_handling,
       } => self.read_data_element_value_bytes_part(
         tag,
         vr,
         length,
         bytes_remaining,
-        emit_parts,
+        value_bytes_handling,
+        out,
       ),
 
-      NextAction::ReadPixelDataItem { vr } => {
-        self.read_pixel_data_item_part(vr)
+      NextAction::ReadPixelDataItem {
+        vr,
+        value_bytes_handling,
+      } => self.read_pixel_data_item_part(vr, value_bytes_handling, out),
+    };
+
+    if result.is_err() {
+      out.clear();
+    }
+
+    result
+  }
+
+  /// Returns whether the next data element is in the root data set and has a
+  /// tag greater than the configured stop tag, which means reading should end.
+  ///
+  fn is_past_stop_after_tag(&mut self) -> Result<bool, P10Error> {
+    match self.config.stop_after_tag {
+      Some(stop_after_tag) if self.path.is_empty() => {
+        Ok(self.peek_data_element_tag()? > stop_after_tag)
       }
+
+      _ => Ok(false),
     }
   }
 
   /// Checks whether there is a delimiter part that needs to be emitted, and if
   /// so then returns it.
   ///
-  fn next_delimiter_part(&mut self) -> Vec<P10Part> {
+  fn next_delimiter_part(&mut self) -> Option<P10Part> {
     let bytes_read = self.stream.bytes_read();
 
     match self.location.next_delimiter_part(bytes_read) {
@@ -307,10 +508,10 @@ impl P10ReadContext {
           self.path.pop().unwrap();
         }
 
-        vec![part]
+        Some(part)
       }
 
-      Err(()) => vec![],
+      Err(()) => None,
     }
   }
 
@@ -321,7 +522,8 @@ impl P10ReadContext {
   ///
   fn read_file_preamble_and_dicm_prefix_part(
     &mut self,
-  ) -> Result<Vec<P10Part>, P10Error> {
+    parts: &mut Vec<P10Part>,
+  ) -> Result<(), P10Error> {
     let preamble = match self.stream.peek(132) {
       Ok(data) => {
         if &data[128..132] == b"DICM" {
@@ -354,7 +556,9 @@ impl P10ReadContext {
       data_set: DataSet::new(),
     };
 
-    Ok(vec![P10Part::FilePreambleAndDICMPrefix { preamble }])
+    parts.push(P10Part::FilePreambleAndDICMPrefix { preamble });
+
+    Ok(())
   }
 
   /// Reads the File Meta Information into a data set and returns the relevant
@@ -365,13 +569,16 @@ impl P10ReadContext {
   ///
   fn read_file_meta_information_part(
     &mut self,
-  ) -> Result<Vec<P10Part>, P10Error> {
+    parts: &mut Vec<P10Part>,
+  ) -> Result<(), P10Error> {
     if let NextAction::ReadFileMetaInformation {
       starts_at,
       ends_at,
       data_set: fmi_data_set,
     } = &mut self.next_action
     {
+      let mut is_transfer_syntax_fallback = false;
+
       loop {
         // Check if the end of the File Meta Information has been reached
         if let Some(ends_at) = ends_at {
@@ -527,11 +734,28 @@ impl P10ReadContext {
         // in the read context
         if tag == dictionary::TRANSFER_SYNTAX_UID.tag {
           self.transfer_syntax = match value.get_string() {
-            Ok(uid) => TransferSyntax::from_uid(uid).map_err(|_| {
-              P10Error::TransferSyntaxNotSupported {
-                transfer_syntax_uid: uid.to_string(),
-              }
-            }),
+            Ok(uid) => match TransferSyntax::from_uid(uid) {
+              Ok(transfer_syntax) => Ok(transfer_syntax),
+
+              // Use the fallback for unrecognized transfer syntaxes, if one
+              // has been configured
+              Err(()) => match self.config.unknown_transfer_syntax_fallback {
+                Some(fallback) => {
+                  self.warnings.push(P10Warning::TransferSyntaxFallback {
+                    transfer_syntax_uid: uid.to_string(),
+                    fallback,
+                  });
+
+                  is_transfer_syntax_fallback = true;
+
+                  Ok(fallback)
+                }
+
+                None => Err(P10Error::TransferSyntaxNotSupported {
+                  transfer_syntax_uid: uid.to_string(),
+                }),
+              },
+            },
 
             Err(e) => {
               if e.is_tag_not_present() {
@@ -571,7 +795,9 @@ impl P10ReadContext {
       }
 
       // Set the final transfer syntax in the File Meta Information part
-      if self.transfer_syntax != &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN {
+      if self.transfer_syntax != &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN
+        || is_transfer_syntax_fallback
+      {
         fmi_data_set
           .insert_string_value(
             &dictionary::TRANSFER_SYNTAX_UID,
@@ -586,7 +812,9 @@ impl P10ReadContext {
 
       self.next_action = NextAction::ReadDataElementHeader;
 
-      Ok(vec![part])
+      parts.push(part);
+
+      Ok(())
     } else {
       unreachable!();
     }
@@ -594,7 +822,8 @@ impl P10ReadContext {
 
   fn read_data_element_header_part(
     &mut self,
-  ) -> Result<Vec<P10Part>, P10Error> {
+    parts: &mut Vec<P10Part>,
+  ) -> Result<(), P10Error> {
     // Read a data element header if bytes for one are available
     let header = self.read_data_element_header()?;
 
@@ -652,7 +881,9 @@ impl P10ReadContext {
 
         self.sequence_depth += 1;
 
-        Ok(vec![part])
+        parts.push(part);
+
+        Ok(())
       }
 
       // If this is the start of a new sequence item then add it to the location
@@ -680,7 +911,9 @@ impl P10ReadContext {
         let item_count = self.location.sequence_item_count().unwrap_or(1);
         self.path.add_sequence_item(item_count - 1).unwrap();
 
-        Ok(vec![part])
+        parts.push(part);
+
+        Ok(())
       }
 
       // If this is an encapsulated pixel data sequence then add it to the
@@ -704,9 +937,18 @@ impl P10ReadContext {
 
         self.path.add_data_element(tag).unwrap();
 
-        self.next_action = NextAction::ReadPixelDataItem { vr };
+        self.next_action = NextAction::ReadPixelDataItem {
+          vr,
+          value_bytes_handling: if self.is_value_skipped(tag) {
+            ValueBytesHandling::Skip
+          } else {
+            ValueBytesHandling::Emit
+          },
+        };
+
+        parts.push(part);
 
-        Ok(vec![part])
+        Ok(())
       }
 
       // If this is a sequence delimitation item then remove the current
@@ -714,23 +956,32 @@ impl P10ReadContext {
       (tag, None, ValueLength::ZERO)
         if tag == dictionary::SEQUENCE_DELIMITATION_ITEM.tag =>
       {
-        let parts = match self.location.end_sequence() {
+        // If a sequence delimiter occurs outside of a sequence then no error
+        // is returned and P10 parsing continues, unless well-formed data is
+        // required. This is done because rogue sequence delimiters have been
+        // observed in some DICOM P10 data, and not propagating an error right
+        // here doesn't do any harm and allows such data to be read.
+        match self.location.end_sequence() {
           Ok(()) => {
             self.path.pop().unwrap();
             self.sequence_depth -= 1;
 
-            vec![P10Part::SequenceDelimiter]
+            parts.push(P10Part::SequenceDelimiter);
           }
 
-          // If a sequence delimiter occurs outside of a sequence then no error
-          // is returned and P10 parsing continues. This is done because rogue
-          // sequence delimiters have been observed in some DICOM P10 data, and
-          // not propagating an error right here doesn't do any harm and allows
-          // such data to be read.
-          Err(_) => vec![],
-        };
+          Err(details) if self.config.require_well_formed => {
+            return Err(P10Error::DataInvalid {
+              when: "Reading data element header".to_string(),
+              details,
+              path: self.path.clone(),
+              offset: self.stream.bytes_read(),
+            });
+          }
 
-        Ok(parts)
+          Err(_) => (),
+        }
+
+        Ok(())
       }
 
       // If this is an item delimitation item then remove the latest item from
@@ -752,14 +1003,32 @@ impl P10ReadContext {
 
         self.path.pop().unwrap();
 
-        Ok(vec![part])
+        parts.push(part);
+
+        Ok(())
       }
 
       // For all other cases this is a standard data element that needs to have
       // its value bytes read
       (tag, Some(vr), ValueLength::Defined { length }) => {
-        let materialized_value_required =
-          self.is_materialized_value_required(header.tag, vr);
+        // Swallow the '(FFFC,FFFC) Data Set Trailing Padding' data element. No
+        // parts for it are emitted. Ref: PS3.10 7.2.
+        // Also swallow group length tags that have an element of 0x0000.
+        // Ref: PS3.5 7.2.
+        let value_bytes_handling = if header.tag
+          == dictionary::DATA_SET_TRAILING_PADDING.tag
+          || header.tag.element == 0x0000
+        {
+          ValueBytesHandling::Swallow
+        } else if self.is_value_skipped(header.tag) {
+          ValueBytesHandling::Skip
+        } else {
+          ValueBytesHandling::Emit
+        };
+
+        let materialized_value_required = value_bytes_handling
+          != ValueBytesHandling::Skip
+          && self.is_materialized_value_required(header.tag, vr);
 
         // If this data element needs to be fully materialized then check it
         // doesn't exceed the max string size
@@ -778,35 +1047,27 @@ impl P10ReadContext {
           });
         }
 
-        // Swallow the '(FFFC,FFFC) Data Set Trailing Padding' data element. No
-        // parts for it are emitted. Ref: PS3.10 7.2.
-        // Also swallow group length tags that have an element of 0x0000.
-        // Ref: PS3.5 7.2.
-        let emit_parts = header.tag
-          != dictionary::DATA_SET_TRAILING_PADDING.tag
-          && header.tag.element != 0x0000;
-
         // If the whole value is being materialized then the DataElementHeader
         // part is only emitted once all the data is available. This is
         // necessary because in the case of string values that are being
         // converted to UTF-8 the length of the final string value following
         // UTF-8 conversion is not yet known.
-        let parts = if emit_parts && !materialized_value_required {
-          vec![P10Part::DataElementHeader {
+        if value_bytes_handling != ValueBytesHandling::Swallow
+          && !materialized_value_required
+        {
+          parts.push(P10Part::DataElementHeader {
             tag: header.tag,
             vr,
             length,
-          }]
-        } else {
-          vec![]
-        };
+          });
+        }
 
         self.next_action = NextAction::ReadDataElementValueBytes {
           tag: header.tag,
           vr,
           length,
           bytes_remaining: length,
-          emit_parts,
+          value_bytes_handling,
         };
 
         // Add data element to the path
@@ -823,7 +1084,7 @@ impl P10ReadContext {
             offset: self.stream.bytes_read(),
           })?;
 
-        Ok(parts)
+        Ok(())
       }
 
       (_, _, _) => Err(P10Error::DataInvalid {
@@ -843,29 +1104,7 @@ impl P10ReadContext {
     &mut self,
   ) -> Result<DataElementHeader, P10Error> {
     let transfer_syntax = self.active_transfer_syntax();
-
-    // Peek the 4 bytes containing the tag
-    let tag = match self.stream.peek(4) {
-      Ok(data) => {
-        let (group, element) = match transfer_syntax.endianness {
-          transfer_syntax::Endianness::LittleEndian => (
-            byteorder::LittleEndian::read_u16(&data[0..2]),
-            byteorder::LittleEndian::read_u16(&data[2..4]),
-          ),
-
-          transfer_syntax::Endianness::BigEndian => (
-            byteorder::BigEndian::read_u16(&data[0..2]),
-            byteorder::BigEndian::read_u16(&data[2..4]),
-          ),
-        };
-
-        Ok(DataElementTag::new(group, element))
-      }
-
-      Err(e) => {
-        Err(self.map_byte_stream_error(e, "Reading data element header"))
-      }
-    }?;
+    let tag = self.peek_data_element_tag()?;
 
     // The item and delimitation tags always use implicit VRs
     let vr_serialization = if tag == dictionary::ITEM.tag
@@ -901,6 +1140,34 @@ impl P10ReadContext {
     }
   }
 
+  /// Peeks the tag of the next data element without consuming any bytes.
+  ///
+  fn peek_data_element_tag(&mut self) -> Result<DataElementTag, P10Error> {
+    let transfer_syntax = self.active_transfer_syntax();
+
+    match self.stream.peek(4) {
+      Ok(data) => {
+        let (group, element) = match transfer_syntax.endianness {
+          transfer_syntax::Endianness::LittleEndian => (
+            byteorder::LittleEndian::read_u16(&data[0..2]),
+            byteorder::LittleEndian::read_u16(&data[2..4]),
+          ),
+
+          transfer_syntax::Endianness::BigEndian => (
+            byteorder::BigEndian::read_u16(&data[0..2]),
+            byteorder::BigEndian::read_u16(&data[2..4]),
+          ),
+        };
+
+        Ok(DataElementTag::new(group, element))
+      }
+
+      Err(e) => {
+        Err(self.map_byte_stream_error(e, "Reading data element header"))
+      }
+    }
+  }
+
   /// Returns the transfer syntax that should be used to decode the current
   /// data. This will always be the transfer syntax specified in the File Meta
   /// Information, except in the case of 'Implicit VR Little Endian' being
@@ -1056,10 +1323,12 @@ impl P10ReadContext {
     vr: ValueRepresentation,
     value_length: u32,
     bytes_remaining: u32,
-    emit_parts: bool,
-  ) -> Result<Vec<P10Part>, P10Error> {
-    let materialized_value_required =
-      self.is_materialized_value_required(tag, vr);
+    value_bytes_handling: ValueBytesHandling,
+    parts: &mut Vec<P10Part>,
+  ) -> Result<(), P10Error> {
+    let materialized_value_required = value_bytes_handling
+      != ValueBytesHandling::Skip
+      && self.is_materialized_value_required(tag, vr);
 
     // If this data element value is being fully materialized then it needs to
     // be read as a whole, so use its full length as the number of bytes to
@@ -1074,7 +1343,9 @@ impl P10ReadContext {
       Ok(mut data) => {
         // Data element values are always returned in little endian, so if this
         // is a big endian transfer syntax then convert to little endian
-        if self.active_transfer_syntax().endianness.is_big() {
+        if value_bytes_handling == ValueBytesHandling::Emit
+          && self.active_transfer_syntax().endianness.is_big()
+        {
           vr.swap_endianness(&mut data);
         }
 
@@ -1086,11 +1357,19 @@ impl P10ReadContext {
           data
         };
 
-        let data = Rc::new(data);
-
-        let mut parts = Vec::with_capacity(2);
+        // Skipped values are discarded, and a single empty part is emitted once
+        // all of their value bytes have been read
+        if value_bytes_handling == ValueBytesHandling::Skip {
+          if bytes_remaining == 0 {
+            parts.push(P10Part::DataElementValueBytes {
+              vr,
+              data: Rc::new(vec![]),
+              bytes_remaining,
+            });
+          }
+        } else if value_bytes_handling == ValueBytesHandling::Emit {
+          let data = Rc::new(data);
 
-        if emit_parts {
           // If this is a materialized value then the data element header for it
           // is emitted now. It was not emitted when it was read due to the
           // possibility of the Value and Value Length being altered above.
@@ -1124,7 +1403,10 @@ impl P10ReadContext {
           // the next pixel data item if currently reading pixel data items, or
           // to read the header for the next data element
           if tag == dictionary::ITEM.tag {
-            NextAction::ReadPixelDataItem { vr }
+            NextAction::ReadPixelDataItem {
+              vr,
+              value_bytes_handling,
+            }
           } else {
             NextAction::ReadDataElementHeader
           }
@@ -1135,7 +1417,7 @@ impl P10ReadContext {
             vr,
             length: value_length,
             bytes_remaining,
-            emit_parts,
+            value_bytes_handling,
           }
         };
 
@@ -1145,7 +1427,7 @@ impl P10ReadContext {
 
         self.next_action = next_action;
 
-        Ok(parts)
+        Ok(())
       }
 
       Err(e) => {
@@ -1159,6 +1441,14 @@ impl P10ReadContext {
     }
   }
 
+  /// Returns whether the value bytes of the data element with the given tag are
+  /// configured to be skipped.
+  ///
+  fn is_value_skipped(&self, tag: DataElementTag) -> bool {
+    !p10_location::is_clarifying_data_element(tag)
+      && self.config.skip_value_tags.contains(&tag)
+  }
+
   fn is_materialized_value_required(
     &self,
     tag: DataElementTag,
@@ -1170,6 +1460,12 @@ impl P10ReadContext {
       return true;
     }
 
+    // Data elements configured to have their raw value bytes emitted aren't
+    // converted
+    if self.config.raw_value_tags.contains(&tag) {
+      return false;
+    }
+
     // If the value is a string, and it isn't UTF-8 data that can be passed
     // straight through, then materialize it so that it can be converted to
     // UTF-8.
@@ -1216,7 +1512,9 @@ impl P10ReadContext {
   fn read_pixel_data_item_part(
     &mut self,
     vr: ValueRepresentation,
-  ) -> Result<Vec<P10Part>, P10Error> {
+    value_bytes_handling: ValueBytesHandling,
+    parts: &mut Vec<P10Part>,
+  ) -> Result<(), P10Error> {
     match self.read_data_element_header() {
       Ok(header) => match header {
         // Pixel data items must have no VR and a defined length
@@ -1232,14 +1530,16 @@ impl P10ReadContext {
             vr,
             length,
             bytes_remaining: length,
-            emit_parts: true,
+            value_bytes_handling,
           };
 
           // Add item to the path
           let item_count = self.location.sequence_item_count().unwrap_or(1);
           self.path.add_sequence_item(item_count - 1).unwrap();
 
-          Ok(vec![part])
+          parts.push(part);
+
+          Ok(())
         }
 
         DataElementHeader {
@@ -1262,7 +1562,9 @@ impl P10ReadContext {
 
           self.next_action = NextAction::ReadDataElementHeader;
 
-          Ok(vec![part])
+          parts.push(part);
+
+          Ok(())
         }
 
         _ => Err(P10Error::DataInvalid {
@@ -1325,3 +1627,388 @@ impl Default for P10ReadContext {
     Self::new()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::internal::test_utils::{
+    p10_bytes_with_transfer_syntax_uid, read_all_parts, read_data_set,
+  };
+
+  #[test]
+  fn unknown_transfer_syntax_fallback_test() {
+    // File Meta Information with an unrecognized transfer syntax UID
+    let mut bytes = p10_bytes_with_transfer_syntax_uid("1.2.3.4");
+
+    // Implicit VR Little Endian '(0010,0020) Patient ID' data element
+    bytes.extend_from_slice(&[0x10, 0x00, 0x20, 0x00, 8, 0, 0, 0]);
+    bytes.extend_from_slice(b"12345678");
+
+    // Check the unrecognized transfer syntax errors by default
+    let mut context = P10ReadContext::new();
+    assert_eq!(
+      read_all_parts(&mut context, bytes.clone()),
+      Err(P10Error::TransferSyntaxNotSupported {
+        transfer_syntax_uid: "1.2.3.4".to_string()
+      })
+    );
+
+    // Check the fallback transfer syntax is used when configured
+    let mut context = P10ReadContext::new();
+    context.set_config(&P10ReadConfig {
+      unknown_transfer_syntax_fallback: Some(
+        &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN,
+      ),
+      ..P10ReadConfig::default()
+    });
+
+    let data_set = read_data_set(&mut context, bytes).unwrap();
+
+    assert_eq!(
+      context.warnings(),
+      [P10Warning::TransferSyntaxFallback {
+        transfer_syntax_uid: "1.2.3.4".to_string(),
+        fallback: &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN,
+      }]
+    );
+    assert_eq!(
+      data_set.get_string(dictionary::PATIENT_ID.tag),
+      Ok("12345678")
+    );
+    assert_eq!(
+      data_set.get_transfer_syntax(),
+      Ok(&transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN)
+    );
+  }
+
+  #[test]
+  fn raw_value_tags_test() {
+    let mut bytes = p10_bytes_with_transfer_syntax_uid(
+      transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN.uid,
+    );
+
+    // '(0008,0005) Specific Character Set' of 'ISO_IR 100', i.e. Latin-1
+    bytes.extend_from_slice(&[0x08, 0x00, 0x05, 0x00, 10, 0, 0, 0]);
+    bytes.extend_from_slice(b"ISO_IR 100");
+
+    // '(0010,0010) Patient's Name' and '(0010,0020) Patient ID' data elements
+    // that contain a Latin-1 character
+    bytes.extend_from_slice(&[0x10, 0x00, 0x10, 0x00, 4, 0, 0, 0]);
+    bytes.extend_from_slice(b"Caf\xE9");
+    bytes.extend_from_slice(&[0x10, 0x00, 0x20, 0x00, 4, 0, 0, 0]);
+    bytes.extend_from_slice(b"Caf\xE9");
+
+    let mut context = P10ReadContext::new();
+    context.set_config(&P10ReadConfig {
+      raw_value_tags: vec![dictionary::PATIENT_NAME.tag],
+      ..P10ReadConfig::default()
+    });
+    let data_set = read_data_set(&mut context, bytes).unwrap();
+
+    // The listed data element's bytes are preserved, while the other one is
+    // converted to UTF-8
+    assert_eq!(
+      data_set
+        .get_value_bytes(
+          dictionary::PATIENT_NAME.tag,
+          ValueRepresentation::PersonName
+        )
+        .map(|bytes| bytes.as_slice()),
+      Ok(&b"Caf\xE9"[..])
+    );
+    assert_eq!(data_set.get_string(dictionary::PATIENT_ID.tag), Ok("Café"));
+  }
+
+  #[test]
+  fn multi_valued_specific_character_set_test() {
+    // This file's '(0008,0005) Specific Character Set' has the two values ''
+    // and 'ISO 2022 IR 87', i.e. ISO 646 with JIS X 0208 code extensions
+    let filename = concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/../../../test/assets/pydicom/charset_files/chrH31.dcm"
+    );
+
+    let mut context = P10ReadContext::new();
+    let data_set =
+      read_data_set(&mut context, std::fs::read(filename).unwrap()).unwrap();
+
+    assert_eq!(
+      data_set.get_string(dictionary::SPECIFIC_CHARACTER_SET.tag),
+      Ok("ISO_IR 192")
+    );
+
+    let patient_name = data_set
+      .get_person_name(dictionary::PATIENT_NAME.tag)
+      .unwrap();
+    assert_eq!(
+      patient_name.alphabetic.as_ref().unwrap().last_name,
+      "Yamada"
+    );
+    assert_eq!(patient_name.ideographic.as_ref().unwrap().last_name, "山田");
+    assert_eq!(
+      patient_name.ideographic.as_ref().unwrap().first_name,
+      "太郎"
+    );
+  }
+
+  #[test]
+  fn stop_after_tag_test() {
+    let filename =
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm");
+
+    let mut context = P10ReadContext::new();
+    context.set_config(&P10ReadConfig {
+      stop_after_tag: Some(dictionary::STUDY_DATE.tag),
+      ..P10ReadConfig::default()
+    });
+    let data_set =
+      read_data_set(&mut context, std::fs::read(filename).unwrap()).unwrap();
+
+    // Only the data elements up to and including the stop tag are read
+    let full_data_set = crate::read_file(filename).unwrap();
+    let expected_tags: Vec<DataElementTag> = full_data_set
+      .tags()
+      .into_iter()
+      .filter(|tag| *tag <= dictionary::STUDY_DATE.tag)
+      .collect();
+
+    assert_eq!(data_set.tags(), expected_tags);
+    assert_eq!(
+      data_set.get_value(dictionary::STUDY_DATE.tag),
+      full_data_set.get_value(dictionary::STUDY_DATE.tag)
+    );
+    assert!(!data_set.has(dictionary::PIXEL_DATA.tag));
+
+    // Further reads continue to end at the same point
+    assert_eq!(context.read_parts(), Ok(vec![P10Part::End]));
+  }
+
+  #[test]
+  fn skip_value_tags_test() {
+    for filename in [
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm"),
+      concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../../test/assets/pydicom/test_files/JPEG-lossy.dcm"
+      ),
+    ] {
+      let mut context = P10ReadContext::new();
+      context.set_config(&P10ReadConfig {
+        max_part_size: 1024,
+        skip_value_tags: vec![dictionary::PIXEL_DATA.tag],
+        ..P10ReadConfig::default()
+      });
+      let parts =
+        read_all_parts(&mut context, std::fs::read(filename).unwrap()).unwrap();
+
+      let full_data_set = crate::read_file(filename).unwrap();
+      let pixel_data =
+        full_data_set.get_value(dictionary::PIXEL_DATA.tag).unwrap();
+
+      // The original value lengths are emitted, each followed by a single
+      // empty value bytes part
+      let expected_lengths: Vec<u32> =
+        match pixel_data.encapsulated_pixel_data() {
+          Ok(items) => items.iter().map(|item| item.len() as u32).collect(),
+          Err(_) => vec![pixel_data.bytes().unwrap().len() as u32],
+        };
+
+      let pixel_data_parts = parts
+        .iter()
+        .skip_while(|part| {
+          !matches!(part,
+            P10Part::DataElementHeader { tag, .. }
+            | P10Part::SequenceStart { tag, .. }
+            if *tag == dictionary::PIXEL_DATA.tag)
+        })
+        .collect::<Vec<_>>();
+
+      let lengths: Vec<u32> = pixel_data_parts
+        .windows(2)
+        .filter_map(|window| match window {
+          [P10Part::DataElementHeader { length, .. }
+          | P10Part::PixelDataItem { length }, P10Part::DataElementValueBytes {
+            data,
+            bytes_remaining: 0,
+            ..
+          }] if data.is_empty() => Some(*length),
+          _ => None,
+        })
+        .collect();
+
+      assert_eq!(lengths, expected_lengths);
+      assert!(!pixel_data_parts.iter().any(|part| matches!(
+        part,
+        P10Part::DataElementValueBytes { data, .. } if !data.is_empty()
+      )));
+    }
+  }
+
+  #[test]
+  fn bytes_read_test() {
+    let bytes = std::fs::read(concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/../../../examples/example.dcm"
+    ))
+    .unwrap();
+    let bytes_len = bytes.len() as u64;
+
+    let mut context = P10ReadContext::new();
+    context.set_config(&P10ReadConfig {
+      max_part_size: 1024,
+      ..P10ReadConfig::default()
+    });
+    assert_eq!(context.bytes_read(), 0);
+
+    // The number of bytes read is the total length once the end is reached
+    read_all_parts(&mut context, bytes.clone()).unwrap();
+    assert_eq!(context.bytes_read(), bytes_len);
+
+    // The number of bytes read increases as parts are read
+    context.reset();
+    context.write_bytes(bytes, true).unwrap();
+    let mut bytes_read = 0;
+    while context.read_parts().unwrap().last() != Some(&P10Part::End) {
+      assert!(context.bytes_read() >= bytes_read);
+      bytes_read = context.bytes_read();
+    }
+
+    context.reset();
+    assert_eq!(context.bytes_read(), 0);
+  }
+
+  #[test]
+  fn require_well_formed_test() {
+    let read = |bytes: &[u8], require_well_formed: bool| {
+      let mut context = P10ReadContext::new();
+      context.set_config(&P10ReadConfig {
+        require_well_formed,
+        ..P10ReadConfig::default()
+      });
+
+      read_all_parts(&mut context, bytes.to_vec())
+    };
+
+    let details = |result: Result<Vec<P10Part>, P10Error>| match result {
+      Err(P10Error::DataInvalid { details, .. }) => details,
+      _ => panic!("Expected a data invalid error"),
+    };
+
+    // Well-formed data is read successfully
+    for filename in [
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm"),
+      concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../../test/assets/pydicom/test_files/rtplan.dcm"
+      ),
+    ] {
+      assert!(read(&std::fs::read(filename).unwrap(), true).is_ok());
+    }
+
+    let bytes = p10_bytes_with_transfer_syntax_uid(
+      transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN.uid,
+    );
+
+    // A sequence delimiter outside of a sequence
+    let mut rogue_delimiter_bytes = bytes.clone();
+    rogue_delimiter_bytes
+      .extend_from_slice(&[0xFE, 0xFF, 0xDD, 0xE0, 0, 0, 0, 0]);
+
+    assert!(read(&rogue_delimiter_bytes, false).is_ok());
+    assert_eq!(
+      details(read(&rogue_delimiter_bytes, true)),
+      "Sequence delimiter encountered outside of a sequence"
+    );
+
+    // An undefined length '(0008,1140) Referenced Image Sequence' with an item
+    // that is never ended
+    let mut truncated_bytes = bytes.clone();
+    truncated_bytes
+      .extend_from_slice(&[0x08, 0x00, 0x40, 0x11, 0xFF, 0xFF, 0xFF, 0xFF]);
+    truncated_bytes
+      .extend_from_slice(&[0xFE, 0xFF, 0x00, 0xE0, 0xFF, 0xFF, 0xFF, 0xFF]);
+    truncated_bytes.extend_from_slice(&[0x08, 0x00, 0x50, 0x11, 4, 0, 0, 0]);
+    truncated_bytes.extend_from_slice(b"1.2\0");
+
+    assert!(read(&truncated_bytes, false).is_ok());
+    assert_eq!(
+      details(read(&truncated_bytes, true)),
+      "DICOM P10 data ended while a sequence or item was still open"
+    );
+  }
+
+  #[test]
+  fn read_parts_into_test() {
+    let mut bytes = p10_bytes_with_transfer_syntax_uid(
+      transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN.uid,
+    );
+
+    // Implicit VR Little Endian '(0010,0020) Patient ID' data element
+    bytes.extend_from_slice(&[0x10, 0x00, 0x20, 0x00, 8, 0, 0, 0]);
+    bytes.extend_from_slice(b"12345678");
+
+    let expected_parts =
+      read_all_parts(&mut P10ReadContext::new(), bytes.clone()).unwrap();
+
+    // Read the same data several times, reusing a single buffer across all
+    // calls to read_parts_into()
+    let mut buffer = Vec::with_capacity(16);
+    for _ in 0..3 {
+      let mut context = P10ReadContext::new();
+      context.write_bytes(bytes.clone(), true).unwrap();
+
+      let mut parts = vec![];
+      while parts.last() != Some(&P10Part::End) {
+        context.read_parts_into(&mut buffer).unwrap();
+        parts.extend_from_slice(&buffer);
+      }
+
+      assert_eq!(parts, expected_parts);
+    }
+
+    // Check the buffer is cleared before parts are added to it, and that it is
+    // left empty on error
+    let mut context = P10ReadContext::new();
+    buffer.push(P10Part::End);
+    assert!(matches!(
+      context.read_parts_into(&mut buffer),
+      Err(P10Error::DataRequired { .. })
+    ));
+    assert!(buffer.is_empty());
+  }
+
+  #[test]
+  fn reset_test() {
+    let filenames = [
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm"),
+      concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../../test/assets/pydicom/charset_files/chrGerm.dcm"
+      ),
+    ];
+
+    let config = P10ReadConfig {
+      max_part_size: 1024,
+      ..P10ReadConfig::default()
+    };
+
+    let mut context = P10ReadContext::new();
+    context.set_config(&config);
+
+    // Leave a partially completed read in the context prior to the first reset
+    let bytes = std::fs::read(filenames[0]).unwrap();
+    context.write_bytes(bytes[0..1000].to_vec(), false).unwrap();
+    context.read_parts().unwrap();
+
+    for filename in filenames {
+      context.reset();
+      assert_eq!(context.config.max_part_size, 1024);
+
+      assert_eq!(
+        read_data_set(&mut context, std::fs::read(filename).unwrap()),
+        Ok(crate::read_file(filename).unwrap())
+      );
+    }
+  }
+}
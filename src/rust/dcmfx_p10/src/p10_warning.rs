@@ -0,0 +1,35 @@
+//! Defines the type used to describe non-fatal issues that can occur when
+//! reading DICOM P10 data.
+
+use dcmfx_core::TransferSyntax;
+
+/// A non-fatal issue found while reading DICOM P10 data. Reading continues
+/// after a warning, but the data read may not be what the creator of the DICOM
+/// P10 data intended.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum P10Warning {
+  /// The File Meta Information specifies a transfer syntax UID that isn't
+  /// recognized, and so the configured fallback transfer syntax is used to
+  /// read the rest of the DICOM P10 data. See
+  /// [`crate::P10ReadConfig::unknown_transfer_syntax_fallback`].
+  TransferSyntaxFallback {
+    transfer_syntax_uid: String,
+    fallback: &'static TransferSyntax,
+  },
+}
+
+impl std::fmt::Display for P10Warning {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      P10Warning::TransferSyntaxFallback {
+        transfer_syntax_uid,
+        fallback,
+      } => write!(
+        f,
+        "Transfer syntax '{}' is not supported, reading as '{}'",
+        transfer_syntax_uid, fallback.name
+      ),
+    }
+  }
+}
@@ -8,7 +8,7 @@ use byteorder::ByteOrder;
 use dcmfx_core::DataSetPath;
 use dcmfx_core::{
   dictionary, transfer_syntax, transfer_syntax::Endianness, DataElementTag,
-  DataElementValue, DataSet, TransferSyntax,
+  DataElementValue, DataError, DataSet, TransferSyntax, ValueRepresentation,
 };
 
 use crate::{
@@ -26,7 +26,7 @@ const ZLIB_DEFLATE_CHUNK_SIZE: usize = 64 * 1024;
 
 /// Configuration used when writing DICOM P10 data.
 ///
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct P10WriteConfig {
   /// The zlib compression level to use when the transfer syntax being used is
   /// deflated. There are only three deflated transfer syntaxes: 'Deflated
@@ -38,16 +38,88 @@ pub struct P10WriteConfig {
   ///
   /// Default: 6.
   pub zlib_compression_level: u32,
+
+  /// Whether sequences and sequence items are written with defined lengths or
+  /// with undefined lengths that are terminated by delimiter items.
+  ///
+  /// Encapsulated pixel data is always written with an undefined length as
+  /// required by the DICOM standard.
+  ///
+  /// Default: [`SequenceLengthMode::Undefined`].
+  pub sequence_length_mode: SequenceLengthMode,
+
+  /// Whether to write a *'(gggg,0000) Group Length'* data element at the start
+  /// of each group of root-level data elements. The group lengths are computed
+  /// from the bytes written for the active transfer syntax, prior to any
+  /// deflate compression.
+  ///
+  /// This requires the bytes of each group to be buffered in memory until the
+  /// group is complete so that its length is known. The part stream being
+  /// written should not contain any group length data elements of its own.
+  ///
+  /// Default: `false`.
+  pub write_group_lengths: bool,
+
+  /// The *'(0002,0012) Implementation Class UID'* that is written into the
+  /// File Meta Information if it doesn't already specify one.
+  ///
+  /// Default: [`uids::DCMFX_IMPLEMENTATION_CLASS_UID`].
+  pub implementation_class_uid: String,
+
+  /// The *'(0002,0013) Implementation Version Name'* that is written into the
+  /// File Meta Information if it doesn't already specify one.
+  ///
+  /// Default: [`uids::DCMFX_IMPLEMENTATION_VERSION_NAME`].
+  pub implementation_version_name: String,
 }
 
 impl Default for P10WriteConfig {
   fn default() -> Self {
     Self {
       zlib_compression_level: 6,
+      sequence_length_mode: SequenceLengthMode::Undefined,
+      write_group_lengths: false,
+      implementation_class_uid: uids::DCMFX_IMPLEMENTATION_CLASS_UID
+        .to_string(),
+      implementation_version_name: uids::DCMFX_IMPLEMENTATION_VERSION_NAME
+        .clone(),
     }
   }
 }
 
+/// Specifies how the lengths of sequences and sequence items are written.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SequenceLengthMode {
+  /// Sequences and items are written with defined lengths and no delimiters.
+  /// This requires the content of each sequence to be buffered in memory until
+  /// it is complete so that its length is known.
+  Defined,
+
+  /// Sequences and items are written with undefined lengths, and are
+  /// terminated by a *'(FFFE,E0DD) Sequence Delimitation Item'* or
+  /// *'(FFFE,E00D) Item Delimitation Item'* respectively.
+  Undefined,
+}
+
+/// A sequence or sequence item that is being written with a defined length.
+/// Its bytes are accumulated until it ends, at which point its length is known
+/// and its header can be generated.
+///
+struct DefinedLengthEntry {
+  sequence: Option<(DataElementTag, ValueRepresentation)>,
+  bytes: Vec<u8>,
+}
+
+/// The state of encapsulated pixel data that is being written one frame at a
+/// time using [`P10WriteContext::write_encapsulated_pixel_data_frame()`].
+///
+struct EncapsulatedPixelDataWrite {
+  vr: ValueRepresentation,
+  frame_lengths: Option<Vec<usize>>,
+  frame_count: usize,
+}
+
 /// A write context holds the current state of an in-progress DICOM P10 write.
 /// DICOM P10 parts are written to a write context with [`Self::write_part()`],
 /// and output P10 bytes are returned by [`Self::read_bytes()`].
@@ -61,6 +133,10 @@ pub struct P10WriteContext {
   zlib_stream: Option<flate2::Compress>,
   path: DataSetPath,
   sequence_item_counts: Vec<usize>,
+  defined_length_entries: Vec<DefinedLengthEntry>,
+  group_length_group: Option<u16>,
+  group_length_bytes: Vec<u8>,
+  encapsulated_pixel_data: Option<EncapsulatedPixelDataWrite>,
 }
 
 impl P10WriteContext {
@@ -76,6 +152,10 @@ impl P10WriteContext {
       zlib_stream: None,
       path: DataSetPath::new(),
       sequence_item_counts: vec![],
+      defined_length_entries: vec![],
+      group_length_group: None,
+      group_length_bytes: vec![],
+      encapsulated_pixel_data: None,
     }
   }
 
@@ -85,6 +165,13 @@ impl P10WriteContext {
     // Clamp zlib compression level to the valid range
     self.config.zlib_compression_level =
       config.zlib_compression_level.clamp(0, 9);
+
+    self.config.sequence_length_mode = config.sequence_length_mode;
+    self.config.write_group_lengths = config.write_group_lengths;
+    self.config.implementation_class_uid =
+      config.implementation_class_uid.clone();
+    self.config.implementation_version_name =
+      config.implementation_version_name.clone();
   }
 
   /// Reads the current DICOM P10 bytes available out of a write context. These
@@ -98,7 +185,25 @@ impl P10WriteContext {
   /// context is returned. Use [`Self::read_bytes()`] to get the new DICOM P10
   /// bytes generated as a result of writing this part.
   ///
+  /// Parts can't be written while encapsulated pixel data started by
+  /// [`Self::write_encapsulated_pixel_data_start()`] is being written, as they
+  /// would be interleaved with its frames.
+  ///
   pub fn write_part(&mut self, part: &P10Part) -> Result<(), P10Error> {
+    if self.encapsulated_pixel_data.is_some() {
+      return Err(P10Error::PartStreamInvalid {
+        when: "Writing DICOM P10 part".to_string(),
+        details: "Received a DICOM P10 part while encapsulated pixel data is \
+          being written"
+          .to_string(),
+        part: part.clone(),
+      });
+    }
+
+    self.do_write_part(part)
+  }
+
+  fn do_write_part(&mut self, part: &P10Part) -> Result<(), P10Error> {
     if self.is_ended {
       return Err(P10Error::PartStreamInvalid {
         when: "Writing DICOM P10 part".to_string(),
@@ -148,6 +253,8 @@ impl P10WriteContext {
       // When the end part is received, update the flag on the write context and
       // flush all remaining data out of the zlib stream if one is in use
       P10Part::End => {
+        self.write_group_length_bytes()?;
+
         if let Some(zlib_stream) = self.zlib_stream.as_mut() {
           loop {
             let mut output = vec![0u8; ZLIB_DEFLATE_CHUNK_SIZE];
@@ -188,6 +295,19 @@ impl P10WriteContext {
       }
 
       _ => {
+        // When writing group lengths, the group currently being written is
+        // complete when a root-level data element in a different group starts
+        if self.config.write_group_lengths && self.path.is_empty() {
+          if let P10Part::DataElementHeader { tag, .. }
+          | P10Part::SequenceStart { tag, .. } = part
+          {
+            if self.group_length_group != Some(tag.group) {
+              self.write_group_length_bytes()?;
+              self.group_length_group = Some(tag.group);
+            }
+          }
+        }
+
         // Update the current path
         match part {
           P10Part::DataElementHeader { tag, .. } => {
@@ -238,47 +358,403 @@ impl P10WriteContext {
           part: part.clone(),
         })?;
 
-        // If a zlib stream is active then pass the P10 bytes through it
-        if let Some(zlib_stream) = self.zlib_stream.as_mut() {
-          let mut part_bytes_remaining = &part_bytes[..];
+        // When writing defined-length sequences, accumulate the bytes of
+        // sequences and items until their length is known
+        let part_bytes = match self.config.sequence_length_mode {
+          SequenceLengthMode::Defined => {
+            match self.accumulate_defined_length_bytes(part, part_bytes)? {
+              Some(part_bytes) => part_bytes,
+              None => return Ok(()),
+            }
+          }
+          SequenceLengthMode::Undefined => part_bytes,
+        };
 
-          while !part_bytes_remaining.is_empty() {
-            let mut output = vec![0u8; ZLIB_DEFLATE_CHUNK_SIZE];
+        // When writing group lengths, accumulate the bytes of the current
+        // group until its length is known
+        if self.group_length_group.is_some() {
+          self.group_length_bytes.extend_from_slice(&part_bytes);
+          return Ok(());
+        }
 
-            // Add bytes to the zlib compressor and read back any compressed
-            // data
-            let total_in = zlib_stream.total_in();
-            let total_out = zlib_stream.total_out();
-            zlib_stream
-              .compress(
-                part_bytes_remaining,
-                &mut output,
-                flate2::FlushCompress::None,
-              )
-              .unwrap();
-            output.resize((zlib_stream.total_out() - total_out) as usize, 0u8);
+        self.output_bytes(part_bytes);
 
-            if !output.is_empty() {
-              self.p10_total_byte_count += output.len() as u64;
-              self.p10_bytes.push(Rc::new(output));
-            }
+        Ok(())
+      }
+    }
+  }
 
-            let input_bytes_consumed =
-              (zlib_stream.total_in() - total_in) as usize;
-            if input_bytes_consumed == 0 {
-              panic!("zlib compressor did not consume any bytes");
-            }
+  /// Starts writing encapsulated *'(7FE0,0010) Pixel Data'* that will have its
+  /// frames supplied one at a time by calling
+  /// [`Self::write_encapsulated_pixel_data_frame()`], followed by a call to
+  /// [`Self::write_encapsulated_pixel_data_end()`]. This allows frames to be
+  /// written as they are produced, e.g. by a transcoder, without assembling
+  /// the whole encapsulated value in memory.
+  ///
+  /// If the lengths of the frames are known up front then a basic offset table
+  /// is written that locates each frame, and the frames subsequently written
+  /// must have exactly these lengths. Otherwise, the basic offset table is
+  /// written empty.
+  ///
+  /// Each frame is written as a single fragment, and is padded with a zero
+  /// byte if its length is odd.
+  ///
+  pub fn write_encapsulated_pixel_data_start(
+    &mut self,
+    vr: ValueRepresentation,
+    frame_lengths: Option<&[usize]>,
+  ) -> Result<(), P10Error> {
+    let sequence_start = P10Part::SequenceStart {
+      tag: dictionary::PIXEL_DATA.tag,
+      vr,
+    };
 
-            part_bytes_remaining =
-              &part_bytes_remaining[input_bytes_consumed..];
-          }
-        } else {
-          self.p10_total_byte_count += part_bytes.len() as u64;
-          self.p10_bytes.push(part_bytes);
+    if self.encapsulated_pixel_data.is_some() {
+      return Err(P10Error::PartStreamInvalid {
+        when: "Starting encapsulated pixel data".to_string(),
+        details: "Encapsulated pixel data is already being written".to_string(),
+        part: sequence_start,
+      });
+    }
+
+    // Construct the basic offset table from the frame lengths, taking into
+    // account each fragment's item header and padding
+    let mut basic_offset_table = vec![];
+    if let Some(frame_lengths) = frame_lengths {
+      let mut offset = 0u64;
+
+      for frame_length in frame_lengths {
+        let offset_u32 =
+          u32::try_from(offset).map_err(|_| P10Error::DataInvalid {
+            when: "Serializing basic offset table".to_string(),
+            details: "Encapsulated pixel data exceeds the 4 GiB that can be \
+              addressed by the basic offset table"
+              .to_string(),
+            path: self.path.clone(),
+            offset: self.p10_total_byte_count,
+          })?;
+
+        basic_offset_table.extend_from_slice(&offset_u32.to_le_bytes());
+
+        offset += 8 + frame_length.next_multiple_of(2) as u64;
+      }
+    }
+
+    self.do_write_part(&sequence_start)?;
+    self.do_write_part(&P10Part::PixelDataItem {
+      length: basic_offset_table.len() as u32,
+    })?;
+    self.do_write_part(&P10Part::DataElementValueBytes {
+      vr,
+      data: Rc::new(basic_offset_table),
+      bytes_remaining: 0,
+    })?;
+
+    self.encapsulated_pixel_data = Some(EncapsulatedPixelDataWrite {
+      vr,
+      frame_lengths: frame_lengths.map(|lengths| lengths.to_vec()),
+      frame_count: 0,
+    });
+
+    Ok(())
+  }
+
+  /// Writes the next frame of encapsulated pixel data started by
+  /// [`Self::write_encapsulated_pixel_data_start()`].
+  ///
+  pub fn write_encapsulated_pixel_data_frame(
+    &mut self,
+    frame: &[u8],
+  ) -> Result<(), P10Error> {
+    let mut data = frame.to_vec();
+    if data.len() % 2 == 1 {
+      data.push(0);
+    }
+
+    let length =
+      u32::try_from(data.len()).map_err(|_| P10Error::DataInvalid {
+        when: "Writing encapsulated pixel data frame".to_string(),
+        details: format!(
+          "Frame length {} exceeds the maximum of 2^32 - 1 bytes",
+          data.len()
+        ),
+        path: self.path.clone(),
+        offset: self.p10_total_byte_count,
+      })?;
+
+    let item_part = P10Part::PixelDataItem { length };
+
+    let pixel_data = match self.encapsulated_pixel_data.as_mut() {
+      Some(pixel_data) => pixel_data,
+      None => {
+        return Err(P10Error::PartStreamInvalid {
+          when: "Writing encapsulated pixel data frame".to_string(),
+          details: "Encapsulated pixel data has not been started".to_string(),
+          part: item_part,
+        })
+      }
+    };
+
+    // Check the frame matches the length used in the basic offset table
+    if let Some(frame_lengths) = pixel_data.frame_lengths.as_ref() {
+      let expected_length = frame_lengths.get(pixel_data.frame_count);
+
+      if expected_length != Some(&frame.len()) {
+        return Err(P10Error::DataInvalid {
+          when: "Writing encapsulated pixel data frame".to_string(),
+          details: format!(
+            "Frame {} has length {} which does not match the basic offset \
+             table",
+            pixel_data.frame_count,
+            frame.len()
+          ),
+          path: self.path.clone(),
+          offset: self.p10_total_byte_count,
+        });
+      }
+    }
+
+    pixel_data.frame_count += 1;
+    let vr = pixel_data.vr;
+
+    self.do_write_part(&item_part)?;
+    self.do_write_part(&P10Part::DataElementValueBytes {
+      vr,
+      data: Rc::new(data),
+      bytes_remaining: 0,
+    })
+  }
+
+  /// Ends the encapsulated pixel data started by
+  /// [`Self::write_encapsulated_pixel_data_start()`]. If frame lengths were
+  /// specified then all of those frames must have been written.
+  ///
+  pub fn write_encapsulated_pixel_data_end(&mut self) -> Result<(), P10Error> {
+    let pixel_data = match self.encapsulated_pixel_data.take() {
+      Some(pixel_data) => pixel_data,
+      None => {
+        return Err(P10Error::PartStreamInvalid {
+          when: "Ending encapsulated pixel data".to_string(),
+          details: "Encapsulated pixel data has not been started".to_string(),
+          part: P10Part::SequenceDelimiter,
+        })
+      }
+    };
+
+    if let Some(frame_lengths) = pixel_data.frame_lengths {
+      if pixel_data.frame_count != frame_lengths.len() {
+        return Err(P10Error::DataInvalid {
+          when: "Ending encapsulated pixel data".to_string(),
+          details: format!(
+            "Only {} of the {} frames in the basic offset table were written",
+            pixel_data.frame_count,
+            frame_lengths.len()
+          ),
+          path: self.path.clone(),
+          offset: self.p10_total_byte_count,
+        });
+      }
+    }
+
+    self.do_write_part(&P10Part::SequenceDelimiter)
+  }
+
+  /// Outputs the bytes of the group currently being written, preceded by a
+  /// *'(gggg,0000) Group Length'* data element that holds their length. Does
+  /// nothing if no group is being written.
+  ///
+  fn write_group_length_bytes(&mut self) -> Result<(), P10Error> {
+    let group = match self.group_length_group.take() {
+      Some(group) => group,
+      None => return Ok(()),
+    };
+
+    let group_bytes = std::mem::take(&mut self.group_length_bytes);
+
+    let length =
+      u32::try_from(group_bytes.len()).map_err(|_| P10Error::DataInvalid {
+        when: "Serializing group length".to_string(),
+        details: format!(
+          "Length {} exceeds the maximum of 2^32 - 1 bytes",
+          group_bytes.len()
+        ),
+        path: self.path.clone(),
+        offset: self.p10_total_byte_count,
+      })?;
+
+    let vr = match self.transfer_syntax.vr_serialization {
+      transfer_syntax::VrSerialization::VrExplicit => {
+        Some(ValueRepresentation::UnsignedLong)
+      }
+      transfer_syntax::VrSerialization::VrImplicit => None,
+    };
+
+    let header_bytes = self.data_element_header_to_bytes(
+      &DataElementHeader {
+        tag: DataElementTag::new(group, 0),
+        vr,
+        length: ValueLength::new(4),
+      },
+      self.transfer_syntax.endianness,
+    )?;
+
+    let mut bytes =
+      Vec::with_capacity(header_bytes.len() + 4 + group_bytes.len());
+    bytes.extend_from_slice(&header_bytes);
+    match self.transfer_syntax.endianness {
+      Endianness::LittleEndian => {
+        bytes.extend_from_slice(length.to_le_bytes().as_slice())
+      }
+      Endianness::BigEndian => {
+        bytes.extend_from_slice(length.to_be_bytes().as_slice())
+      }
+    }
+    bytes.extend_from_slice(&group_bytes);
+
+    self.output_bytes(Rc::new(bytes));
+
+    Ok(())
+  }
+
+  /// Outputs P10 bytes, passing them through the zlib stream if one is active.
+  ///
+  fn output_bytes(&mut self, part_bytes: Rc<Vec<u8>>) {
+    // If a zlib stream is active then pass the P10 bytes through it
+    if let Some(zlib_stream) = self.zlib_stream.as_mut() {
+      let mut part_bytes_remaining = &part_bytes[..];
+
+      while !part_bytes_remaining.is_empty() {
+        let mut output = vec![0u8; ZLIB_DEFLATE_CHUNK_SIZE];
+
+        // Add bytes to the zlib compressor and read back any compressed
+        // data
+        let total_in = zlib_stream.total_in();
+        let total_out = zlib_stream.total_out();
+        zlib_stream
+          .compress(
+            part_bytes_remaining,
+            &mut output,
+            flate2::FlushCompress::None,
+          )
+          .unwrap();
+        output.resize((zlib_stream.total_out() - total_out) as usize, 0u8);
+
+        if !output.is_empty() {
+          self.p10_total_byte_count += output.len() as u64;
+          self.p10_bytes.push(Rc::new(output));
         }
 
-        Ok(())
+        let input_bytes_consumed = (zlib_stream.total_in() - total_in) as usize;
+        if input_bytes_consumed == 0 {
+          panic!("zlib compressor did not consume any bytes");
+        }
+
+        part_bytes_remaining = &part_bytes_remaining[input_bytes_consumed..];
       }
+    } else {
+      self.p10_total_byte_count += part_bytes.len() as u64;
+      self.p10_bytes.push(part_bytes);
+    }
+  }
+
+  /// Accumulates the bytes for a part into the innermost defined-length
+  /// sequence or item that is currently being written. When a sequence or item
+  /// ends, its header is generated using the final length and its complete
+  /// bytes are added to its parent.
+  ///
+  /// Returns the bytes that are ready to be output, which only occurs when
+  /// there is no defined-length sequence being written.
+  ///
+  fn accumulate_defined_length_bytes(
+    &mut self,
+    part: &P10Part,
+    mut part_bytes: Rc<Vec<u8>>,
+  ) -> Result<Option<Rc<Vec<u8>>>, P10Error> {
+    let top_is_sequence = self
+      .defined_length_entries
+      .last()
+      .map(|entry| entry.sequence.is_some());
+
+    match (part, top_is_sequence) {
+      // Start a new defined-length sequence. Encapsulated pixel data is not
+      // included here as it must always have an undefined length.
+      (P10Part::SequenceStart { tag, vr }, _)
+        if *vr == ValueRepresentation::Sequence =>
+      {
+        self.defined_length_entries.push(DefinedLengthEntry {
+          sequence: Some((*tag, *vr)),
+          bytes: vec![],
+        });
+
+        return Ok(None);
+      }
+
+      (P10Part::SequenceItemStart, Some(true)) => {
+        self.defined_length_entries.push(DefinedLengthEntry {
+          sequence: None,
+          bytes: vec![],
+        });
+
+        return Ok(None);
+      }
+
+      (P10Part::SequenceDelimiter, Some(true))
+      | (P10Part::SequenceItemDelimiter, Some(false)) => {
+        let entry = self.defined_length_entries.pop().unwrap();
+
+        let length = u32::try_from(entry.bytes.len())
+          .ok()
+          .filter(|length| *length != ValueLength::Undefined.to_u32())
+          .ok_or_else(|| P10Error::DataInvalid {
+            when: "Serializing defined-length sequence".to_string(),
+            details: format!(
+              "Length {} exceeds the maximum of 2^32 - 2 bytes",
+              entry.bytes.len()
+            ),
+            path: self.path.clone(),
+            offset: self.p10_total_byte_count,
+          })?;
+
+        let header = match entry.sequence {
+          Some((tag, vr)) => DataElementHeader {
+            tag,
+            vr: match self.transfer_syntax.vr_serialization {
+              transfer_syntax::VrSerialization::VrExplicit => Some(vr),
+              transfer_syntax::VrSerialization::VrImplicit => None,
+            },
+            length: ValueLength::new(length),
+          },
+
+          None => DataElementHeader {
+            tag: dictionary::ITEM.tag,
+            vr: None,
+            length: ValueLength::new(length),
+          },
+        };
+
+        let header_bytes = self.data_element_header_to_bytes(
+          &header,
+          self.transfer_syntax.endianness,
+        )?;
+
+        let mut bytes =
+          Vec::with_capacity(header_bytes.len() + entry.bytes.len());
+        bytes.extend_from_slice(&header_bytes);
+        bytes.extend_from_slice(&entry.bytes);
+
+        part_bytes = Rc::new(bytes);
+      }
+
+      _ => (),
+    }
+
+    match self.defined_length_entries.last_mut() {
+      Some(entry) => {
+        entry.bytes.extend_from_slice(&part_bytes);
+        Ok(None)
+      }
+
+      None => Ok(Some(part_bytes)),
     }
   }
 
@@ -297,7 +773,16 @@ impl P10WriteContext {
 
       P10Part::FileMetaInformation { data_set } => {
         let mut file_meta_information = data_set.clone();
-        prepare_file_meta_information_part_data_set(&mut file_meta_information);
+        prepare_file_meta_information_part_data_set(
+          &mut file_meta_information,
+          &self.config,
+        )
+        .map_err(|e| P10Error::DataInvalid {
+          when: "Preparing File Meta Information".to_string(),
+          details: e.to_string(),
+          path: e.path().cloned().unwrap_or_default(),
+          offset: self.p10_total_byte_count,
+        })?;
 
         let mut fmi_bytes = Vec::with_capacity(8192);
 
@@ -522,6 +1007,10 @@ impl Default for P10WriteContext {
 /// Converts a data set to DICOM P10 parts. The generated P10 parts are returned
 /// via a callback.
 ///
+/// Data elements are emitted in ascending tag order, and the items in each
+/// sequence are emitted in the order they are stored in the sequence. Sequence
+/// items are never reordered.
+///
 pub fn data_set_to_parts<E>(
   data_set: &DataSet,
   part_callback: &mut impl FnMut(&P10Part) -> Result<(), E>,
@@ -600,45 +1089,54 @@ pub fn data_set_to_bytes(
   data_set_to_parts(data_set, &mut process_part)
 }
 
-/// Sets the *'(0002,0001) File Meta Information Version'*, *'(0002,0012)
-/// Implementation Class UID'* and *'(0002,0013) Implementation Version Name'*
-/// values in the File Meta Information. This is done prior to serializing it
-/// to bytes.
+/// Sets the *'(0002,0001) File Meta Information Version'* value in the File
+/// Meta Information, and also sets the *'(0002,0012) Implementation Class UID'*
+/// and *'(0002,0013) Implementation Version Name'* values from the write config
+/// if they aren't already present. This is done prior to serializing it to
+/// bytes.
 ///
 fn prepare_file_meta_information_part_data_set(
   file_meta_information: &mut DataSet,
-) {
+  config: &P10WriteConfig,
+) -> Result<(), DataError> {
   let file_meta_information_version =
     DataElementValue::new_other_byte_string(vec![0, 1]).unwrap();
-  let implementation_class_uid = DataElementValue::new_unique_identifier(&[
-    uids::DCMFX_IMPLEMENTATION_CLASS_UID,
-  ])
-  .unwrap();
-
-  let implementation_version_name = DataElementValue::new_short_string(&[
-    &uids::DCMFX_IMPLEMENTATION_VERSION_NAME,
-  ])
-  .unwrap();
-
   file_meta_information.insert(
     dictionary::FILE_META_INFORMATION_VERSION.tag,
     file_meta_information_version,
   );
-  file_meta_information.insert(
-    dictionary::IMPLEMENTATION_CLASS_UID.tag,
-    implementation_class_uid,
-  );
-  file_meta_information.insert(
-    dictionary::IMPLEMENTATION_VERSION_NAME.tag,
-    implementation_version_name,
-  )
+
+  let tag = dictionary::IMPLEMENTATION_CLASS_UID.tag;
+  if !file_meta_information.has(tag) {
+    file_meta_information
+      .insert_string_value(
+        &dictionary::IMPLEMENTATION_CLASS_UID,
+        &[&config.implementation_class_uid],
+      )
+      .map_err(|e| e.with_path(&DataSetPath::new_with_data_element(tag)))?;
+  }
+
+  let tag = dictionary::IMPLEMENTATION_VERSION_NAME.tag;
+  if !file_meta_information.has(tag) {
+    file_meta_information
+      .insert_string_value(
+        &dictionary::IMPLEMENTATION_VERSION_NAME,
+        &[&config.implementation_version_name],
+      )
+      .map_err(|e| e.with_path(&DataSetPath::new_with_data_element(tag)))?;
+  }
+
+  Ok(())
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
-  use dcmfx_core::ValueRepresentation;
+  use std::io::Read;
+
+  use byteorder::ByteOrder;
+  use dcmfx_core::{transfer_syntax, ValueRepresentation};
 
   #[test]
   fn data_element_header_to_bytes_test() {
@@ -748,4 +1246,379 @@ mod tests {
       Ok(Rc::new(vec![0, 40, 1, 6, 83, 83, 18, 52]))
     );
   }
+
+  #[test]
+  fn sequence_length_mode_round_trip_test() {
+    let mut code_item = DataSet::new();
+    code_item
+      .insert_string_value(&dictionary::CODE_VALUE, &["121311"])
+      .unwrap();
+
+    let mut referenced_image = DataSet::new();
+    referenced_image
+      .insert_string_value(&dictionary::REFERENCED_SOP_INSTANCE_UID, &["1.2.3"])
+      .unwrap();
+    referenced_image
+      .insert_sequence_value(
+        &dictionary::PURPOSE_OF_REFERENCE_CODE_SEQUENCE,
+        vec![code_item, DataSet::new()],
+      )
+      .unwrap();
+
+    for transfer_syntax in [
+      &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN,
+      &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+      &transfer_syntax::EXPLICIT_VR_BIG_ENDIAN,
+    ] {
+      let mut data_set = DataSet::new();
+      data_set
+        .insert_string_value(
+          &dictionary::TRANSFER_SYNTAX_UID,
+          &[transfer_syntax.uid],
+        )
+        .unwrap();
+      data_set
+        .insert_sequence_value(
+          &dictionary::REFERENCED_IMAGE_SEQUENCE,
+          vec![referenced_image.clone(), DataSet::new()],
+        )
+        .unwrap();
+      data_set
+        .insert_sequence_value(&dictionary::REFERENCED_SERIES_SEQUENCE, vec![])
+        .unwrap();
+
+      for sequence_length_mode in
+        [SequenceLengthMode::Defined, SequenceLengthMode::Undefined]
+      {
+        let config = P10WriteConfig {
+          sequence_length_mode,
+          ..P10WriteConfig::default()
+        };
+
+        let mut bytes = vec![];
+        data_set_to_bytes(
+          &data_set,
+          &mut |p10_bytes: Rc<Vec<u8>>| {
+            bytes.extend_from_slice(&p10_bytes);
+            Ok(())
+          },
+          &config,
+        )
+        .unwrap();
+
+        // Check for the presence of the sequence delimitation item
+        let delimiter = match transfer_syntax.endianness {
+          Endianness::LittleEndian => [0xFE, 0xFF, 0xDD, 0xE0],
+          Endianness::BigEndian => [0xFF, 0xFE, 0xE0, 0xDD],
+        };
+        assert_eq!(
+          bytes.windows(4).any(|window| window == delimiter),
+          sequence_length_mode == SequenceLengthMode::Undefined
+        );
+
+        let read_data_set = crate::read_bytes(bytes).map_err(|e| e.0).unwrap();
+
+        for item in [
+          dictionary::REFERENCED_IMAGE_SEQUENCE,
+          dictionary::REFERENCED_SERIES_SEQUENCE,
+        ] {
+          assert_eq!(
+            read_data_set.get_value(item.tag),
+            data_set.get_value(item.tag)
+          );
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn data_set_to_parts_sequence_item_order_test() {
+    let items: Vec<DataSet> = ["3", "1", "2"]
+      .into_iter()
+      .map(|patient_id| {
+        let mut item = DataSet::new();
+        item
+          .insert_string_value(&dictionary::PATIENT_ID, &[patient_id])
+          .unwrap();
+        item
+      })
+      .collect();
+
+    let mut data_set = DataSet::new();
+    data_set.insert(
+      dictionary::REFERENCED_PATIENT_SEQUENCE.tag,
+      DataElementValue::new_sequence(items),
+    );
+
+    let mut parts = vec![];
+    data_set_to_parts::<()>(&data_set, &mut |part: &P10Part| {
+      parts.push(part.clone());
+      Ok(())
+    })
+    .unwrap();
+
+    let patient_ids: Vec<_> = parts
+      .iter()
+      .filter_map(|part| match part {
+        P10Part::DataElementValueBytes {
+          vr: ValueRepresentation::LongString,
+          data,
+          ..
+        } => Some(data.as_slice()),
+        _ => None,
+      })
+      .collect();
+
+    assert_eq!(patient_ids, [b"3 ", b"1 ", b"2 "]);
+  }
+
+  #[test]
+  fn write_group_lengths_test() {
+    let mut data_set = crate::read_file(concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/../../../examples/example.dcm"
+    ))
+    .unwrap();
+    data_set
+      .insert_string_value(&dictionary::SPECIFIC_CHARACTER_SET, &["ISO_IR 192"])
+      .unwrap();
+
+    for transfer_syntax in [
+      &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN,
+      &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+    ] {
+      data_set
+        .insert_string_value(
+          &dictionary::TRANSFER_SYNTAX_UID,
+          &[transfer_syntax.uid],
+        )
+        .unwrap();
+
+      for sequence_length_mode in
+        [SequenceLengthMode::Defined, SequenceLengthMode::Undefined]
+      {
+        let config = P10WriteConfig {
+          sequence_length_mode,
+          write_group_lengths: true,
+          ..P10WriteConfig::default()
+        };
+
+        let mut bytes = vec![];
+        data_set_to_bytes(
+          &data_set,
+          &mut |p10_bytes: Rc<Vec<u8>>| {
+            bytes.extend_from_slice(&p10_bytes);
+            Ok(())
+          },
+          &config,
+        )
+        .unwrap();
+
+        // Walk the groups following the File Meta Information, checking that
+        // each group length points exactly to the start of the next group
+        let fmi_length =
+          byteorder::LittleEndian::read_u32(&bytes[140..144]) as usize;
+        let mut offset = 144 + fmi_length;
+
+        let mut group_lengths = vec![];
+        while offset < bytes.len() {
+          let group = byteorder::LittleEndian::read_u16(&bytes[offset..]);
+          let element = byteorder::LittleEndian::read_u16(&bytes[offset + 2..]);
+          let length =
+            byteorder::LittleEndian::read_u32(&bytes[offset + 8..]) as usize;
+
+          assert_eq!(element, 0);
+
+          group_lengths.push((DataElementTag::new(group, 0), length as u32));
+          offset += 12 + length;
+        }
+
+        assert_eq!(offset, bytes.len());
+        assert!(group_lengths.len() > 1);
+
+        // Sequences in the computed group lengths are assumed to have an
+        // undefined length
+        if sequence_length_mode == SequenceLengthMode::Undefined {
+          assert_eq!(group_lengths, data_set.compute_group_lengths());
+        }
+
+        // Group lengths aren't included when reading the data back in
+        let without_fmi = |data_set: DataSet| -> DataSet {
+          data_set
+            .into_iter()
+            .filter(|(tag, _)| tag.group != 2)
+            .collect()
+        };
+        assert_eq!(
+          without_fmi(crate::read_bytes(bytes).map_err(|e| e.0).unwrap()),
+          without_fmi(data_set.clone())
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn write_deflated_test() {
+    let write = |data_set: &DataSet| {
+      let mut bytes = vec![];
+      data_set_to_bytes(
+        data_set,
+        &mut |p10_bytes: Rc<Vec<u8>>| {
+          bytes.extend_from_slice(&p10_bytes);
+          Ok(())
+        },
+        &P10WriteConfig::default(),
+      )
+      .unwrap();
+
+      bytes
+    };
+
+    // Returns the bytes that follow the File Meta Information, which starts
+    // with its group length after the 128-byte preamble and "DICM" prefix
+    let main_data_set_bytes = |bytes: &[u8]| {
+      let group_length = byteorder::LittleEndian::read_u32(&bytes[140..144]);
+      bytes[144 + group_length as usize..].to_vec()
+    };
+
+    let mut data_set = crate::read_file(concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/../../../test/assets/pydicom/test_files/image_dfl.dcm"
+    ))
+    .unwrap();
+    assert_eq!(
+      data_set.get_transfer_syntax(),
+      Ok(&transfer_syntax::DEFLATED_EXPLICIT_VR_LITTLE_ENDIAN)
+    );
+
+    // Writing and then reading the data set gives the original data set. The
+    // File Meta Information is excluded from the comparison because writing
+    // updates its implementation details, and the specific character set
+    // written for UTF-8 data is set upfront.
+    data_set
+      .insert_string_value(&dictionary::SPECIFIC_CHARACTER_SET, &["ISO_IR 192"])
+      .unwrap();
+
+    let without_file_meta_information = |mut data_set: DataSet| {
+      for tag in data_set.file_meta_information().tags() {
+        data_set.delete(tag);
+      }
+      data_set
+    };
+
+    let deflated_bytes = write(&data_set);
+    assert_eq!(
+      without_file_meta_information(
+        crate::read_bytes(deflated_bytes.clone())
+          .map_err(|e| e.0)
+          .unwrap()
+      ),
+      without_file_meta_information(data_set.clone())
+    );
+
+    // The data following the File Meta Information is the zlib-compressed
+    // equivalent of the data written using 'Explicit VR Little Endian'
+    data_set
+      .insert_string_value(
+        &dictionary::TRANSFER_SYNTAX_UID,
+        &[transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN.uid],
+      )
+      .unwrap();
+    let explicit_bytes = write(&data_set);
+
+    let mut inflated_bytes = vec![];
+    flate2::read::DeflateDecoder::new(
+      main_data_set_bytes(&deflated_bytes).as_slice(),
+    )
+    .read_to_end(&mut inflated_bytes)
+    .unwrap();
+
+    assert_eq!(inflated_bytes, main_data_set_bytes(&explicit_bytes));
+    assert!(deflated_bytes.len() < explicit_bytes.len());
+  }
+
+  #[test]
+  fn write_part_during_encapsulated_pixel_data_test() {
+    let mut context = P10WriteContext::new();
+    context
+      .write_encapsulated_pixel_data_start(
+        ValueRepresentation::OtherByteString,
+        None,
+      )
+      .unwrap();
+
+    assert!(matches!(
+      context.write_part(&P10Part::SequenceDelimiter),
+      Err(P10Error::PartStreamInvalid { .. })
+    ));
+
+    context
+      .write_encapsulated_pixel_data_frame(&[1, 2])
+      .unwrap();
+    context.write_encapsulated_pixel_data_end().unwrap();
+    context.write_part(&P10Part::End).unwrap();
+  }
+
+  #[test]
+  fn write_implementation_uids_test() {
+    let mut data_set = crate::read_file(concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/../../../examples/example.dcm"
+    ))
+    .unwrap();
+    data_set.delete(dictionary::IMPLEMENTATION_CLASS_UID.tag);
+    data_set.delete(dictionary::IMPLEMENTATION_VERSION_NAME.tag);
+
+    let write = |data_set: &DataSet, config: &P10WriteConfig| {
+      let mut bytes = vec![];
+      data_set_to_bytes(
+        data_set,
+        &mut |p10_bytes: Rc<Vec<u8>>| {
+          bytes.extend_from_slice(&p10_bytes);
+          Ok(())
+        },
+        config,
+      )
+      .unwrap();
+
+      crate::read_bytes(bytes).unwrap()
+    };
+
+    // The defaults are written when the data set lacks the values
+    let written = write(&data_set, &P10WriteConfig::default());
+    assert_eq!(
+      written.get_string(dictionary::IMPLEMENTATION_CLASS_UID.tag),
+      Ok(uids::DCMFX_IMPLEMENTATION_CLASS_UID)
+    );
+    assert_eq!(
+      written.get_string(dictionary::IMPLEMENTATION_VERSION_NAME.tag),
+      Ok(uids::DCMFX_IMPLEMENTATION_VERSION_NAME.as_str())
+    );
+
+    // Configured values are written when the data set lacks the values
+    let config = P10WriteConfig {
+      implementation_class_uid: "1.2.3.4".to_string(),
+      implementation_version_name: "TEST 1.0".to_string(),
+      ..P10WriteConfig::default()
+    };
+    let written = write(&data_set, &config);
+    assert_eq!(
+      written.get_string(dictionary::IMPLEMENTATION_CLASS_UID.tag),
+      Ok("1.2.3.4")
+    );
+    assert_eq!(
+      written.get_string(dictionary::IMPLEMENTATION_VERSION_NAME.tag),
+      Ok("TEST 1.0")
+    );
+
+    // Values already present in the data set are preserved
+    data_set
+      .insert_string_value(&dictionary::IMPLEMENTATION_CLASS_UID, &["1.2.5"])
+      .unwrap();
+    let written = write(&data_set, &config);
+    assert_eq!(
+      written.get_string(dictionary::IMPLEMENTATION_CLASS_UID.tag),
+      Ok("1.2.5")
+    );
+  }
 }
@@ -5,3 +5,5 @@
 pub mod p10_filter_transform;
 pub mod p10_insert_transform;
 pub mod p10_print_transform;
+pub mod p10_retag_transform;
+pub mod p10_transcode_transform;
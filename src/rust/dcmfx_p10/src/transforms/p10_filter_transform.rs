@@ -1,24 +1,38 @@
-use dcmfx_core::{dictionary, DataElementTag, DataSet, ValueRepresentation};
+use dcmfx_core::{
+  dictionary, DataElementTag, DataSet, DataSetPath, ValueRepresentation,
+};
 
 use crate::{DataSetBuilder, P10Error, P10Part};
 
 /// Transform that applies a data element filter to a stream of DICOM P10 parts.
 ///
 pub struct P10FilterTransform {
-  predicate: Box<PredicateFunction>,
+  predicate: Predicate,
   location: Vec<LocationEntry>,
+  path: DataSetPath,
   data_set_builder: Option<Result<DataSetBuilder, P10Error>>,
 }
 
 pub struct LocationEntry {
-  #[allow(dead_code)]
   tag: DataElementTag,
   filter_result: bool,
+  sequence_item_count: usize,
 }
 
 type PredicateFunction =
   dyn FnMut(DataElementTag, ValueRepresentation, &[LocationEntry]) -> bool;
 
+type PathPredicateFunction =
+  dyn FnMut(&DataSetPath, ValueRepresentation) -> bool;
+
+/// The predicate function used by a filter transform, which is either passed
+/// the tag of each data element, or its full path in the data set.
+///
+enum Predicate {
+  Tag(Box<PredicateFunction>),
+  Path(Box<PathPredicateFunction>),
+}
+
 impl P10FilterTransform {
   /// Creates a new filter transform for filtering a stream of DICOM P10 parts.
   ///
@@ -31,6 +45,30 @@ impl P10FilterTransform {
   /// retrieved with [`Self::data_set()`].
   ///
   pub fn new(predicate: Box<PredicateFunction>, create_data_set: bool) -> Self {
+    Self::new_with_predicate(Predicate::Tag(predicate), create_data_set)
+  }
+
+  /// Creates a new filter transform for filtering a stream of DICOM P10 parts
+  /// using a predicate function that is passed the full path of each data
+  /// element, e.g. `"00400275/[0]/00400008"`. This allows data elements in
+  /// specific sequence items to be filtered.
+  ///
+  /// When a sequence doesn't pass the predicate then the whole sequence is
+  /// filtered out, including all of its items, and the predicate isn't called
+  /// for any of the data elements inside it.
+  ///
+  /// If `create_data_set` is `true` then the data elements that are permitted
+  /// by the predicate are collected into an in-memory data set that can be
+  /// retrieved with [`Self::data_set()`].
+  ///
+  pub fn new_with_path_predicate(
+    predicate: Box<PathPredicateFunction>,
+    create_data_set: bool,
+  ) -> Self {
+    Self::new_with_predicate(Predicate::Path(predicate), create_data_set)
+  }
+
+  fn new_with_predicate(predicate: Predicate, create_data_set: bool) -> Self {
     let data_set_builder = if create_data_set {
       Some(Ok(DataSetBuilder::new()))
     } else {
@@ -40,6 +78,7 @@ impl P10FilterTransform {
     Self {
       predicate,
       location: vec![],
+      path: DataSetPath::new(),
       data_set_builder,
     }
   }
@@ -77,6 +116,8 @@ impl P10FilterTransform {
       // function to see if it passes the filter, then add it to the location
       P10Part::SequenceStart { tag, vr }
       | P10Part::DataElementHeader { tag, vr, .. } => {
+        let _ = self.path.add_data_element(*tag);
+
         // The predicate function is skipped if a parent has already been
         // filtered out
         let filter_result = match self.location.as_slice() {
@@ -84,7 +125,10 @@ impl P10FilterTransform {
           | [.., LocationEntry {
             filter_result: true,
             ..
-          }] => (self.predicate)(*tag, *vr, &self.location),
+          }] => match &mut self.predicate {
+            Predicate::Tag(predicate) => predicate(*tag, *vr, &self.location),
+            Predicate::Path(predicate) => predicate(&self.path, *vr),
+          },
 
           _ => false,
         };
@@ -92,11 +136,34 @@ impl P10FilterTransform {
         self.location.push(LocationEntry {
           tag: *tag,
           filter_result,
+          sequence_item_count: 0,
         });
 
         filter_result
       }
 
+      // If this is a new sequence item then add it to the path
+      P10Part::SequenceItemStart => match self.location.last_mut() {
+        Some(entry) => {
+          let _ = self.path.add_sequence_item(entry.sequence_item_count);
+          entry.sequence_item_count += 1;
+
+          entry.filter_result
+        }
+
+        None => true,
+      },
+
+      // If this is the end of a sequence item then remove it from the path
+      P10Part::SequenceItemDelimiter => {
+        let _ = self.path.pop();
+
+        match self.location.last() {
+          Some(LocationEntry { filter_result, .. }) => *filter_result,
+          None => true,
+        }
+      }
+
       // If this is a new pixel data item then add it to the location
       P10Part::PixelDataItem { .. } => {
         let filter_result = match self.location.last() {
@@ -107,6 +174,7 @@ impl P10FilterTransform {
         self.location.push(LocationEntry {
           tag: dictionary::ITEM.tag,
           filter_result,
+          sequence_item_count: 0,
         });
 
         filter_result
@@ -116,16 +184,20 @@ impl P10FilterTransform {
       P10Part::SequenceDelimiter
       | P10Part::DataElementValueBytes {
         bytes_remaining: 0, ..
-      } => {
-        let filter_result = match self.location.last() {
-          Some(LocationEntry { filter_result, .. }) => *filter_result,
-          None => true,
-        };
+      } => match self.location.pop() {
+        Some(LocationEntry {
+          tag, filter_result, ..
+        }) => {
+          // Pixel data items aren't part of the path
+          if tag != dictionary::ITEM.tag {
+            let _ = self.path.pop();
+          }
 
-        self.location.pop();
+          filter_result
+        }
 
-        filter_result
-      }
+        None => true,
+      },
 
       _ => {
         match self.location.last() {
@@ -156,3 +228,74 @@ impl P10FilterTransform {
     filter_result
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::internal::test_utils::read_all_parts;
+  use crate::P10ReadContext;
+
+  #[test]
+  fn new_with_path_predicate_test() {
+    let filename = concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/../../../test/assets/pydicom/test_files/rtplan.dcm"
+    );
+    let data_set = crate::read_file(filename).unwrap();
+
+    // Remove the beam limiting device sequence from every beam, and the
+    // cumulative dose reference coefficient from only the second referenced
+    // dose reference of the second control point of the first beam
+    let mut filter_transform = P10FilterTransform::new_with_path_predicate(
+      Box::new(|path, _vr| {
+        let path = path.to_string();
+
+        !(path.starts_with("300A00B0/") && path.ends_with("/300A00B6")
+          || path == "300A00B0/[0]/300A0111/[1]/300C0050/[1]/300A010C")
+      }),
+      true,
+    );
+
+    let bytes = std::fs::read(filename).unwrap();
+    for part in read_all_parts(&mut P10ReadContext::new(), bytes).unwrap() {
+      filter_transform.add_part(&part);
+    }
+
+    let filtered_data_set = filter_transform.data_set().unwrap();
+
+    for path in [
+      "300A00B0/[0]/300A00B6",
+      "300A00B0/[0]/300A0111/[1]/300C0050/[1]/300A010C",
+    ] {
+      let path = DataSetPath::from_string(path).unwrap();
+      assert!(data_set.get_value_at_path(&path).is_ok());
+      assert!(filtered_data_set.get_value_at_path(&path).is_err());
+    }
+
+    for path in [
+      "300A00B0/[0]/300A00C0",
+      "300A00B0/[0]/300A0111/[0]/300A011A",
+      "300A00B0/[0]/300A0111/[0]/300C0050/[1]/300A010C",
+      "300A00B0/[0]/300A0111/[1]/300C0050/[0]/300A010C",
+      "300A00B0/[0]/300A0111/[1]/300C0050/[1]/300C0051",
+    ] {
+      let path = DataSetPath::from_string(path).unwrap();
+      assert_eq!(
+        filtered_data_set.get_value_at_path(&path),
+        data_set.get_value_at_path(&path)
+      );
+    }
+
+    // Data elements in the root data set are unaffected, though the File Meta
+    // Information isn't included in the filtered data set
+    assert_eq!(
+      filtered_data_set.get_value(dictionary::DOSE_REFERENCE_SEQUENCE.tag),
+      data_set.get_value(dictionary::DOSE_REFERENCE_SEQUENCE.tag)
+    );
+    assert_eq!(
+      filtered_data_set.size(),
+      data_set.size() - data_set.file_meta_information().size()
+    );
+  }
+}
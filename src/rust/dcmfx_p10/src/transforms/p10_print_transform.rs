@@ -9,7 +9,9 @@ use crate::P10Part;
 /// that describes the structure and content of the contained DICOM data.
 ///
 /// This is used for printing data sets on the command line, and the output can
-/// be styled via [`DataSetPrintOptions`].
+/// be styled via [`DataSetPrintOptions`]. The output is formatted in the same
+/// way as [`DataSet::to_lines()`], including truncation of lines to the
+/// maximum width and limiting of the number of sequence items printed.
 ///
 pub struct P10PrintTransform {
   print_options: DataSetPrintOptions,
@@ -19,6 +21,12 @@ pub struct P10PrintTransform {
   ignore_data_element_value_bytes: bool,
   value_max_width: usize,
 
+  // The number of items seen so far in each sequence and encapsulated pixel
+  // data value currently being printed. These are used to omit items beyond
+  // the configured maximum number of sequence items.
+  item_counts: Vec<usize>,
+  omitted_item_depth: usize,
+
   // Track private creator data elements so that private tags can be printed
   // with the correct names where possible
   private_creators: Vec<DataSet>,
@@ -36,6 +44,8 @@ impl P10PrintTransform {
       current_data_element: DataElementTag::new(0, 0),
       ignore_data_element_value_bytes: false,
       value_max_width: 0,
+      item_counts: vec![],
+      omitted_item_depth: 0,
       private_creators: vec![DataSet::new()],
       last_data_element_private_creator_tag: None,
     }
@@ -45,6 +55,23 @@ impl P10PrintTransform {
   /// text output to be displayed.
   ///
   pub fn add_part(&mut self, part: &P10Part) -> String {
+    // Skip all parts inside sequence items that are beyond the maximum number
+    // of sequence items to print, tracking nested sequences and items so the
+    // end of the omitted item can be detected
+    if self.omitted_item_depth > 0 {
+      match part {
+        P10Part::SequenceStart { .. } | P10Part::SequenceItemStart => {
+          self.omitted_item_depth += 1
+        }
+        P10Part::SequenceDelimiter | P10Part::SequenceItemDelimiter => {
+          self.omitted_item_depth -= 1
+        }
+        _ => (),
+      }
+
+      return "".to_string();
+    }
+
     match part {
       P10Part::FileMetaInformation { data_set } => {
         let mut s = "".to_string();
@@ -105,7 +132,7 @@ impl P10PrintTransform {
               ValueRepresentation::LongString,
               data.clone(),
             ),
-          )
+          );
         }
 
         format!(
@@ -128,6 +155,7 @@ impl P10PrintTransform {
         s.push('\n');
 
         self.indent += 1;
+        self.item_counts.push(0);
 
         s
       }
@@ -135,7 +163,20 @@ impl P10PrintTransform {
       P10Part::SequenceDelimiter => {
         self.indent -= 1;
 
-        let mut s = data_set::print::format_data_element_prefix(
+        // Report any items that were omitted from the output
+        let mut s = "".to_string();
+        let item_count = self.item_counts.pop().unwrap_or(0);
+        data_set::print::items_omitted_line(
+          item_count,
+          self.indent + 1,
+          &self.print_options,
+          &mut |line| {
+            s.push_str(&line);
+            s.push('\n');
+          },
+        );
+
+        s += &data_set::print::format_data_element_prefix(
           dictionary::SEQUENCE_DELIMITATION_ITEM.tag,
           dictionary::SEQUENCE_DELIMITATION_ITEM.name,
           None,
@@ -151,6 +192,11 @@ impl P10PrintTransform {
       }
 
       P10Part::SequenceItemStart => {
+        if self.is_item_omitted() {
+          self.omitted_item_depth = 1;
+          return "".to_string();
+        }
+
         let mut s = data_set::print::format_data_element_prefix(
           dictionary::ITEM.tag,
           dictionary::ITEM.name,
@@ -188,6 +234,11 @@ impl P10PrintTransform {
       }
 
       P10Part::PixelDataItem { length } => {
+        if self.is_item_omitted() {
+          self.ignore_data_element_value_bytes = true;
+          return "".to_string();
+        }
+
         let (s, width) = data_set::print::format_data_element_prefix(
           dictionary::ITEM.tag,
           dictionary::ITEM.name,
@@ -211,4 +262,118 @@ impl P10PrintTransform {
       _ => "".to_string(),
     }
   }
+
+  /// Records the start of a new item in the current sequence or encapsulated
+  /// pixel data value, and returns whether it is beyond the maximum number of
+  /// sequence items to print.
+  ///
+  fn is_item_omitted(&mut self) -> bool {
+    match self.item_counts.last_mut() {
+      Some(item_count) => {
+        *item_count += 1;
+
+        data_set::print::item_limit(*item_count, &self.print_options)
+          < *item_count
+      }
+      None => false,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::p10_part;
+
+  /// Prints a data set's data elements by streaming them through a print
+  /// transform.
+  ///
+  fn print_with_transform(
+    data_set: &DataSet,
+    print_options: &DataSetPrintOptions,
+  ) -> String {
+    let mut transform = P10PrintTransform::new(print_options);
+
+    let mut s = String::new();
+    p10_part::data_elements_to_parts::<()>(data_set, &mut |part| {
+      s.push_str(&transform.add_part(part));
+      Ok(())
+    })
+    .unwrap();
+
+    s
+  }
+
+  /// Prints a data set using [`DataSet::to_lines()`].
+  ///
+  fn print_with_to_lines(
+    data_set: &DataSet,
+    print_options: &DataSetPrintOptions,
+  ) -> String {
+    let mut s = String::new();
+    data_set.to_lines(print_options, &mut |line| {
+      s.push_str(&line);
+      s.push('\n');
+    });
+
+    s
+  }
+
+  #[test]
+  fn value_max_width_test() {
+    let value = DataElementValue::new_long_text("a".repeat(100)).unwrap();
+
+    let mut data_set = DataSet::new();
+    data_set.insert(dictionary::STUDY_COMMENTS.tag, value.clone());
+
+    let print_options = DataSetPrintOptions::new().styled(false).max_width(100);
+    let (prefix, prefix_width) = data_set::print::format_data_element_prefix(
+      dictionary::STUDY_COMMENTS.tag,
+      dictionary::STUDY_COMMENTS.name,
+      Some(ValueRepresentation::LongText),
+      Some(100),
+      0,
+      &print_options,
+    );
+
+    assert_eq!(
+      print_with_transform(&data_set, &print_options),
+      format!(
+        "{}{}\n",
+        prefix,
+        value.to_string(dictionary::STUDY_COMMENTS.tag, 100 - prefix_width)
+      )
+    );
+  }
+
+  #[test]
+  fn matches_to_lines_test() {
+    for filename in [
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm"),
+      concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../../test/assets/pydicom/test_files/rtplan.dcm"
+      ),
+    ] {
+      let mut data_set = crate::read_file(filename).unwrap();
+      for tag in data_set.file_meta_information().tags() {
+        data_set.delete(tag);
+      }
+
+      for print_options in [
+        DataSetPrintOptions::new().styled(false).max_width(60),
+        DataSetPrintOptions::new().styled(true).max_width(120),
+        DataSetPrintOptions::new()
+          .styled(false)
+          .max_width(80)
+          .max_sequence_items(Some(1)),
+      ] {
+        assert_eq!(
+          print_with_transform(&data_set, &print_options),
+          print_with_to_lines(&data_set, &print_options)
+        );
+      }
+    }
+  }
 }
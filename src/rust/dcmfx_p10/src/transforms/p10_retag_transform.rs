@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use dcmfx_core::DataElementTag;
+
+use crate::P10Part;
+
+/// Transform that changes the tags of data elements in a stream of DICOM P10
+/// parts. This can be used to fix misplaced private tags, or to migrate retired
+/// data elements to their current equivalents. Data element values are passed
+/// through unchanged.
+///
+/// Tags are rewritten at every level of the data set, including inside
+/// sequences. Parts are not reordered, so if a data element is retagged to a
+/// tag that is out of order relative to the data elements around it then the
+/// resulting stream of parts won't be sorted by tag. Such a stream should be
+/// passed through a [`crate::DataSetBuilder`] and written out again if sorted
+/// output is required.
+///
+pub struct P10RetagTransform {
+  tags: HashMap<DataElementTag, DataElementTag>,
+}
+
+impl P10RetagTransform {
+  /// Creates a new retag transform that changes the tag of any data element
+  /// that is a key in `tags` to the corresponding value.
+  ///
+  pub fn new(tags: HashMap<DataElementTag, DataElementTag>) -> Self {
+    Self { tags }
+  }
+
+  /// Adds the next part to the P10 retag transform and returns the resulting
+  /// part.
+  ///
+  pub fn add_part(&mut self, part: &P10Part) -> P10Part {
+    match part {
+      P10Part::DataElementHeader { tag, vr, length } => {
+        P10Part::DataElementHeader {
+          tag: self.retag(*tag),
+          vr: *vr,
+          length: *length,
+        }
+      }
+
+      P10Part::SequenceStart { tag, vr } => P10Part::SequenceStart {
+        tag: self.retag(*tag),
+        vr: *vr,
+      },
+
+      _ => part.clone(),
+    }
+  }
+
+  fn retag(&self, tag: DataElementTag) -> DataElementTag {
+    *self.tags.get(&tag).unwrap_or(&tag)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::rc::Rc;
+
+  use dcmfx_core::{dictionary, ValueRepresentation};
+
+  use super::*;
+
+  #[test]
+  fn add_part_test() {
+    let mut retag_transform = P10RetagTransform::new(HashMap::from([(
+      DataElementTag::new(0x0009, 0x1001),
+      dictionary::PATIENT_ID.tag,
+    )]));
+
+    assert_eq!(
+      retag_transform.add_part(&P10Part::DataElementHeader {
+        tag: DataElementTag::new(0x0009, 0x1001),
+        vr: ValueRepresentation::LongString,
+        length: 4,
+      }),
+      P10Part::DataElementHeader {
+        tag: dictionary::PATIENT_ID.tag,
+        vr: ValueRepresentation::LongString,
+        length: 4,
+      }
+    );
+
+    let value_bytes = P10Part::DataElementValueBytes {
+      vr: ValueRepresentation::LongString,
+      data: Rc::new(b"1234".to_vec()),
+      bytes_remaining: 0,
+    };
+    assert_eq!(retag_transform.add_part(&value_bytes), value_bytes);
+
+    let header = P10Part::DataElementHeader {
+      tag: dictionary::PATIENT_NAME.tag,
+      vr: ValueRepresentation::PersonName,
+      length: 0,
+    };
+    assert_eq!(retag_transform.add_part(&header), header);
+  }
+}
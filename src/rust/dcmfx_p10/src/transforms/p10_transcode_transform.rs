@@ -0,0 +1,262 @@
+use dcmfx_core::{
+  dictionary, transfer_syntax, DataError, DataSet, TransferSyntax,
+};
+
+use crate::{P10Error, P10Part};
+
+/// Transform that changes the transfer syntax of a stream of DICOM P10 parts by
+/// updating the *'(0002,0010) Transfer Syntax UID'* data element in its File
+/// Meta Information. The parts themselves don't depend on the transfer syntax,
+/// so when the resulting parts are written out they are re-encoded in the new
+/// transfer syntax. This avoids having to materialize the whole data set in
+/// memory.
+///
+/// Only conversion between transfer syntaxes that use native, i.e. not
+/// encapsulated, pixel data is supported. These are the implicit and explicit
+/// VR transfer syntaxes in both little and big endian, including 'Deflated
+/// Explicit VR Little Endian'.
+///
+/// When the stream of parts uses a transfer syntax with encapsulated pixel
+/// data, such as a compressed transfer syntax, its encapsulated pixel data
+/// can't be re-encoded, so all parts are passed through unchanged, including
+/// its transfer syntax UID.
+///
+pub struct P10TranscodeTransform {
+  output_transfer_syntax: &'static TransferSyntax,
+}
+
+impl P10TranscodeTransform {
+  /// Creates a new transcode transform that converts a stream of DICOM P10
+  /// parts to the given transfer syntax. Returns an error if the output
+  /// transfer syntax uses encapsulated pixel data.
+  ///
+  pub fn new(
+    output_transfer_syntax: &'static TransferSyntax,
+  ) -> Result<Self, P10Error> {
+    if output_transfer_syntax.is_encapsulated {
+      return Err(P10Error::OtherError {
+        error_type: "Unsupported transfer syntax conversion".to_string(),
+        details: format!(
+          "The transfer syntax '{}' is not able to be converted to",
+          output_transfer_syntax.name
+        ),
+      });
+    }
+
+    Ok(Self {
+      output_transfer_syntax,
+    })
+  }
+
+  /// Adds the next part to the P10 transcode transform and returns the
+  /// resulting part. Returns an error if the File Meta Information specifies
+  /// a transfer syntax that isn't recognized, as its data can't be re-encoded.
+  ///
+  pub fn add_part(&mut self, part: &P10Part) -> Result<P10Part, P10Error> {
+    match part {
+      P10Part::FileMetaInformation { data_set } => {
+        Ok(P10Part::FileMetaInformation {
+          data_set: self.transcode_file_meta_information(data_set)?,
+        })
+      }
+
+      _ => Ok(part.clone()),
+    }
+  }
+
+  /// Returns a copy of the File Meta Information with its transfer syntax UID
+  /// set to the output transfer syntax. An absent transfer syntax UID is
+  /// treated as 'Implicit VR Little Endian'.
+  ///
+  fn transcode_file_meta_information(
+    &self,
+    data_set: &DataSet,
+  ) -> Result<DataSet, P10Error> {
+    let transfer_syntax =
+      match data_set.get_string(dictionary::TRANSFER_SYNTAX_UID.tag) {
+        Ok(uid) => TransferSyntax::from_uid(uid).map_err(|_| {
+          P10Error::TransferSyntaxNotSupported {
+            transfer_syntax_uid: uid.to_string(),
+          }
+        })?,
+
+        Err(e) if e.is_tag_not_present() => {
+          &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN
+        }
+
+        Err(e) => return Err(file_meta_information_error(data_set, e)),
+      };
+
+    let mut data_set = data_set.clone();
+
+    if !transfer_syntax.is_encapsulated {
+      data_set
+        .insert_string_value(
+          &dictionary::TRANSFER_SYNTAX_UID,
+          &[self.output_transfer_syntax.uid],
+        )
+        .map_err(|e| file_meta_information_error(&data_set, e))?;
+    }
+
+    Ok(data_set)
+  }
+}
+
+/// Returns the error used when the File Meta Information passed to a transcode
+/// transform is invalid.
+///
+fn file_meta_information_error(
+  data_set: &DataSet,
+  error: DataError,
+) -> P10Error {
+  P10Error::PartStreamInvalid {
+    when: "Transcoding File Meta Information".to_string(),
+    details: error.to_string(),
+    part: P10Part::FileMetaInformation {
+      data_set: data_set.clone(),
+    },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::internal::test_utils::read_all_parts;
+  use crate::{P10ReadContext, P10WriteContext};
+
+  /// Returns a data set with its File Meta Information removed. This is used
+  /// when comparing data sets because writing DICOM P10 data also updates
+  /// the implementation details in the File Meta Information.
+  ///
+  fn without_file_meta_information(mut data_set: DataSet) -> DataSet {
+    for tag in data_set.file_meta_information().tags() {
+      data_set.delete(tag);
+    }
+
+    data_set
+  }
+
+  /// Reads the parts from DICOM P10 bytes, passes them through a transcode
+  /// transform, and returns the DICOM P10 bytes that result from writing them.
+  ///
+  fn transcode(
+    bytes: Vec<u8>,
+    transfer_syntax: &'static TransferSyntax,
+  ) -> Vec<u8> {
+    let mut transform = P10TranscodeTransform::new(transfer_syntax).unwrap();
+    let mut write_context = P10WriteContext::new();
+
+    for part in read_all_parts(&mut P10ReadContext::new(), bytes).unwrap() {
+      write_context
+        .write_part(&transform.add_part(&part).unwrap())
+        .unwrap();
+    }
+
+    write_context
+      .read_bytes()
+      .iter()
+      .flat_map(|bytes| bytes.iter().copied())
+      .collect()
+  }
+
+  #[test]
+  fn add_part_test() {
+    for filename in [
+      concat!(env!("CARGO_MANIFEST_DIR"), "/../../../examples/example.dcm"),
+      concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../../test/assets/pydicom/test_files/rtplan.dcm"
+      ),
+    ] {
+      let bytes = std::fs::read(filename).unwrap();
+      let data_set = crate::read_bytes(bytes.clone()).map_err(|e| e.0).unwrap();
+
+      for transfer_syntax in [
+        &transfer_syntax::IMPLICIT_VR_LITTLE_ENDIAN,
+        &transfer_syntax::EXPLICIT_VR_BIG_ENDIAN,
+        &transfer_syntax::DEFLATED_EXPLICIT_VR_LITTLE_ENDIAN,
+        &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+      ] {
+        let transcoded_bytes = transcode(bytes.clone(), transfer_syntax);
+        let transcoded_data_set = crate::read_bytes(transcoded_bytes.clone())
+          .map_err(|e| e.0)
+          .unwrap();
+
+        assert_eq!(
+          transcoded_data_set.get_transfer_syntax(),
+          Ok(transfer_syntax)
+        );
+
+        // Converting back to the original transfer syntax gives the original
+        // data set
+        let original_transfer_syntax = data_set.get_transfer_syntax().unwrap();
+        let round_tripped_data_set = crate::read_bytes(transcode(
+          transcoded_bytes,
+          original_transfer_syntax,
+        ))
+        .map_err(|e| e.0)
+        .unwrap();
+        assert_eq!(
+          round_tripped_data_set.get_transfer_syntax(),
+          Ok(original_transfer_syntax)
+        );
+        assert_eq!(
+          without_file_meta_information(round_tripped_data_set),
+          without_file_meta_information(data_set.clone())
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn add_part_unrecognized_transfer_syntax_test() {
+    let mut data_set = DataSet::new();
+    data_set
+      .insert_string_value(&dictionary::TRANSFER_SYNTAX_UID, &["1.2.3.4"])
+      .unwrap();
+
+    let mut transform =
+      P10TranscodeTransform::new(&transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN)
+        .unwrap();
+
+    assert_eq!(
+      transform.add_part(&P10Part::FileMetaInformation { data_set }),
+      Err(P10Error::TransferSyntaxNotSupported {
+        transfer_syntax_uid: "1.2.3.4".to_string()
+      })
+    );
+  }
+
+  #[test]
+  fn add_part_encapsulated_test() {
+    // Encapsulated pixel data is passed through unchanged
+    let bytes = std::fs::read(concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/../../../test/assets/pydicom/test_files/JPEG-lossy.dcm"
+    ))
+    .unwrap();
+    let data_set = crate::read_bytes(bytes.clone()).map_err(|e| e.0).unwrap();
+
+    let transcoded_data_set = crate::read_bytes(transcode(
+      bytes,
+      &transfer_syntax::EXPLICIT_VR_LITTLE_ENDIAN,
+    ))
+    .map_err(|e| e.0)
+    .unwrap();
+
+    assert_eq!(
+      transcoded_data_set.get_transfer_syntax(),
+      data_set.get_transfer_syntax()
+    );
+    assert_eq!(
+      transcoded_data_set.get_value(dictionary::PIXEL_DATA.tag),
+      data_set.get_value(dictionary::PIXEL_DATA.tag)
+    );
+
+    // Encapsulated transfer syntaxes can't be converted to
+    assert!(
+      P10TranscodeTransform::new(&transfer_syntax::JPEG_BASELINE_8BIT).is_err()
+    );
+  }
+}
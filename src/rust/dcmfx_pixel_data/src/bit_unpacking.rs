@@ -0,0 +1,155 @@
+//! Unpacks native pixel data that has one bit allocated per sample, such as
+//! that used by segmentations and overlays, into one byte per sample.
+//!
+//! Ref: PS3.5 8.1.1, PS3.5 Annex D.
+
+use dcmfx_core::{dictionary, DataError, DataSet, DataSetPath};
+
+/// Returns the frames of native pixel data in a data set that has a
+/// *'(0028,0100) Bits Allocated'* value of 1, with each sample unpacked into
+/// its own byte that holds either zero or one.
+///
+/// Samples are packed eight to a byte with the first sample in the least
+/// significant bit. There is no padding between frames, so a frame doesn't
+/// start on a byte boundary when its size in bits isn't a multiple of 8, which
+/// means such frames can't be returned by
+/// [`crate::DataSetPixelDataExtensions::get_pixel_data()`].
+///
+/// An error is returned if the bits allocated value isn't 1, if the pixel data
+/// is encapsulated, or if it's too short to hold all of the frames.
+///
+pub fn unpack_1_bit_frames(
+  data_set: &DataSet,
+) -> Result<Vec<Vec<u8>>, DataError> {
+  let pixel_data_path =
+    DataSetPath::new_with_data_element(dictionary::PIXEL_DATA.tag);
+
+  let bits_allocated = data_set.get_int(dictionary::BITS_ALLOCATED.tag)?;
+  if bits_allocated != 1 {
+    return Err(
+      DataError::new_value_invalid(format!(
+        "Only pixel data with 1 bit allocated can be unpacked, but it has {}",
+        bits_allocated
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::BITS_ALLOCATED.tag,
+      )),
+    );
+  }
+
+  let bytes = match data_set.get_value(dictionary::PIXEL_DATA.tag)?.bytes() {
+    Ok(bytes) => bytes,
+    Err(_) => {
+      return Err(
+        DataError::new_value_invalid(
+          "Only native pixel data can be unpacked".to_string(),
+        )
+        .with_path(&pixel_data_path),
+      )
+    }
+  };
+
+  let number_of_frames =
+    match data_set.get_int(dictionary::NUMBER_OF_FRAMES.tag) {
+      Ok(number_of_frames) => number_of_frames.max(1) as usize,
+      Err(e) if e.is_tag_not_present() => 1,
+      Err(e) => return Err(e),
+    };
+
+  let frame_size = crate::frame_size_bits(data_set)?;
+
+  // Every frame must hold at least one bit, which stops a malformed number of
+  // frames from causing a huge allocation
+  let required_bits = frame_size.max(1).saturating_mul(number_of_frames);
+
+  if bytes.len() * 8 < required_bits {
+    return Err(
+      DataError::new_value_invalid(format!(
+        "Pixel data of length {} bytes is too short to hold {} frame(s) of {} \
+         bits",
+        bytes.len(),
+        number_of_frames,
+        frame_size
+      ))
+      .with_path(&pixel_data_path),
+    );
+  }
+
+  Ok(
+    (0..number_of_frames)
+      .map(|frame| unpack_bits(bytes, frame * frame_size, frame_size))
+      .collect(),
+  )
+}
+
+/// Unpacks the given number of bits, starting at the specified bit offset, into
+/// one byte per bit.
+///
+fn unpack_bits(bytes: &[u8], bit_offset: usize, count: usize) -> Vec<u8> {
+  (bit_offset..bit_offset + count)
+    .map(|bit| (bytes[bit / 8] >> (bit % 8)) & 1)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::rc::Rc;
+
+  use dcmfx_core::ValueRepresentation;
+
+  use crate::DataSetPixelDataExtensions;
+
+  #[test]
+  fn unpack_1_bit_frames_test() {
+    let mut ds = DataSet::new();
+    ds.insert_int_value(&dictionary::ROWS, &[1]).unwrap();
+    ds.insert_int_value(&dictionary::COLUMNS, &[5]).unwrap();
+    ds.insert_int_value(&dictionary::SAMPLES_PER_PIXEL, &[1])
+      .unwrap();
+    ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[1])
+      .unwrap();
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[3])
+      .unwrap();
+    ds.insert_binary_value(
+      dictionary::PIXEL_DATA.tag,
+      ValueRepresentation::OtherByteString,
+      Rc::new(vec![0b1100_1101, 0b0101_1110]),
+    )
+    .unwrap();
+
+    assert_eq!(
+      unpack_1_bit_frames(&ds),
+      Ok(vec![
+        vec![1, 0, 1, 1, 0],
+        vec![0, 1, 1, 0, 1],
+        vec![1, 1, 1, 0, 1]
+      ])
+    );
+
+    // The frames of 5 bits aren't byte aligned so can't be sliced
+    assert!(ds.get_pixel_data().unwrap_err().is_value_not_byte_aligned());
+    assert!(ds.get_frame(1).unwrap_err().is_value_not_byte_aligned());
+
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[4])
+      .unwrap();
+    assert_eq!(
+      unpack_1_bit_frames(&ds),
+      Err(
+        DataError::new_value_invalid(
+          "Pixel data of length 2 bytes is too short to hold 4 frame(s) of 5 \
+           bits"
+            .to_string()
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::PIXEL_DATA.tag
+        ))
+      )
+    );
+
+    ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[8])
+      .unwrap();
+    assert!(unpack_1_bit_frames(&ds).is_err());
+  }
+}
@@ -0,0 +1,206 @@
+//! Decodes frames of pixel data that use the 'JPEG Baseline (Process 1)'
+//! transfer syntax. This requires the `jpeg` feature.
+//!
+//! Ref: PS3.5 8.2.1, PS3.5 A.4.1.
+
+use dcmfx_core::{dictionary, DataError, DataSetPath};
+use jpeg_decoder::{CodingProcess, ColorTransform, Decoder, PixelFormat};
+
+use crate::PixelDataInfo;
+
+/// Decodes a frame of 'JPEG Baseline (Process 1)' pixel data into 8-bit pixel
+/// values. If the frame is made up of multiple fragments then they must be
+/// concatenated into a single slice prior to calling this function.
+///
+/// Frames with one sample per pixel are returned as one byte per pixel. Frames
+/// with three samples per pixel are returned as interleaved RGB, i.e. with a
+/// planar configuration of zero, regardless of the planar configuration in
+/// `info`. When the photometric interpretation is *'YBR_FULL_422'* or
+/// *'YBR_FULL'* the YCbCr to RGB color transform is applied, and when it is
+/// *'RGB'* the markers in the JPEG data determine whether a color transform is
+/// needed.
+///
+pub fn decode_jpeg_baseline_frame(
+  fragment: &[u8],
+  info: &PixelDataInfo,
+) -> Result<Vec<u8>, DataError> {
+  if info.bits_allocated != 8 {
+    return Err(
+      DataError::new_value_invalid(format!(
+        "JPEG Baseline pixel data must have 8 bits allocated, but it has {}",
+        info.bits_allocated
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::BITS_ALLOCATED.tag,
+      )),
+    );
+  }
+
+  let (color_transform, expected_pixel_format) = match (
+    info.samples_per_pixel,
+    info.photometric_interpretation.as_str(),
+  ) {
+    (1, "MONOCHROME1" | "MONOCHROME2") => {
+      (Some(ColorTransform::Grayscale), PixelFormat::L8)
+    }
+    (3, "YBR_FULL_422" | "YBR_FULL") => {
+      (Some(ColorTransform::YCbCr), PixelFormat::RGB24)
+    }
+    (3, "RGB") => (None, PixelFormat::RGB24),
+
+    (samples_per_pixel, photometric_interpretation) => {
+      return Err(
+        DataError::new_value_invalid(format!(
+          "JPEG Baseline decoding of photometric interpretation '{}' with {} \
+           samples per pixel is not supported",
+          photometric_interpretation, samples_per_pixel
+        ))
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::PHOTOMETRIC_INTERPRETATION.tag,
+        )),
+      )
+    }
+  };
+
+  let invalid_pixel_data_error = |details: String| {
+    DataError::new_value_invalid(details).with_path(
+      &DataSetPath::new_with_data_element(dictionary::PIXEL_DATA.tag),
+    )
+  };
+
+  let mut decoder = Decoder::new(fragment);
+  if let Some(color_transform) = color_transform {
+    decoder.set_color_transform(color_transform);
+  }
+
+  let pixels = decoder.decode().map_err(|e| {
+    invalid_pixel_data_error(format!("JPEG Baseline decode failed: {}", e))
+  })?;
+
+  // The image info is always available after a successful decode
+  let jpeg_info = decoder.info().unwrap();
+
+  if jpeg_info.coding_process != CodingProcess::DctSequential {
+    return Err(invalid_pixel_data_error(format!(
+      "JPEG data uses the {:?} coding process, not baseline",
+      jpeg_info.coding_process
+    )));
+  }
+
+  if jpeg_info.width != info.columns || jpeg_info.height != info.rows {
+    return Err(invalid_pixel_data_error(format!(
+      "JPEG data has dimensions {}x{} but the data set specifies {}x{}",
+      jpeg_info.width, jpeg_info.height, info.columns, info.rows
+    )));
+  }
+
+  if jpeg_info.pixel_format != expected_pixel_format {
+    return Err(invalid_pixel_data_error(format!(
+      "JPEG data has pixel format {:?} but {} samples per pixel were expected",
+      jpeg_info.pixel_format, info.samples_per_pixel
+    )));
+  }
+
+  Ok(pixels)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// An 8x8 grayscale baseline JPEG with a single block whose DC coefficient
+  /// gives every pixel a value of 136. The quantization table is all ones,
+  /// and both Huffman tables contain a single one bit code.
+  ///
+  #[rustfmt::skip]
+  const GRAYSCALE_JPEG: &[u8] = &[
+    // SOI
+    0xFF, 0xD8,
+
+    // DQT
+    0xFF, 0xDB, 0x00, 0x43, 0x00,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+
+    // SOF0
+    0xFF, 0xC0, 0x00, 0x0B, 0x08, 0x00, 0x08, 0x00, 0x08, 0x01, 0x01, 0x11,
+    0x00,
+
+    // DHT for DC with category 7 as its only symbol
+    0xFF, 0xC4, 0x00, 0x14, 0x00,
+    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0x07,
+
+    // DHT for AC with end of block as its only symbol
+    0xFF, 0xC4, 0x00, 0x14, 0x10,
+    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0x00,
+
+    // SOS
+    0xFF, 0xDA, 0x00, 0x08, 0x01, 0x01, 0x00, 0x00, 0x3F, 0x00,
+
+    // Scan data with a DC coefficient of 64 followed by end of block
+    0x40, 0x7F,
+
+    // EOI
+    0xFF, 0xD9,
+  ];
+
+  fn pixel_data_info(
+    rows: u16,
+    columns: u16,
+    photometric_interpretation: &str,
+  ) -> PixelDataInfo {
+    PixelDataInfo {
+      rows,
+      columns,
+      samples_per_pixel: 1,
+      bits_allocated: 8,
+      bits_stored: 8,
+      is_signed: false,
+      photometric_interpretation: photometric_interpretation.to_string(),
+      planar_configuration: 0,
+    }
+  }
+
+  #[test]
+  fn decode_jpeg_baseline_frame_test() {
+    assert_eq!(
+      decode_jpeg_baseline_frame(
+        GRAYSCALE_JPEG,
+        &pixel_data_info(8, 8, "MONOCHROME2")
+      ),
+      Ok(vec![136; 64])
+    );
+
+    assert_eq!(
+      decode_jpeg_baseline_frame(
+        GRAYSCALE_JPEG,
+        &pixel_data_info(8, 16, "MONOCHROME2")
+      ),
+      Err(
+        DataError::new_value_invalid(
+          "JPEG data has dimensions 8x8 but the data set specifies 16x8"
+            .to_string()
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::PIXEL_DATA.tag
+        ))
+      )
+    );
+
+    assert!(decode_jpeg_baseline_frame(
+      GRAYSCALE_JPEG,
+      &pixel_data_info(8, 8, "PALETTE COLOR")
+    )
+    .is_err());
+
+    assert!(decode_jpeg_baseline_frame(
+      &GRAYSCALE_JPEG[0..100],
+      &pixel_data_info(8, 8, "MONOCHROME2")
+    )
+    .is_err());
+  }
+}
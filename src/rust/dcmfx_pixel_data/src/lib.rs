@@ -1,14 +1,105 @@
 //! Extracts frames of pixel data present in a data set.
 
+mod bit_unpacking;
+#[cfg(feature = "jpeg")]
+mod jpeg_baseline;
+mod modality_lut;
+mod monochrome;
+mod palette_color_lut;
+mod pixel_data_info;
+mod planar_configuration;
+mod rle_lossless;
+mod voi_window;
+
+use std::rc::Rc;
+
 use byteorder::ByteOrder;
 
 use dcmfx_core::{
   dictionary, transfer_syntax, DataElementValue, DataError, DataSet,
-  TransferSyntax, ValueRepresentation,
+  DataSetPath, TransferSyntax, ValueRepresentation,
+};
+
+pub use bit_unpacking::unpack_1_bit_frames;
+#[cfg(feature = "jpeg")]
+pub use jpeg_baseline::decode_jpeg_baseline_frame;
+pub use modality_lut::apply_modality_lut;
+pub use monochrome::normalize_to_monochrome2;
+pub use palette_color_lut::{
+  apply_palette, apply_palette_color_lut, get_palette_color_lut,
+  PaletteColorLut,
+};
+pub use pixel_data_info::PixelDataInfo;
+pub use planar_configuration::{
+  get_component_plane, interleaved_to_planar, planar_to_interleaved,
 };
+pub use rle_lossless::decode_rle_frame;
+pub use voi_window::apply_voi_window;
 
 type Frame<'a> = Vec<&'a [u8]>;
 
+/// A non-fatal inconsistency found while getting the frames of pixel data in a
+/// data set. Pixel data that has warnings is still returned, but may not be
+/// what the creator of the data set intended.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum PixelDataWarning {
+  /// The *'(0028,0008) Number of Frames'* value does not match the number of
+  /// frames defined by the *'(7FE0,0001) Extended Offset Table'*. The
+  /// extended offset table is authoritative and is used to determine the
+  /// frames.
+  NumberOfFramesMismatch {
+    number_of_frames: usize,
+    extended_offset_table_frames: usize,
+  },
+
+  /// The *'(0028,0008) Number of Frames'* value does not match the number of
+  /// items in the *'(5200,9230) Per-frame Functional Groups Sequence'*, which
+  /// should have one item per frame. This indicates a malformed enhanced
+  /// multi-frame data set. The number of frames value is used to determine the
+  /// frames.
+  PerFrameFunctionalGroupsMismatch {
+    number_of_frames: usize,
+    per_frame_functional_groups: usize,
+  },
+
+  /// The *'(7FE0,0010) Pixel Data'* is native pixel data with a VR of `UN`.
+  /// This is common in data that was converted from an implicit VR transfer
+  /// syntax without knowledge of the pixel data's VR. The pixel data is
+  /// treated as `OB`.
+  UnknownValueRepresentation,
+}
+
+impl std::fmt::Display for PixelDataWarning {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      PixelDataWarning::NumberOfFramesMismatch {
+        number_of_frames,
+        extended_offset_table_frames,
+      } => write!(
+        f,
+        "Number of frames is {} but the extended offset table defines {} \
+         frames, the extended offset table will be used",
+        number_of_frames, extended_offset_table_frames
+      ),
+
+      PixelDataWarning::PerFrameFunctionalGroupsMismatch {
+        number_of_frames,
+        per_frame_functional_groups,
+      } => write!(
+        f,
+        "Number of frames is {} but the per-frame functional groups sequence \
+         has {} items, the number of frames will be used",
+        number_of_frames, per_frame_functional_groups
+      ),
+
+      PixelDataWarning::UnknownValueRepresentation => {
+        write!(f, "Pixel data has a VR of UN, it will be treated as OB")
+      }
+    }
+  }
+}
+
 /// Adds functions to [`DataSet`] for getting its raw pixel data.
 ///
 pub trait DataSetPixelDataExtensions
@@ -23,97 +114,461 @@ where
   ///
   /// The *'(0028,0008) Number of Frames'*, *'(7FE0,0001) Extended Offset
   /// Table'*, and *'(7FE0,0002) Extended Offset Table Lengths'* data elements
-  /// are used when present and relevant.
+  /// are used when present and relevant. When an extended offset table is
+  /// present it is authoritative, and any disagreement with the number of
+  /// frames is ignored. The number of frames is also expected to match the
+  /// number of items in the *'(5200,9230) Per-frame Functional Groups
+  /// Sequence'*, when present. Use [`Self::get_pixel_data_with_warnings()`] to
+  /// detect such inconsistencies.
+  ///
+  /// Frames of native pixel data are returned as slices of its bytes, so each
+  /// frame must start on a byte boundary. This isn't the case for multi-frame
+  /// pixel data when the size of a frame isn't a multiple of 8 bits, which can
+  /// happen when *'(0028,0100) Bits Allocated'* is 1, or for retired bit-packed
+  /// formats such as 12 bits allocated. Such pixel data results in an error for
+  /// which [`DataError::is_value_not_byte_aligned()`] returns true. Pixel data
+  /// with 1 bit allocated can instead be read using [`unpack_1_bit_frames()`].
+  ///
+  /// No pixel data is copied. Each fragment of a returned frame is a slice that
+  /// borrows directly from the data set, so the frames can be used for as long
+  /// as the data set is borrowed:
+  ///
+  /// ```
+  /// use std::rc::Rc;
+  ///
+  /// use dcmfx_core::{dictionary, DataSet, ValueRepresentation};
+  /// use dcmfx_pixel_data::DataSetPixelDataExtensions;
+  ///
+  /// let mut data_set = DataSet::new();
+  /// data_set
+  ///   .insert_binary_value(
+  ///     dictionary::PIXEL_DATA.tag,
+  ///     ValueRepresentation::OtherByteString,
+  ///     Rc::new(vec![1, 2, 3, 4]),
+  ///   )
+  ///   .unwrap();
+  ///
+  /// let (_vr, frames) = data_set.get_pixel_data().unwrap();
+  ///
+  /// let pixel_data = data_set.get_value(dictionary::PIXEL_DATA.tag).unwrap();
+  /// assert_eq!(frames[0][0].as_ptr(), pixel_data.bytes().unwrap().as_ptr());
+  /// ```
   ///
   fn get_pixel_data(
     &self,
-  ) -> Result<(ValueRepresentation, Vec<Frame>), DataError>;
+  ) -> Result<(ValueRepresentation, Vec<Frame<'_>>), DataError>;
+
+  /// Returns all frames of image data present in a data set in the same way as
+  /// [`Self::get_pixel_data()`], along with a list of any non-fatal
+  /// inconsistencies that were found in the pixel data.
+  ///
+  #[allow(clippy::type_complexity)]
+  fn get_pixel_data_with_warnings(
+    &self,
+  ) -> Result<
+    (ValueRepresentation, Vec<Frame<'_>>, Vec<PixelDataWarning>),
+    DataError,
+  >;
+
+  /// Returns a single frame of image data present in a data set. This gives
+  /// the same result as indexing into the frames returned by
+  /// [`Self::get_pixel_data()`], but only the requested frame is gathered. For
+  /// non-encapsulated pixel data only the frame's range of bytes is sliced,
+  /// and for encapsulated pixel data the offset tables are used to find the
+  /// frame's fragments.
+  ///
+  /// An error is returned if `index` is not less than the number of frames.
+  ///
+  fn get_frame(
+    &self,
+    index: usize,
+  ) -> Result<(ValueRepresentation, Frame<'_>), DataError>;
+
+  /// Returns the total size in bytes of the pixel data in a data set. For
+  /// non-encapsulated pixel data this is the length of its value, and for
+  /// encapsulated pixel data it is the sum of the lengths of its fragments,
+  /// excluding the basic offset table. This is cheaper than calling
+  /// [`Self::get_pixel_data()`] as frames aren't determined.
+  ///
+  fn total_pixel_bytes(&self) -> Result<u64, DataError>;
+
+  /// Returns the value of the *'(0028,7FE0) Pixel Data Provider URL'* data
+  /// element, if present. This is used by data sets that reference their pixel
+  /// data externally rather than embedding it in *'(7FE0,0010) Pixel Data'*.
+  ///
+  fn pixel_data_provider_url(&self) -> Option<&str>;
+
+  /// Returns the first window center and width pair stored in the
+  /// *'(0028,1050) Window Center'* and *'(0028,1051) Window Width'* data
+  /// elements, if both are present and hold at least one value. This is the
+  /// window that display code should use by default.
+  ///
+  fn default_window(&self) -> Option<(f64, f64)>;
 }
 
 impl DataSetPixelDataExtensions for DataSet {
   fn get_pixel_data(
     &self,
-  ) -> Result<(ValueRepresentation, Vec<Frame>), DataError> {
-    // Get the pixel data value
-    let pixel_data = self.get_value(dictionary::PIXEL_DATA.tag)?;
+  ) -> Result<(ValueRepresentation, Vec<Frame<'_>>), DataError> {
+    let (vr, frames, _warnings) = self.get_pixel_data_with_warnings()?;
 
-    // Get the extended offset table value, if present
-    let extended_offset_table = match parse_extended_offset_table(self) {
-      Ok(table) => Ok(Some(table)),
-      Err(data_error) => {
-        if data_error.is_tag_not_present() {
-          Ok(None)
-        } else {
-          Err(data_error)
-        }
-      }
-    }?;
+    Ok((vr, frames))
+  }
 
-    // Get the number of frames value, if present
-    let number_of_frames = self.get_int(dictionary::NUMBER_OF_FRAMES.tag).ok();
+  fn get_pixel_data_with_warnings(
+    &self,
+  ) -> Result<
+    (ValueRepresentation, Vec<Frame<'_>>, Vec<PixelDataWarning>),
+    DataError,
+  > {
+    let (pixel_data, number_of_frames, extended_offset_table) =
+      pixel_data_components(self)?;
+
+    check_frames_are_byte_aligned(self, pixel_data, number_of_frames)?;
+
+    let mut warnings = vec![];
+
+    // Enhanced multi-frame data sets should have one per-frame functional
+    // groups item per frame, so check this agrees with the number of frames.
+    // An absent number of frames means there is a single frame.
+    if let Ok(items) = self
+      .get_value(dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE.tag)
+      .and_then(|value| value.sequence_items())
+    {
+      let number_of_frames = number_of_frames.unwrap_or(1);
 
-    if let Some(n) = number_of_frames {
-      if n < 0 {
-        return Err(DataError::new_value_invalid(format!(
-          "Number of frames is invalid: {n}"
-        )));
+      if items.len() != number_of_frames {
+        warnings.push(PixelDataWarning::PerFrameFunctionalGroupsMismatch {
+          number_of_frames,
+          per_frame_functional_groups: items.len(),
+        });
       }
     }
 
     let frames = do_get_pixel_data(
       pixel_data,
-      number_of_frames.map(|n| n as usize),
+      number_of_frames,
       extended_offset_table,
+      &mut warnings,
     )?;
 
-    Ok((pixel_data.value_representation(), frames))
+    Ok((pixel_data_vr(pixel_data), frames, warnings))
+  }
+
+  fn get_frame(
+    &self,
+    index: usize,
+  ) -> Result<(ValueRepresentation, Frame<'_>), DataError> {
+    let (pixel_data, number_of_frames, extended_offset_table) =
+      pixel_data_components(self)?;
+
+    check_frames_are_byte_aligned(self, pixel_data, number_of_frames)?;
+
+    let frame =
+      do_get_frame(pixel_data, index, number_of_frames, extended_offset_table)?;
+
+    Ok((pixel_data_vr(pixel_data), frame))
+  }
+
+  fn total_pixel_bytes(&self) -> Result<u64, DataError> {
+    let pixel_data = self.get_value(dictionary::PIXEL_DATA.tag)?;
+
+    if let Ok(bytes) = pixel_data.bytes() {
+      return Ok(bytes.len() as u64);
+    }
+
+    if let Ok(items) = pixel_data.encapsulated_pixel_data() {
+      return Ok(items.iter().skip(1).map(|item| item.len() as u64).sum());
+    }
+
+    Err(DataError::new_value_not_present().with_path(
+      &DataSetPath::new_with_data_element(dictionary::PIXEL_DATA.tag),
+    ))
+  }
+
+  fn pixel_data_provider_url(&self) -> Option<&str> {
+    self
+      .get_string(dictionary::PIXEL_DATA_PROVIDER_URL.tag)
+      .ok()
+  }
+
+  fn default_window(&self) -> Option<(f64, f64)> {
+    let window_centers = self.get_floats(dictionary::WINDOW_CENTER.tag).ok()?;
+    let window_widths = self.get_floats(dictionary::WINDOW_WIDTH.tag).ok()?;
+
+    match (window_centers.first(), window_widths.first()) {
+      (Some(center), Some(width)) => Some((*center, *width)),
+      _ => None,
+    }
+  }
+}
+
+/// Returns the *'(7FE0,0010) Pixel Data'* value in a data set, along with the
+/// number of frames and the extended offset table, if present.
+///
+fn pixel_data_components(
+  data_set: &DataSet,
+) -> Result<
+  (
+    &DataElementValue,
+    Option<usize>,
+    Option<ExtendedOffsetTable>,
+  ),
+  DataError,
+> {
+  // Get the pixel data value. If it isn't present but there is a pixel data
+  // provider URL then the pixel data is stored externally.
+  let pixel_data = match data_set.get_value(dictionary::PIXEL_DATA.tag) {
+    Ok(pixel_data) => pixel_data,
+    Err(e) => {
+      return Err(match data_set.pixel_data_provider_url() {
+        Some(url) if e.is_tag_not_present() => {
+          DataError::new_value_external(url.to_string()).with_path(
+            &DataSetPath::new_with_data_element(dictionary::PIXEL_DATA.tag),
+          )
+        }
+        _ => e,
+      });
+    }
+  };
+
+  // Pixel data that is a sequence is malformed, so reject it up front with a
+  // specific error
+  if pixel_data.sequence_items().is_ok() {
+    return Err(
+      DataError::new_value_invalid(
+        "Pixel data is unexpectedly a sequence rather than native or \
+         encapsulated pixel data"
+          .to_string(),
+      )
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::PIXEL_DATA.tag,
+      )),
+    );
+  }
+
+  // Get the extended offset table value, if present
+  let extended_offset_table = match parse_extended_offset_table(data_set) {
+    Ok(table) => Ok(Some(table)),
+    Err(data_error) => {
+      if data_error.is_tag_not_present() {
+        Ok(None)
+      } else {
+        Err(data_error)
+      }
+    }
+  }?;
+
+  // Get the number of frames value, if present
+  let number_of_frames =
+    data_set.get_int(dictionary::NUMBER_OF_FRAMES.tag).ok();
+
+  if let Some(n) = number_of_frames {
+    if n < 0 {
+      return Err(DataError::new_value_invalid(format!(
+        "Number of frames is invalid: {n}"
+      )));
+    }
+  }
+
+  Ok((
+    pixel_data,
+    number_of_frames.map(|n| n as usize),
+    extended_offset_table,
+  ))
+}
+
+/// Checks that each frame of multi-frame native pixel data starts on a byte
+/// boundary, which requires the frame size in bits to be a multiple of 8. If
+/// the frame size can't be determined then no error is returned, and any
+/// inconsistency is instead detected when the pixel data is split into frames.
+///
+fn check_frames_are_byte_aligned(
+  data_set: &DataSet,
+  pixel_data: &DataElementValue,
+  number_of_frames: Option<usize>,
+) -> Result<(), DataError> {
+  if pixel_data.bytes().is_err() || number_of_frames.unwrap_or(1) <= 1 {
+    return Ok(());
+  }
+
+  match frame_size_bits(data_set) {
+    Ok(frame_size_bits) if !frame_size_bits.is_multiple_of(8) => Err(
+      DataError::new_value_not_byte_aligned(format!(
+        "Multi-frame pixel data has frames of {} bits that aren't aligned on \
+         byte boundaries",
+        frame_size_bits
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::PIXEL_DATA.tag,
+      )),
+    ),
+
+    _ => Ok(()),
+  }
+}
+
+/// Returns the VR of pixel data. Native pixel data with a VR of UN is treated
+/// as OB.
+///
+fn pixel_data_vr(pixel_data: &DataElementValue) -> ValueRepresentation {
+  match pixel_data.value_representation() {
+    ValueRepresentation::Unknown => ValueRepresentation::OtherByteString,
+    vr => vr,
+  }
+}
+
+fn do_get_pixel_data<'a>(
+  value: &'a DataElementValue,
+  number_of_frames: Option<usize>,
+  extended_offset_table: Option<ExtendedOffsetTable>,
+  warnings: &mut Vec<PixelDataWarning>,
+) -> Result<Vec<Frame<'a>>, DataError> {
+  let frame_locations =
+    locate_frames(value, number_of_frames, extended_offset_table, warnings)?;
+
+  Ok(
+    (0..frame_locations.len())
+      .map(|index| frame_locations.frame(index))
+      .collect(),
+  )
+}
+
+/// Returns a single frame of pixel data in the same way as
+/// [`do_get_pixel_data()`] does for all frames, but without gathering any of
+/// the other frames.
+///
+fn do_get_frame<'a>(
+  value: &'a DataElementValue,
+  index: usize,
+  number_of_frames: Option<usize>,
+  extended_offset_table: Option<ExtendedOffsetTable>,
+) -> Result<Frame<'a>, DataError> {
+  let frame_locations =
+    locate_frames(value, number_of_frames, extended_offset_table, &mut vec![])?;
+
+  if index >= frame_locations.len() {
+    return Err(DataError::new_value_invalid(format!(
+      "Frame index {} is out of range as there are {} frames",
+      index,
+      frame_locations.len()
+    )));
+  }
+
+  Ok(frame_locations.frame(index))
+}
+
+/// The location of each frame in a pixel data value. Frames are located
+/// without copying any pixel data, which allows a single frame to be gathered
+/// without gathering all of them.
+///
+enum FrameLocations<'a> {
+  /// Native pixel data made up of frames that are all the same size.
+  Native {
+    bytes: &'a [u8],
+    frame_count: usize,
+    frame_size: usize,
+  },
+
+  /// Encapsulated pixel data where each frame is made up of one or more
+  /// consecutive fragments, specified by the index of its first fragment and
+  /// its number of fragments.
+  Fragments {
+    fragments: &'a [Rc<Vec<u8>>],
+    frame_fragments: Vec<(usize, usize)>,
+  },
+
+  /// Encapsulated pixel data with an extended offset table, where each frame
+  /// is the initial bytes of a single fragment.
+  ExtendedOffsetTable {
+    fragments: &'a [Rc<Vec<u8>>],
+    frame_lengths: Vec<usize>,
+  },
+}
+
+impl<'a> FrameLocations<'a> {
+  /// Returns the number of frames.
+  ///
+  fn len(&self) -> usize {
+    match self {
+      Self::Native { frame_count, .. } => *frame_count,
+      Self::Fragments {
+        frame_fragments, ..
+      } => frame_fragments.len(),
+      Self::ExtendedOffsetTable { frame_lengths, .. } => frame_lengths.len(),
+    }
+  }
+
+  /// Returns the frame at the given index, which must be less than the number
+  /// of frames.
+  ///
+  fn frame(&self, index: usize) -> Frame<'a> {
+    match self {
+      Self::Native {
+        bytes, frame_size, ..
+      } => vec![&bytes[index * frame_size..(index + 1) * frame_size]],
+
+      Self::Fragments {
+        fragments,
+        frame_fragments,
+      } => {
+        let (first_fragment, fragment_count) = frame_fragments[index];
+
+        fragments[first_fragment..first_fragment + fragment_count]
+          .iter()
+          .map(|fragment| fragment.as_slice())
+          .collect()
+      }
+
+      Self::ExtendedOffsetTable {
+        fragments,
+        frame_lengths,
+      } => vec![&fragments[index][0..frame_lengths[index]]],
+    }
   }
 }
 
-fn do_get_pixel_data(
-  value: &DataElementValue,
+/// Locates the frames in a pixel data value. Native pixel data is split into
+/// frames of equal size, and the frames of encapsulated pixel data are located
+/// using its extended offset table or basic offset table, falling back to
+/// the number of frames when there is no offset table.
+///
+fn locate_frames<'a>(
+  value: &'a DataElementValue,
   number_of_frames: Option<usize>,
   extended_offset_table: Option<ExtendedOffsetTable>,
-) -> Result<Vec<Frame>, DataError> {
+  warnings: &mut Vec<PixelDataWarning>,
+) -> Result<FrameLocations<'a>, DataError> {
   let vr = value.value_representation();
 
-  // Non-encapsulated OB or OW pixel data
+  // Non-encapsulated OB or OW pixel data. Pixel data with a VR of UN is
+  // treated as OB.
   if let Ok(bytes) = value.bytes() {
-    if vr != ValueRepresentation::OtherByteString
-      && vr != ValueRepresentation::OtherWordString
-    {
-      return Err(DataError::new_value_not_present());
-    }
+    match vr {
+      ValueRepresentation::OtherByteString
+      | ValueRepresentation::OtherWordString => (),
 
-    return match number_of_frames {
-      None | Some(0) | Some(1) => Ok(vec![vec![bytes.as_slice()]]),
-
-      Some(number_of_frames) => {
-        let bytes_size = bytes.len();
-        let frame_size = bytes_size / number_of_frames;
-
-        // Check that the pixel data divides exactly into the number of frames.
-        // If it doesn't then it's either due to an inconsistency in the pixel
-        // data, or the pixel data for a single frame is not aligned on byte
-        // boundaries, which is not supported by this library. The latter is
-        // possible when the bits allocated value isn't a multiple of 8.
-        if number_of_frames * frame_size == bytes_size {
-          Ok(
-            (0..number_of_frames)
-              .map(|i| {
-                vec![&bytes.as_slice()[i * frame_size..((i + 1) * frame_size)]]
-              })
-              .collect(),
-          )
-        } else {
-          Err(DataError::new_value_invalid(format!(
-            "Multi-frame pixel data of length {} does not divide evenly into \
-             {} frames",
-            bytes_size, number_of_frames
-          )))
-        }
+      ValueRepresentation::Unknown => {
+        warnings.push(PixelDataWarning::UnknownValueRepresentation)
       }
+
+      _ => return Err(DataError::new_value_not_present()),
+    }
+
+    let (frame_count, frame_size) = match number_of_frames {
+      None | Some(0) | Some(1) => (1, bytes.len()),
+
+      Some(number_of_frames) => (
+        number_of_frames,
+        native_frame_size(bytes.len(), number_of_frames)?,
+      ),
     };
+
+    return Ok(FrameLocations::Native {
+      bytes,
+      frame_count,
+      frame_size,
+    });
   }
 
   if let Ok(items) = value.encapsulated_pixel_data() {
@@ -121,6 +576,8 @@ fn do_get_pixel_data(
       return Err(DataError::new_value_not_present());
     }
 
+    let fragments = &items[1..];
+
     // Encapsulated pixel data with an extended offset table present in the data
     // set. There should be no basic offset table, and the extended offset table
     // is used to define the frames.
@@ -135,134 +592,232 @@ fn do_get_pixel_data(
         ));
       }
 
-      let frames = fragments_to_frames_using_extended_offset_table(
-        items[1..].iter().map(|f| f.as_ref().as_slice()).collect(),
+      let frame_lengths = frame_lengths_using_extended_offset_table(
+        fragments,
         &extended_offset_table,
       )?;
 
-      return Ok(frames.iter().map(|i| vec![*i]).collect());
+      // The extended offset table is authoritative, so a number of frames
+      // value that disagrees with it is reported but otherwise ignored
+      if let Some(number_of_frames) = number_of_frames {
+        if number_of_frames != frame_lengths.len() {
+          warnings.push(PixelDataWarning::NumberOfFramesMismatch {
+            number_of_frames,
+            extended_offset_table_frames: frame_lengths.len(),
+          });
+        }
+      }
+
+      return Ok(FrameLocations::ExtendedOffsetTable {
+        fragments,
+        frame_lengths,
+      });
     }
 
     // Encapsulated pixel data with an empty basic offset table and a single
     // fragment. The sole fragment is treated as a single frame of pixel data.
-    if items.len() == 2 && items[0].is_empty() {
-      return Ok(vec![vec![&items[1]]]);
+    if fragments.len() == 1 && items[0].is_empty() {
+      return Ok(FrameLocations::Fragments {
+        fragments,
+        frame_fragments: vec![(0, 1)],
+      });
     }
 
     // Encapsulated pixel data with an empty basic offset table and multiple
     // fragments. Use the number of frames to decide what to do.
-    if !items.is_empty() && items[0].is_empty() {
-      let fragments = &items[1..];
-
-      return match number_of_frames {
+    if items[0].is_empty() {
+      let frame_fragments = match number_of_frames {
         // Exactly one frame, so all fragments must belong to it
-        None | Some(1) => {
-          Ok(vec![fragments.iter().map(|f| f.as_slice()).collect()])
-        }
+        None | Some(1) => vec![(0, fragments.len())],
 
         // The same number of fragments as frames, so each fragment is its own
         // frame
         Some(number_of_frames) if number_of_frames == fragments.len() => {
-          Ok(fragments.iter().map(|f| vec![f.as_slice()]).collect())
+          (0..fragments.len()).map(|i| (i, 1)).collect()
         }
 
         // There is a different number of fragments and frames. Given there is
         // no basic offset table, this means there's no way to allocate
         // fragments to frames.
-        _ => Err(DataError::new_value_invalid(
-          "Encapsulated pixel data structure can't be determined".to_string(),
-        )),
+        _ => {
+          return Err(DataError::new_value_invalid(
+            "Encapsulated pixel data structure can't be determined".to_string(),
+          ))
+        }
       };
+
+      return Ok(FrameLocations::Fragments {
+        fragments,
+        frame_fragments,
+      });
     }
 
     // Encapsulated pixel data with a basic offset table. A single frame can be
     // spread over one or more fragments.
-    if items.len() > 1 {
-      let basic_offset_table = &items[0];
+    if !fragments.is_empty() {
+      let basic_offset_table_values = parse_basic_offset_table(items)?;
+
+      let fragment_lengths: Vec<u64> = fragments
+        .iter()
+        .map(|fragment| fragment.len() as u64)
+        .collect();
+
+      let frame_fragment_counts =
+        frame_fragment_counts_using_basic_offset_table(
+          &fragment_lengths,
+          &basic_offset_table_values[1..],
+        )?;
+
+      // Find the index of the first fragment of each frame
+      let mut first_fragment = 0;
+      let frame_fragments = frame_fragment_counts
+        .into_iter()
+        .map(|count| {
+          first_fragment += count;
+          (first_fragment - count, count)
+        })
+        .collect();
+
+      return Ok(FrameLocations::Fragments {
+        fragments,
+        frame_fragments,
+      });
+    }
+  }
 
-      // Decode the 32-bit integers in the basic offset table data
-      if basic_offset_table.len() % 4 != 0 {
-        return Err(DataError::new_value_invalid(
-          "Encapsulated pixel data basic offset table is invalid".to_string(),
-        ));
-      }
-      let mut basic_offset_table_values =
-        vec![0u32; basic_offset_table.len() / 4];
-      byteorder::LittleEndian::read_u32_into(
-        basic_offset_table.as_slice(),
-        basic_offset_table_values.as_mut_slice(),
-      );
+  Err(DataError::new_value_not_present())
+}
 
-      // Check the basic offset table is sorted
-      if !basic_offset_table_values.windows(2).all(|w| w[0] <= w[1]) {
-        return Err(DataError::new_value_invalid(
-          "Encapsulated pixel data basic offset table is not sorted"
-            .to_string(),
-        ));
-      }
+/// Returns the size in bytes of each frame of native pixel data of the given
+/// length that holds the specified number of frames.
+///
+/// Every frame must hold at least one byte, so the number of frames can't
+/// exceed the length of the pixel data. This guards against a malformed
+/// *'(0028,0008) Number of Frames'* value causing a huge number of empty frames
+/// to be allocated. The pixel data must also divide exactly into the number
+/// of frames. If it doesn't then there is an inconsistency in the pixel data.
+/// Frames that aren't aligned on byte boundaries have already been reported by
+/// [`check_frames_are_byte_aligned()`].
+///
+fn native_frame_size(
+  length: usize,
+  number_of_frames: usize,
+) -> Result<usize, DataError> {
+  if number_of_frames > length {
+    return Err(DataError::new_value_invalid(format!(
+      "Number of frames {} exceeds the length of the pixel data of {} bytes",
+      number_of_frames, length
+    )));
+  }
 
-      // The first item in the basic offset table should always be zero
-      if basic_offset_table_values[0] != 0 {
-        return Err(DataError::new_value_invalid(
-          "Encapsulated pixel data basic offset table does not start at zero"
-            .to_string(),
-        ));
-      }
+  let frame_size = length / number_of_frames;
 
-      // Turn the flat list of fragments into a list of frames
-      let frames = fragments_to_frames_using_basic_offset_table(
-        items[1..].iter().map(|f| f.as_ref().as_slice()).collect(),
-        &basic_offset_table_values[1..],
-      )?;
+  if number_of_frames * frame_size != length {
+    return Err(DataError::new_value_invalid(format!(
+      "Multi-frame pixel data of length {} does not divide evenly into {} \
+       frames",
+      length, number_of_frames
+    )));
+  }
+
+  Ok(frame_size)
+}
+
+/// Decodes the 32-bit offsets in the basic offset table of encapsulated pixel
+/// data, which is its first item, and checks that they are valid.
+///
+fn parse_basic_offset_table(
+  items: &[Rc<Vec<u8>>],
+) -> Result<Vec<u32>, DataError> {
+  let basic_offset_table = &items[0];
+
+  // Decode the 32-bit integers in the basic offset table data
+  if !basic_offset_table.len().is_multiple_of(4) {
+    return Err(DataError::new_value_invalid(
+      "Encapsulated pixel data basic offset table is invalid".to_string(),
+    ));
+  }
+  let mut basic_offset_table_values = vec![0u32; basic_offset_table.len() / 4];
+  byteorder::LittleEndian::read_u32_into(
+    basic_offset_table.as_slice(),
+    basic_offset_table_values.as_mut_slice(),
+  );
 
-      return Ok(frames);
+  // Check the basic offset table is sorted. If it isn't, and the fragments
+  // are too large to be addressed by 32-bit offsets, then the offsets have
+  // most likely wrapped around, so report that specifically.
+  if !basic_offset_table_values.windows(2).all(|w| w[0] <= w[1]) {
+    let total_length: u64 =
+      items[1..].iter().map(|item| item.len() as u64 + 8).sum();
+    if total_length > u32::MAX as u64 {
+      return Err(basic_offset_table_overflow_error());
     }
+
+    return Err(DataError::new_value_invalid(
+      "Encapsulated pixel data basic offset table is not sorted".to_string(),
+    ));
   }
 
-  Err(DataError::new_value_not_present())
+  // The first item in the basic offset table should always be zero
+  if basic_offset_table_values[0] != 0 {
+    return Err(DataError::new_value_invalid(
+      "Encapsulated pixel data basic offset table does not start at zero"
+        .to_string(),
+    ));
+  }
+
+  Ok(basic_offset_table_values)
 }
 
-/// Takes a list of pixel data fragments and turns them into a list of frames
-/// using a basic offset table. A single frame can be made up of one or more
-/// fragments, and the basic offset table specifies where the frame boundaries
-/// lie.
+/// Takes the lengths of a list of pixel data fragments and returns the number
+/// of fragments in each frame as specified by a basic offset table.
+///
+/// Basic offset tables use 32-bit offsets, and so are only able to address
+/// frames that start in the first 4 GiB of the encapsulated pixel data. An
+/// error is returned if the fragments extend past this limit before the basic
+/// offset table is exhausted, as this data must use an extended offset table
+/// instead.
 ///
-fn fragments_to_frames_using_basic_offset_table<'a>(
-  fragments: Vec<&'a [u8]>,
+fn frame_fragment_counts_using_basic_offset_table(
+  mut fragment_lengths: &[u64],
   mut basic_offset_table: &[u32],
-) -> Result<Vec<Vec<&'a [u8]>>, DataError> {
-  let mut offset = 0usize;
-  let mut fragments = fragments.as_slice();
+) -> Result<Vec<usize>, DataError> {
+  let mut offset = 0u64;
 
-  let mut current_frame: Vec<&'a [u8]> = vec![];
-  let mut frames: Vec<Vec<&'a [u8]>> = vec![];
+  let mut current_frame_fragment_count = 0usize;
+  let mut frame_fragment_counts = vec![];
 
   loop {
     // When the basic offset table has no more entries, all remaining fragments
     // constitute the final frame
     if basic_offset_table.is_empty() {
-      if !fragments.is_empty() {
-        frames.push(fragments.to_vec());
+      if !fragment_lengths.is_empty() {
+        frame_fragment_counts.push(fragment_lengths.len());
       }
 
       break;
     }
 
-    if fragments.is_empty() {
+    if fragment_lengths.is_empty() {
       return Err(DataError::new_value_invalid(
         "Encapsulated pixel data basic offset table is malformed".to_string(),
       ));
     }
 
-    let next_frame_offset = basic_offset_table[0] as usize;
+    let next_frame_offset = basic_offset_table[0] as u64;
 
     // Add the next fragment to the current frame
-    let fragment = &fragments[0];
-    current_frame.push(fragment);
-    fragments = &fragments[1..];
+    current_frame_fragment_count += 1;
 
     // Increment the offset, with an extra 8 bytes for the item header
-    offset += fragment.len() + 8;
+    offset += fragment_lengths[0] + 8;
+    fragment_lengths = &fragment_lengths[1..];
+
+    // If the offset now exceeds what can be stored in a basic offset table
+    // then the next frame can't be located
+    if offset > u32::MAX as u64 {
+      return Err(basic_offset_table_overflow_error());
+    }
 
     // If the offset now exceeds the offset to the next frame, then the values
     // in the basic offset table are invalid
@@ -276,14 +831,25 @@ fn fragments_to_frames_using_basic_offset_table<'a>(
     // this frame is now complete, so add it to the list and start gathering
     // fragments for the next frame
     if offset == next_frame_offset {
-      frames.push(current_frame);
-      current_frame = Vec::new();
+      frame_fragment_counts.push(current_frame_fragment_count);
+      current_frame_fragment_count = 0;
 
       basic_offset_table = &basic_offset_table[1..];
     }
   }
 
-  Ok(frames)
+  Ok(frame_fragment_counts)
+}
+
+/// Returns the error used when encapsulated pixel data is too large for its
+/// frames to be located using a basic offset table.
+///
+fn basic_offset_table_overflow_error() -> DataError {
+  DataError::new_value_invalid(
+    "Encapsulated pixel data exceeds the 4 GiB that can be addressed by the \
+     basic offset table, an extended offset table must be used instead"
+      .to_string(),
+  )
 }
 
 struct ExtendedOffsetTableEntry {
@@ -359,14 +925,14 @@ fn parse_extended_offset_table(
   Ok(entries)
 }
 
-/// Takes a list of pixel data fragments and turns them into a list of frames
-/// using an extended offset table. Each frame is made up of exactly one
-/// fragment.
+/// Takes a list of pixel data fragments and returns the length of each frame
+/// as specified by an extended offset table. Each frame is made up of exactly
+/// one fragment.
 ///
-fn fragments_to_frames_using_extended_offset_table<'a>(
-  fragments: Vec<&'a [u8]>,
+fn frame_lengths_using_extended_offset_table(
+  fragments: &[Rc<Vec<u8>>],
   extended_offset_table: &ExtendedOffsetTable,
-) -> Result<Vec<&'a [u8]>, DataError> {
+) -> Result<Vec<usize>, DataError> {
   if fragments.len() != extended_offset_table.len() {
     return Err(DataError::new_value_invalid(
       "Encapsulated pixel data extended offset table size does not match \
@@ -376,7 +942,7 @@ fn fragments_to_frames_using_extended_offset_table<'a>(
   }
 
   let mut current_offset = 0u64;
-  let mut frames = vec![];
+  let mut frame_lengths = Vec::with_capacity(fragments.len());
 
   for (entry, fragment) in extended_offset_table.iter().zip(fragments.iter()) {
     // Check the extended offset table's offset matches the offset of this
@@ -399,16 +965,203 @@ fn fragments_to_frames_using_extended_offset_table<'a>(
       )));
     }
 
-    // Slice the bytes for the frame from the fragment. The frame length is
-    // allowed to be less than the size of the fragment, which can be used
-    // in cases where the frame's data is of odd length, as fragment length
-    // is always even.
-    frames.push(&fragment[0..(entry.length as usize)]);
+    // The frame length is allowed to be less than the size of the fragment,
+    // which can be used in cases where the frame's data is of odd length, as
+    // fragment length is always even
+    frame_lengths.push(entry.length as usize);
 
     current_offset += entry.length + 8;
   }
 
-  Ok(frames)
+  Ok(frame_lengths)
+}
+
+/// Checks that the *'(0028,0100) Bits Allocated'*, *'(0028,0101) Bits
+/// Stored'*, *'(0028,0102) High Bit'*, and *'(0028,0103) Pixel
+/// Representation'* data elements in a data set are present and consistent
+/// with each other. This allows malformed pixel data attributes to be detected
+/// prior to decoding any pixel data.
+///
+pub fn validate_pixel_attributes(data_set: &DataSet) -> Result<(), DataError> {
+  let bits_allocated = data_set.get_int(dictionary::BITS_ALLOCATED.tag)?;
+  let bits_stored = data_set.get_int(dictionary::BITS_STORED.tag)?;
+  let high_bit = data_set.get_int(dictionary::HIGH_BIT.tag)?;
+  let pixel_representation =
+    data_set.get_int(dictionary::PIXEL_REPRESENTATION.tag)?;
+
+  if bits_stored < 1 || bits_stored > bits_allocated {
+    return Err(
+      DataError::new_value_invalid(format!(
+        "Bits stored value of {} is not in the range 1-{}, where {} is the bits \
+         allocated value",
+        bits_stored, bits_allocated, bits_allocated
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::BITS_STORED.tag,
+      )),
+    );
+  }
+
+  if high_bit != bits_stored - 1 {
+    return Err(
+      DataError::new_value_invalid(format!(
+        "High bit value of {} is not one less than the bits stored value of {}",
+        high_bit, bits_stored
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::HIGH_BIT.tag,
+      )),
+    );
+  }
+
+  if pixel_representation != 0 && pixel_representation != 1 {
+    return Err(
+      DataError::new_value_invalid(format!(
+        "Pixel representation value of {} is not 0 or 1",
+        pixel_representation
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::PIXEL_REPRESENTATION.tag,
+      )),
+    );
+  }
+
+  Ok(())
+}
+
+/// Returns the size in bytes of a single frame of native pixel data, as
+/// determined by the *'(0028,0010) Rows'*, *'(0028,0011) Columns'*,
+/// *'(0028,0002) Samples per Pixel'*, and *'(0028,0100) Bits Allocated'* values
+/// in a data set. When the bits allocated value isn't a multiple of 8, e.g. for
+/// bit-packed data with 1 bit allocated, the size is rounded up to a whole
+/// number of bytes.
+///
+pub fn expected_frame_size_bytes(
+  data_set: &DataSet,
+) -> Result<usize, DataError> {
+  Ok(frame_size_bits(data_set)?.div_ceil(8))
+}
+
+/// Checks that the length of the native pixel data in a data set matches the
+/// length implied by its frame size, see [`expected_frame_size_bytes()`], and
+/// its *'(0028,0008) Number of Frames'* value. A single frame is assumed if
+/// the number of frames isn't present. A trailing padding byte that makes the
+/// pixel data even length is allowed.
+///
+/// An error is returned if the lengths don't match, or if the pixel data is
+/// encapsulated.
+///
+pub fn validate_pixel_data_length(data_set: &DataSet) -> Result<(), DataError> {
+  let frame_size_bits = frame_size_bits(data_set)?;
+
+  let number_of_frames =
+    match data_set.get_int(dictionary::NUMBER_OF_FRAMES.tag) {
+      Ok(number_of_frames) => number_of_frames.max(0) as usize,
+      Err(e) if e.is_tag_not_present() => 1,
+      Err(e) => return Err(e),
+    };
+
+  let pixel_data_path =
+    DataSetPath::new_with_data_element(dictionary::PIXEL_DATA.tag);
+
+  let bytes = match data_set.get_value(dictionary::PIXEL_DATA.tag)?.bytes() {
+    Ok(bytes) => bytes,
+    Err(_) => {
+      return Err(
+        DataError::new_value_invalid(
+          "Pixel data length can only be validated for native pixel data"
+            .to_string(),
+        )
+        .with_path(&pixel_data_path),
+      )
+    }
+  };
+
+  let expected_length =
+    frame_size_bits.saturating_mul(number_of_frames).div_ceil(8);
+  let actual_length = bytes.len();
+
+  if actual_length == expected_length
+    || (actual_length == expected_length + 1 && expected_length % 2 == 1)
+  {
+    return Ok(());
+  }
+
+  Err(
+    DataError::new_value_invalid(format!(
+      "Pixel data length is {} bytes but {} bytes were expected for {} \
+       frame(s) of {}x{} pixels",
+      actual_length,
+      expected_length,
+      number_of_frames,
+      data_set.get_int(dictionary::COLUMNS.tag)?,
+      data_set.get_int(dictionary::ROWS.tag)?,
+    ))
+    .with_path(&pixel_data_path),
+  )
+}
+
+/// Returns the size in bits of a single frame of native pixel data.
+///
+fn frame_size_bits(data_set: &DataSet) -> Result<usize, DataError> {
+  let rows = data_set.get_int(dictionary::ROWS.tag)?;
+  let columns = data_set.get_int(dictionary::COLUMNS.tag)?;
+  let samples_per_pixel =
+    data_set.get_int(dictionary::SAMPLES_PER_PIXEL.tag)?;
+  let bits_allocated = data_set.get_int(dictionary::BITS_ALLOCATED.tag)?;
+
+  Ok(
+    [rows, columns, samples_per_pixel, bits_allocated]
+      .iter()
+      .map(|value| (*value).max(0) as usize)
+      .product(),
+  )
+}
+
+/// Builds an encapsulated pixel data value that holds the given frames, with
+/// one fragment per frame. Frames of odd length are padded with a trailing zero
+/// byte to make them even length, and the first item is a basic offset table
+/// that holds the offset of each frame's fragment. The VR must be either
+/// [`ValueRepresentation::OtherByteString`] or
+/// [`ValueRepresentation::OtherWordString`].
+///
+/// An error is returned if the offset of a frame doesn't fit in the 32-bit
+/// basic offset table, in which case an extended offset table is needed.
+///
+pub fn build_encapsulated_pixel_data(
+  frames: &[Vec<u8>],
+  vr: ValueRepresentation,
+) -> Result<DataElementValue, DataError> {
+  let mut basic_offset_table = Vec::with_capacity(frames.len() * 4);
+  let mut items = Vec::with_capacity(frames.len() + 1);
+  let mut offset: u64 = 0;
+
+  for frame in frames {
+    let offset_u32 = u32::try_from(offset).map_err(|_| {
+      DataError::new_value_invalid(format!(
+        "Frame offset of {} bytes is too large for the basic offset table",
+        offset
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::PIXEL_DATA.tag,
+      ))
+    })?;
+    basic_offset_table.extend_from_slice(&offset_u32.to_le_bytes());
+
+    let mut fragment = frame.clone();
+    if fragment.len() % 2 == 1 {
+      fragment.push(0);
+    }
+
+    // Each fragment is preceded by an 8-byte item header
+    offset += fragment.len() as u64 + 8;
+
+    items.push(Rc::new(fragment));
+  }
+
+  items.insert(0, Rc::new(basic_offset_table));
+
+  DataElementValue::new_encapsulated_pixel_data(vr, items)
 }
 
 /// Returns the file extension to use for raw image data in the given transfer
@@ -594,6 +1347,26 @@ mod tests {
       ))
     );
 
+    // Read frames specified by an extended offset table that disagrees with
+    // the number of frames
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[2])
+      .unwrap();
+    assert_eq!(
+      ds.get_pixel_data_with_warnings(),
+      Ok((
+        ValueRepresentation::OtherByteString,
+        vec![
+          vec!["1".repeat(0x4C6).as_bytes().to_vec().as_slice()],
+          vec!["2".repeat(0x24A).as_bytes().to_vec().as_slice()],
+          vec!["3".repeat(0x627).as_bytes().to_vec().as_slice()],
+        ],
+        vec![PixelDataWarning::NumberOfFramesMismatch {
+          number_of_frames: 2,
+          extended_offset_table_frames: 3
+        }]
+      ))
+    );
+
     // Read three fragments into a single frame
     // Taken from the DICOM standard. Ref: PS3.5 Table A.4-1.
     assert_eq!(
@@ -656,5 +1429,671 @@ mod tests {
         ]
       ))
     );
+
+    // Read a single frame spread over multiple fragments, with a basic offset
+    // table that has exactly one entry
+    let mut ds = data_set_with_three_fragments.clone();
+    let mut items = ds
+      .get_value(dictionary::PIXEL_DATA.tag)
+      .unwrap()
+      .encapsulated_pixel_data()
+      .unwrap()
+      .clone();
+    items[0] = Rc::new(vec![0, 0, 0, 0]);
+    ds.insert(
+      dictionary::PIXEL_DATA.tag,
+      DataElementValue::new_encapsulated_pixel_data(
+        ValueRepresentation::OtherByteString,
+        items.clone(),
+      )
+      .unwrap(),
+    );
+
+    let single_frame = Ok((
+      ValueRepresentation::OtherByteString,
+      vec![vec![
+        items[1].as_slice(),
+        items[2].as_slice(),
+        items[3].as_slice(),
+      ]],
+    ));
+
+    assert_eq!(ds.get_pixel_data(), single_frame);
+
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[1])
+      .unwrap();
+    assert_eq!(ds.get_pixel_data(), single_frame);
+
+    // Read a single frame in a single fragment, with a basic offset table that
+    // has exactly one entry
+    let mut ds = DataSet::new();
+    ds.insert(
+      dictionary::PIXEL_DATA.tag,
+      DataElementValue::new_encapsulated_pixel_data(
+        ValueRepresentation::OtherByteString,
+        items[0..2].to_vec(),
+      )
+      .unwrap(),
+    );
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[1])
+      .unwrap();
+    assert_eq!(
+      ds.get_pixel_data(),
+      Ok((
+        ValueRepresentation::OtherByteString,
+        vec![vec!["1".repeat(0x4C6).as_bytes().to_vec().as_slice()]]
+      ))
+    );
+  }
+
+  #[test]
+  fn pixel_data_provider_url_test() {
+    let mut ds = DataSet::new();
+    assert_eq!(ds.pixel_data_provider_url(), None);
+    assert!(ds.get_pixel_data().unwrap_err().is_tag_not_present());
+
+    ds.insert(
+      dictionary::PIXEL_DATA_PROVIDER_URL.tag,
+      DataElementValue::new_universal_resource_identifier(
+        "https://example.com/pixel-data",
+      )
+      .unwrap(),
+    );
+
+    assert_eq!(
+      ds.pixel_data_provider_url(),
+      Some("https://example.com/pixel-data")
+    );
+    assert_eq!(
+      ds.get_pixel_data(),
+      Err(
+        DataError::new_value_external(
+          "https://example.com/pixel-data".to_string()
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::PIXEL_DATA.tag
+        ))
+      )
+    );
+  }
+
+  #[test]
+  fn get_pixel_data_number_of_frames_too_large_test() {
+    for bytes in [vec![], vec![1, 2, 3, 4]] {
+      let mut ds = DataSet::new();
+      ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[i32::MAX as i64])
+        .unwrap();
+      ds.insert_binary_value(
+        dictionary::PIXEL_DATA.tag,
+        ValueRepresentation::OtherByteString,
+        Rc::new(bytes.clone()),
+      )
+      .unwrap();
+
+      let error = DataError::new_value_invalid(format!(
+        "Number of frames 2147483647 exceeds the length of the pixel data of \
+         {} bytes",
+        bytes.len()
+      ));
+
+      assert_eq!(ds.get_pixel_data(), Err(error.clone()));
+      assert_eq!(ds.get_frame(0), Err(error));
+
+      // Validating the pixel data length doesn't overflow
+      ds.insert_int_value(&dictionary::ROWS, &[0xFFFF]).unwrap();
+      ds.insert_int_value(&dictionary::COLUMNS, &[0xFFFF])
+        .unwrap();
+      ds.insert_int_value(&dictionary::SAMPLES_PER_PIXEL, &[3])
+        .unwrap();
+      ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[16])
+        .unwrap();
+      assert!(validate_pixel_data_length(&ds).is_err());
+
+      ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[1])
+        .unwrap();
+      assert!(unpack_1_bit_frames(&ds).is_err());
+
+      ds.insert_int_value(&dictionary::ROWS, &[0]).unwrap();
+      assert!(unpack_1_bit_frames(&ds).is_err());
+    }
+  }
+
+  #[test]
+  fn get_pixel_data_zero_copy_test() {
+    /// Returns whether a fragment of a frame lies within the given bytes, i.e.
+    /// it is a slice of them rather than a copy.
+    ///
+    fn is_slice_of(fragment: &[u8], bytes: &[u8]) -> bool {
+      bytes.as_ptr_range().contains(&fragment.as_ptr())
+        && fragment.as_ptr_range().end <= bytes.as_ptr_range().end
+    }
+
+    // Native multi-frame pixel data
+    let mut ds = DataSet::new();
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[2])
+      .unwrap();
+    ds.insert_binary_value(
+      dictionary::PIXEL_DATA.tag,
+      ValueRepresentation::OtherByteString,
+      Rc::new(vec![1, 2, 3, 4]),
+    )
+    .unwrap();
+
+    let bytes = ds
+      .get_value(dictionary::PIXEL_DATA.tag)
+      .unwrap()
+      .bytes()
+      .unwrap();
+    let (_, frames) = ds.get_pixel_data().unwrap();
+    assert_eq!(frames[0][0].as_ptr(), bytes.as_ptr());
+    assert_eq!(frames[1][0].as_ptr(), bytes[2..].as_ptr());
+
+    let (_, frame) = ds.get_frame(1).unwrap();
+    assert_eq!(frame[0].as_ptr(), bytes[2..].as_ptr());
+
+    // Encapsulated pixel data with a single fragment, and with a basic offset
+    // table
+    for items in [
+      vec![Rc::new(vec![]), Rc::new(vec![1, 2])],
+      vec![
+        Rc::new(vec![0, 0, 0, 0, 10, 0, 0, 0]),
+        Rc::new(vec![1, 2]),
+        Rc::new(vec![3, 4]),
+      ],
+    ] {
+      let mut ds = DataSet::new();
+      ds.insert(
+        dictionary::PIXEL_DATA.tag,
+        DataElementValue::new_encapsulated_pixel_data(
+          ValueRepresentation::OtherByteString,
+          items,
+        )
+        .unwrap(),
+      );
+
+      let fragments = ds
+        .get_value(dictionary::PIXEL_DATA.tag)
+        .unwrap()
+        .encapsulated_pixel_data()
+        .unwrap();
+      let (_, frames) = ds.get_pixel_data().unwrap();
+      assert_eq!(frames.len(), fragments.len() - 1);
+
+      for (frame, fragment) in frames.iter().zip(fragments.iter().skip(1)) {
+        assert!(is_slice_of(frame[0], fragment));
+      }
+    }
+  }
+
+  #[test]
+  fn get_pixel_data_unknown_vr_test() {
+    let mut ds = DataSet::new();
+    ds.insert_binary_value(
+      dictionary::PIXEL_DATA.tag,
+      ValueRepresentation::Unknown,
+      Rc::new(vec![1, 2, 3, 4]),
+    )
+    .unwrap();
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[2])
+      .unwrap();
+
+    assert_eq!(
+      ds.get_pixel_data_with_warnings(),
+      Ok((
+        ValueRepresentation::OtherByteString,
+        vec![vec![[1, 2].as_slice()], vec![[3, 4].as_slice()]],
+        vec![PixelDataWarning::UnknownValueRepresentation]
+      ))
+    );
+  }
+
+  #[test]
+  fn get_frame_test() {
+    let encapsulated_pixel_data = |items: Vec<Vec<u8>>| {
+      DataElementValue::new_encapsulated_pixel_data(
+        ValueRepresentation::OtherByteString,
+        items.into_iter().map(Rc::new).collect(),
+      )
+      .unwrap()
+    };
+
+    let three_fragments = encapsulated_pixel_data(vec![
+      vec![],
+      vec![1; 0x4C6],
+      vec![2; 0x24A],
+      vec![3; 0x628],
+    ]);
+
+    let mut data_sets = vec![];
+
+    // Non-encapsulated pixel data with one and two frames
+    for number_of_frames in [1, 2] {
+      let mut ds = DataSet::new();
+      ds.insert_binary_value(
+        dictionary::PIXEL_DATA.tag,
+        ValueRepresentation::OtherWordString,
+        Rc::new(vec![1, 2, 3, 4]),
+      )
+      .unwrap();
+      ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[number_of_frames])
+        .unwrap();
+      data_sets.push(ds);
+    }
+
+    // Encapsulated pixel data with no basic offset table, as a single frame
+    // and with one fragment per frame
+    let mut ds = DataSet::new();
+    ds.insert(dictionary::PIXEL_DATA.tag, three_fragments.clone());
+    data_sets.push(ds.clone());
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[3])
+      .unwrap();
+    data_sets.push(ds);
+
+    // Encapsulated pixel data with a basic offset table. Ref: PS3.5 Table
+    // A.4-2.
+    let mut ds = DataSet::new();
+    ds.insert(
+      dictionary::PIXEL_DATA.tag,
+      encapsulated_pixel_data(vec![
+        vec![0, 0, 0, 0, 0x46, 0x06, 0, 0],
+        vec![b'A'; 0x2C8],
+        vec![b'a'; 0x36E],
+        vec![b'B'; 0xBC8],
+      ]),
+    );
+    data_sets.push(ds);
+
+    // Encapsulated pixel data with an extended offset table
+    let mut ds = DataSet::new();
+    ds.insert(dictionary::PIXEL_DATA.tag, three_fragments);
+    for (tag, values) in [
+      (dictionary::EXTENDED_OFFSET_TABLE.tag, [0u64, 0x4CE, 0x720]),
+      (
+        dictionary::EXTENDED_OFFSET_TABLE_LENGTHS.tag,
+        [0x4C6, 0x24A, 0x627],
+      ),
+    ] {
+      ds.insert_binary_value(
+        tag,
+        ValueRepresentation::OtherVeryLongString,
+        Rc::new(values.iter().flat_map(|v| v.to_le_bytes()).collect()),
+      )
+      .unwrap();
+    }
+    data_sets.push(ds);
+
+    for ds in data_sets {
+      let (vr, frames) = ds.get_pixel_data().unwrap();
+
+      for (index, frame) in frames.iter().enumerate() {
+        assert_eq!(ds.get_frame(index), Ok((vr, frame.clone())));
+      }
+
+      assert_eq!(
+        ds.get_frame(frames.len()),
+        Err(DataError::new_value_invalid(format!(
+          "Frame index {} is out of range as there are {} frames",
+          frames.len(),
+          frames.len()
+        )))
+      );
+    }
+
+    let mut ds = DataSet::new();
+    ds.insert_binary_value(
+      dictionary::PIXEL_DATA.tag,
+      ValueRepresentation::OtherByteString,
+      Rc::new(vec![1, 2, 3, 4]),
+    )
+    .unwrap();
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[3])
+      .unwrap();
+    assert_eq!(
+      ds.get_frame(0).unwrap_err(),
+      ds.get_pixel_data().unwrap_err()
+    );
+  }
+
+  #[test]
+  fn get_pixel_data_per_frame_functional_groups_test() {
+    let mut ds = DataSet::new();
+    ds.insert_binary_value(
+      dictionary::PIXEL_DATA.tag,
+      ValueRepresentation::OtherByteString,
+      Rc::new(vec![1, 2, 3, 4]),
+    )
+    .unwrap();
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[2])
+      .unwrap();
+    ds.insert_sequence_value(
+      &dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE,
+      vec![DataSet::new(), DataSet::new()],
+    )
+    .unwrap();
+
+    let frames = vec![vec![[1, 2].as_slice()], vec![[3, 4].as_slice()]];
+
+    assert_eq!(
+      ds.get_pixel_data_with_warnings(),
+      Ok((ValueRepresentation::OtherByteString, frames.clone(), vec![]))
+    );
+
+    ds.insert_sequence_value(
+      &dictionary::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE,
+      vec![DataSet::new(), DataSet::new(), DataSet::new()],
+    )
+    .unwrap();
+
+    assert_eq!(
+      ds.get_pixel_data_with_warnings(),
+      Ok((
+        ValueRepresentation::OtherByteString,
+        frames,
+        vec![PixelDataWarning::PerFrameFunctionalGroupsMismatch {
+          number_of_frames: 2,
+          per_frame_functional_groups: 3,
+        }]
+      ))
+    );
+  }
+
+  #[test]
+  fn get_pixel_data_sequence_test() {
+    let mut ds = DataSet::new();
+    ds.insert(
+      dictionary::PIXEL_DATA.tag,
+      DataElementValue::new_sequence(vec![DataSet::new()]),
+    );
+
+    assert_eq!(
+      ds.get_pixel_data(),
+      Err(
+        DataError::new_value_invalid(
+          "Pixel data is unexpectedly a sequence rather than native or \
+           encapsulated pixel data"
+            .to_string()
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::PIXEL_DATA.tag
+        ))
+      )
+    );
+  }
+
+  #[test]
+  fn total_pixel_bytes_test() {
+    let mut ds = DataSet::new();
+    assert!(ds.total_pixel_bytes().unwrap_err().is_tag_not_present());
+
+    ds.insert(
+      dictionary::PIXEL_DATA.tag,
+      DataElementValue::new_binary(
+        ValueRepresentation::OtherWordString,
+        Rc::new(vec![0; 512 * 512 * 2]),
+      )
+      .unwrap(),
+    );
+    assert_eq!(ds.total_pixel_bytes(), Ok(512 * 512 * 2));
+
+    ds.insert(
+      dictionary::PIXEL_DATA.tag,
+      DataElementValue::new_encapsulated_pixel_data(
+        ValueRepresentation::OtherByteString,
+        vec![
+          Rc::new(vec![0, 0, 0, 0, 0x46, 0x06, 0, 0]),
+          Rc::new(vec![0; 0x2C8]),
+          Rc::new(vec![0; 0x36E]),
+          Rc::new(vec![0; 0xBC8]),
+        ],
+      )
+      .unwrap(),
+    );
+    assert_eq!(ds.total_pixel_bytes(), Ok(0x2C8 + 0x36E + 0xBC8));
+  }
+
+  #[test]
+  fn default_window_test() {
+    let mut ds = DataSet::new();
+    assert_eq!(ds.default_window(), None);
+
+    ds.insert_float_value(&dictionary::WINDOW_CENTER, &[40.0, 300.0])
+      .unwrap();
+    assert_eq!(ds.default_window(), None);
+
+    ds.insert_float_value(&dictionary::WINDOW_WIDTH, &[400.0, 1500.0])
+      .unwrap();
+    assert_eq!(ds.default_window(), Some((40.0, 400.0)));
+  }
+
+  #[test]
+  fn frame_fragment_counts_using_basic_offset_table_test() {
+    let max_offset = u32::MAX as u64;
+
+    // Frames that start right up to the 32-bit boundary are allowed
+    assert_eq!(
+      frame_fragment_counts_using_basic_offset_table(
+        &[0xFFFF_0000 - 8, 0xFFF0 - 8, 0x10000],
+        &[0xFFFF_0000, 0xFFFF_FFF0]
+      ),
+      Ok(vec![1, 1, 1])
+    );
+
+    // The final frame is allowed to extend past the 32-bit boundary as its
+    // start offset is still addressable
+    assert_eq!(
+      frame_fragment_counts_using_basic_offset_table(
+        &[max_offset - 8, 0x1000, 0x1000],
+        &[u32::MAX]
+      ),
+      Ok(vec![1, 2])
+    );
+
+    // Data that continues past the 32-bit boundary while the basic offset table
+    // still has entries is rejected
+    assert_eq!(
+      frame_fragment_counts_using_basic_offset_table(
+        &[0xFFFF_0000 - 8, 0x1_0000, 0x1000],
+        &[0xFFFF_0000, u32::MAX]
+      ),
+      Err(basic_offset_table_overflow_error())
+    );
+  }
+
+  #[test]
+  fn validate_pixel_data_length_test() {
+    let mut ds = DataSet::new();
+    ds.insert_int_value(&dictionary::ROWS, &[3]).unwrap();
+    ds.insert_int_value(&dictionary::COLUMNS, &[5]).unwrap();
+    ds.insert_int_value(&dictionary::SAMPLES_PER_PIXEL, &[3])
+      .unwrap();
+    ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[8])
+      .unwrap();
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[2])
+      .unwrap();
+
+    assert_eq!(expected_frame_size_bytes(&ds), Ok(45));
+
+    // The pixel data is padded to even length
+    ds.insert_binary_value(
+      dictionary::PIXEL_DATA.tag,
+      ValueRepresentation::OtherByteString,
+      Rc::new(vec![0; 90]),
+    )
+    .unwrap();
+    assert_eq!(validate_pixel_data_length(&ds), Ok(()));
+
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[3])
+      .unwrap();
+    assert_eq!(
+      validate_pixel_data_length(&ds),
+      Err(
+        DataError::new_value_invalid(
+          "Pixel data length is 90 bytes but 135 bytes were expected for 3 \
+           frame(s) of 5x3 pixels"
+            .to_string()
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::PIXEL_DATA.tag
+        ))
+      )
+    );
+
+    ds.insert_binary_value(
+      dictionary::PIXEL_DATA.tag,
+      ValueRepresentation::OtherByteString,
+      Rc::new(vec![0; 136]),
+    )
+    .unwrap();
+    assert_eq!(validate_pixel_data_length(&ds), Ok(()));
+
+    // Bit-packed data with 1 bit allocated
+    ds.insert_int_value(&dictionary::SAMPLES_PER_PIXEL, &[1])
+      .unwrap();
+    ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[1])
+      .unwrap();
+    assert_eq!(expected_frame_size_bytes(&ds), Ok(2));
+    ds.insert_binary_value(
+      dictionary::PIXEL_DATA.tag,
+      ValueRepresentation::OtherByteString,
+      Rc::new(vec![0; 6]),
+    )
+    .unwrap();
+    assert_eq!(validate_pixel_data_length(&ds), Ok(()));
+
+    ds.delete(dictionary::ROWS.tag);
+    assert!(expected_frame_size_bytes(&ds)
+      .unwrap_err()
+      .is_tag_not_present());
+  }
+
+  #[test]
+  fn build_encapsulated_pixel_data_test() {
+    let value = build_encapsulated_pixel_data(
+      &[vec![1, 2, 3], vec![4, 5], vec![6]],
+      ValueRepresentation::OtherByteString,
+    )
+    .unwrap();
+
+    assert_eq!(
+      value.encapsulated_pixel_data().unwrap(),
+      &vec![
+        Rc::new(vec![0, 0, 0, 0, 12, 0, 0, 0, 22, 0, 0, 0]),
+        Rc::new(vec![1, 2, 3, 0]),
+        Rc::new(vec![4, 5]),
+        Rc::new(vec![6, 0]),
+      ]
+    );
+
+    // The frames are found using the basic offset table
+    let mut ds = DataSet::new();
+    ds.insert_int_value(&dictionary::NUMBER_OF_FRAMES, &[3])
+      .unwrap();
+    ds.insert(dictionary::PIXEL_DATA.tag, value);
+
+    assert_eq!(
+      ds.get_pixel_data(),
+      Ok((
+        ValueRepresentation::OtherByteString,
+        vec![
+          vec![[1, 2, 3, 0].as_slice()],
+          vec![[4, 5].as_slice()],
+          vec![[6, 0].as_slice()]
+        ]
+      ))
+    );
+
+    assert!(build_encapsulated_pixel_data(
+      &[vec![]],
+      ValueRepresentation::UnsignedShort
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn validate_pixel_attributes_test() {
+    fn data_set(
+      bits_allocated: i64,
+      bits_stored: i64,
+      high_bit: i64,
+      pixel_representation: i64,
+    ) -> DataSet {
+      let mut ds = DataSet::new();
+      ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[bits_allocated])
+        .unwrap();
+      ds.insert_int_value(&dictionary::BITS_STORED, &[bits_stored])
+        .unwrap();
+      ds.insert_int_value(&dictionary::HIGH_BIT, &[high_bit])
+        .unwrap();
+      ds.insert_int_value(
+        &dictionary::PIXEL_REPRESENTATION,
+        &[pixel_representation],
+      )
+      .unwrap();
+      ds
+    }
+
+    assert_eq!(validate_pixel_attributes(&data_set(16, 12, 11, 0)), Ok(()));
+    assert_eq!(validate_pixel_attributes(&data_set(8, 8, 7, 1)), Ok(()));
+    assert_eq!(validate_pixel_attributes(&data_set(1, 1, 0, 0)), Ok(()));
+
+    assert_eq!(
+      validate_pixel_attributes(&data_set(8, 12, 11, 0)),
+      Err(
+        DataError::new_value_invalid(
+          "Bits stored value of 12 is not in the range 1-8, where 8 is the \
+           bits allocated value"
+            .to_string()
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::BITS_STORED.tag
+        ))
+      )
+    );
+
+    assert_eq!(
+      validate_pixel_attributes(&data_set(16, 0, 0, 0)),
+      Err(
+        DataError::new_value_invalid(
+          "Bits stored value of 0 is not in the range 1-16, where 16 is the \
+           bits allocated value"
+            .to_string()
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::BITS_STORED.tag
+        ))
+      )
+    );
+
+    assert_eq!(
+      validate_pixel_attributes(&data_set(16, 12, 15, 0)),
+      Err(
+        DataError::new_value_invalid(
+          "High bit value of 15 is not one less than the bits stored value of \
+           12"
+            .to_string()
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::HIGH_BIT.tag
+        ))
+      )
+    );
+
+    assert_eq!(
+      validate_pixel_attributes(&data_set(16, 16, 15, 2)),
+      Err(
+        DataError::new_value_invalid(
+          "Pixel representation value of 2 is not 0 or 1".to_string()
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::PIXEL_REPRESENTATION.tag
+        ))
+      )
+    );
+
+    let mut ds = data_set(16, 12, 11, 0);
+    ds.delete(dictionary::HIGH_BIT.tag);
+    assert!(validate_pixel_attributes(&ds)
+      .unwrap_err()
+      .is_tag_not_present());
   }
 }
@@ -0,0 +1,86 @@
+//! Applies the Modality LUT to stored pixel values to convert them into
+//! modality-specific output units, e.g. Hounsfield Units for CT.
+//!
+//! Ref: PS3.3 C.11.1.
+
+use dcmfx_core::{dictionary, DataElementTag, DataError, DataSet, DataSetPath};
+
+/// Applies the Modality LUT defined in a data set to the given stored pixel
+/// values. The *'(0028,1053) Rescale Slope'* and *'(0028,1052) Rescale
+/// Intercept'* data elements define a linear transform that is applied to
+/// each value. When these data elements are absent the slope defaults to one
+/// and the intercept defaults to zero, i.e. the values are unchanged.
+///
+/// Non-linear Modality LUTs specified by a *'(0028,3000) Modality LUT
+/// Sequence'* are not supported, and result in an error.
+///
+pub fn apply_modality_lut(
+  data_set: &DataSet,
+  samples: &[i32],
+) -> Result<Vec<f64>, DataError> {
+  if data_set.has(dictionary::MODALITY_LUT_SEQUENCE.tag) {
+    return Err(
+      DataError::new_value_invalid(
+        "Modality LUT Sequence is not supported, only a linear rescale slope \
+         and intercept can be applied"
+          .to_string(),
+      )
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::MODALITY_LUT_SEQUENCE.tag,
+      )),
+    );
+  }
+
+  let slope = get_optional_float(data_set, dictionary::RESCALE_SLOPE.tag, 1.0)?;
+  let intercept =
+    get_optional_float(data_set, dictionary::RESCALE_INTERCEPT.tag, 0.0)?;
+
+  Ok(
+    samples
+      .iter()
+      .map(|sample| *sample as f64 * slope + intercept)
+      .collect(),
+  )
+}
+
+/// Returns the singular floating point value for a data element in a data set,
+/// or the passed default value if the data element is not present.
+///
+fn get_optional_float(
+  data_set: &DataSet,
+  tag: DataElementTag,
+  default: f64,
+) -> Result<f64, DataError> {
+  if data_set.has(tag) {
+    data_set.get_float(tag)
+  } else {
+    Ok(default)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn apply_modality_lut_test() {
+    let mut ds = DataSet::new();
+    assert_eq!(
+      apply_modality_lut(&ds, &[0, 10, -5]),
+      Ok(vec![0.0, 10.0, -5.0])
+    );
+
+    ds.insert_float_value(&dictionary::RESCALE_SLOPE, &[2.0])
+      .unwrap();
+    ds.insert_float_value(&dictionary::RESCALE_INTERCEPT, &[-1024.0])
+      .unwrap();
+    assert_eq!(
+      apply_modality_lut(&ds, &[0, 1024, 2000]),
+      Ok(vec![-1024.0, 1024.0, 2976.0])
+    );
+
+    ds.insert_sequence_value(&dictionary::MODALITY_LUT_SEQUENCE, vec![])
+      .unwrap();
+    assert!(apply_modality_lut(&ds, &[0]).is_err());
+  }
+}
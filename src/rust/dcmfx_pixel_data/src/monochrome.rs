@@ -0,0 +1,197 @@
+//! Converts stored *'MONOCHROME1'* pixel data to *'MONOCHROME2'*.
+//!
+//! Ref: PS3.3 C.7.6.3.1.2.
+
+use std::rc::Rc;
+
+use byteorder::ByteOrder;
+
+use dcmfx_core::{
+  dictionary, DataElementValue, DataError, DataSet, DataSetPath,
+};
+
+use crate::validate_pixel_attributes;
+
+/// Normalizes the stored pixel data in a data set to *'MONOCHROME2'*. If the
+/// *'(0028,0004) Photometric Interpretation'* is *'MONOCHROME1'* then every
+/// stored pixel value is inverted within the range allowed by *'(0028,0101)
+/// Bits Stored'* and *'(0028,0103) Pixel Representation'*, and the photometric
+/// interpretation is changed to *'MONOCHROME2'*. Data sets with any other
+/// photometric interpretation are left unchanged.
+///
+/// Only native pixel data with a *'(0028,0100) Bits Allocated'* value of 8 or
+/// 16 is supported. Signed pixel values with fewer bits stored than allocated
+/// are written back sign-extended. Other data elements that relate to pixel
+/// values, such as windowing and rescale parameters, are not altered.
+///
+pub fn normalize_to_monochrome2(ds: &mut DataSet) -> Result<(), DataError> {
+  let photometric_interpretation =
+    ds.get_string(dictionary::PHOTOMETRIC_INTERPRETATION.tag)?;
+  if photometric_interpretation != "MONOCHROME1" {
+    return Ok(());
+  }
+
+  validate_pixel_attributes(ds)?;
+
+  let bits_allocated = ds.get_int(dictionary::BITS_ALLOCATED.tag)?;
+  let bits_stored = ds.get_int(dictionary::BITS_STORED.tag)?;
+  let is_signed = ds.get_int(dictionary::PIXEL_REPRESENTATION.tag)? == 1;
+
+  if bits_allocated != 8 && bits_allocated != 16 {
+    return Err(
+      DataError::new_value_invalid(format!(
+        "Bits allocated value of {} is not supported for MONOCHROME1 \
+         normalization",
+        bits_allocated
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::BITS_ALLOCATED.tag,
+      )),
+    );
+  }
+
+  let pixel_data = ds.get_value(dictionary::PIXEL_DATA.tag)?;
+  let vr = pixel_data.value_representation();
+  let mut bytes = match pixel_data.bytes() {
+    Ok(bytes) => (**bytes).clone(),
+    Err(_) => {
+      return Err(
+        DataError::new_value_invalid(
+          "MONOCHROME1 normalization requires native pixel data".to_string(),
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::PIXEL_DATA.tag,
+        )),
+      )
+    }
+  };
+
+  if bits_allocated == 16 {
+    for chunk in bytes.chunks_exact_mut(2) {
+      let value = byteorder::LittleEndian::read_u16(chunk) as u64;
+      let value = invert_stored_value(value, bits_stored, is_signed) as u16;
+      byteorder::LittleEndian::write_u16(chunk, value);
+    }
+  } else {
+    for byte in bytes.iter_mut() {
+      *byte = invert_stored_value(*byte as u64, bits_stored, is_signed) as u8;
+    }
+  }
+
+  ds.insert(
+    dictionary::PIXEL_DATA.tag,
+    DataElementValue::new_binary(vr, Rc::new(bytes))?,
+  );
+  ds.insert_string_value(
+    &dictionary::PHOTOMETRIC_INTERPRETATION,
+    &["MONOCHROME2"],
+  )?;
+
+  Ok(())
+}
+
+/// Inverts a stored pixel value so that the minimum value allowed by the bits
+/// stored becomes the maximum value, and vice versa.
+///
+fn invert_stored_value(value: u64, bits_stored: i64, is_signed: bool) -> u64 {
+  let mask = (1u64 << bits_stored) - 1;
+
+  if is_signed {
+    // Sign-extend the stored value, invert it, and then return it in two's
+    // complement form
+    let shift = 64 - bits_stored;
+    let value = (((value & mask) << shift) as i64) >> shift;
+
+    (-1 - value) as u64
+  } else {
+    (value & mask) ^ mask
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use dcmfx_core::ValueRepresentation;
+
+  fn monochrome1_data_set(
+    bits_allocated: i64,
+    bits_stored: i64,
+    pixel_representation: i64,
+    pixel_data: Vec<u8>,
+  ) -> DataSet {
+    let mut ds = DataSet::new();
+
+    ds.insert_string_value(
+      &dictionary::PHOTOMETRIC_INTERPRETATION,
+      &["MONOCHROME1"],
+    )
+    .unwrap();
+    ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[bits_allocated])
+      .unwrap();
+    ds.insert_int_value(&dictionary::BITS_STORED, &[bits_stored])
+      .unwrap();
+    ds.insert_int_value(&dictionary::HIGH_BIT, &[bits_stored - 1])
+      .unwrap();
+    ds.insert_int_value(
+      &dictionary::PIXEL_REPRESENTATION,
+      &[pixel_representation],
+    )
+    .unwrap();
+    ds.insert_binary_value(
+      dictionary::PIXEL_DATA.tag,
+      ValueRepresentation::OtherWordString,
+      Rc::new(pixel_data),
+    )
+    .unwrap();
+
+    ds
+  }
+
+  fn assert_monochrome2(ds: &DataSet, pixel_data: &[u8]) {
+    assert_eq!(
+      ds.get_string(dictionary::PHOTOMETRIC_INTERPRETATION.tag),
+      Ok("MONOCHROME2")
+    );
+    assert_eq!(
+      ds.get_value_bytes(
+        dictionary::PIXEL_DATA.tag,
+        ValueRepresentation::OtherWordString
+      )
+      .map(|bytes| bytes.as_slice()),
+      Ok(pixel_data)
+    );
+  }
+
+  #[test]
+  fn normalize_to_monochrome2_test() {
+    let mut ds = monochrome1_data_set(8, 8, 0, vec![0, 10, 255, 128]);
+    normalize_to_monochrome2(&mut ds).unwrap();
+    assert_monochrome2(&ds, &[255, 245, 0, 127]);
+
+    let mut ds = monochrome1_data_set(16, 12, 0, vec![0, 0, 0xFF, 0x0F]);
+    normalize_to_monochrome2(&mut ds).unwrap();
+    assert_monochrome2(&ds, &[0xFF, 0x0F, 0, 0]);
+
+    let mut ds = monochrome1_data_set(
+      16,
+      16,
+      1,
+      [(-1000i16).to_le_bytes(), 32767i16.to_le_bytes()].concat(),
+    );
+    normalize_to_monochrome2(&mut ds).unwrap();
+    assert_monochrome2(
+      &ds,
+      &[999i16.to_le_bytes(), (-32768i16).to_le_bytes()].concat(),
+    );
+
+    let mut ds = monochrome1_data_set(16, 16, 0, vec![1, 2]);
+    ds.insert_string_value(
+      &dictionary::PHOTOMETRIC_INTERPRETATION,
+      &["MONOCHROME2"],
+    )
+    .unwrap();
+    normalize_to_monochrome2(&mut ds).unwrap();
+    assert_monochrome2(&ds, &[1, 2]);
+  }
+}
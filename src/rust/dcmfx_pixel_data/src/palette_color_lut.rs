@@ -0,0 +1,608 @@
+//! Reads palette color lookup tables from a data set and uses them to convert
+//! *'PALETTE COLOR'* pixel data to RGB.
+//!
+//! Ref: PS3.3 C.7.6.3.1.5, PS3.3 C.7.9.
+
+use byteorder::ByteOrder;
+
+use dcmfx_core::{
+  dictionary, lookup_table, DataElementTag, DataError, DataSet, DataSetPath,
+};
+
+/// A palette color lookup table that maps the stored indices of *'PALETTE
+/// COLOR'* pixel data to red, green, and blue color values.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaletteColorLut {
+  /// The red entries in the lookup table.
+  pub red: Vec<u16>,
+
+  /// The green entries in the lookup table.
+  pub green: Vec<u16>,
+
+  /// The blue entries in the lookup table.
+  pub blue: Vec<u16>,
+
+  /// The stored pixel value that maps to the first entry in the lookup table.
+  pub first_input_value: i64,
+
+  /// The number of bits in each lookup table entry, either 8 or 16.
+  pub bits_per_entry: u16,
+
+  /// The number of bits allocated to each stored pixel index, either 8 or 16.
+  /// This is the value of *'(0028,0100) Bits Allocated'*.
+  pub bits_allocated: u16,
+}
+
+/// Reads the palette color lookup table from a data set. This uses the *'Red,
+/// Green, and Blue Palette Color Lookup Table Descriptor'* data elements along
+/// with either the *'Red, Green, and Blue Palette Color Lookup Table Data'* or
+/// *'Segmented Red, Green, and Blue Palette Color Lookup Table Data'* data
+/// elements. Segmented lookup tables are expanded into a full lookup table.
+///
+pub fn get_palette_color_lut(
+  data_set: &DataSet,
+) -> Result<PaletteColorLut, DataError> {
+  let bits_allocated = data_set.get_int(dictionary::BITS_ALLOCATED.tag)?;
+  if bits_allocated != 8 && bits_allocated != 16 {
+    return Err(
+      DataError::new_value_invalid(format!(
+        "Bits allocated value of {} is not supported for palette color pixel \
+         data",
+        bits_allocated
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::BITS_ALLOCATED.tag,
+      )),
+    );
+  }
+
+  let (red, first_input_value, bits_per_entry) = read_lut(
+    data_set,
+    dictionary::RED_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR.tag,
+    dictionary::RED_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+    dictionary::SEGMENTED_RED_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+  )?;
+
+  let (green, _, _) = read_lut(
+    data_set,
+    dictionary::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR.tag,
+    dictionary::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+    dictionary::SEGMENTED_GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+  )?;
+
+  let (blue, _, _) = read_lut(
+    data_set,
+    dictionary::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR.tag,
+    dictionary::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+    dictionary::SEGMENTED_BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+  )?;
+
+  Ok(PaletteColorLut {
+    red,
+    green,
+    blue,
+    first_input_value,
+    bits_per_entry,
+    bits_allocated: bits_allocated as u16,
+  })
+}
+
+/// Reads a single lookup table for one color channel, returning its entries,
+/// first input value, and bits per entry.
+///
+fn read_lut(
+  data_set: &DataSet,
+  descriptor_tag: DataElementTag,
+  data_tag: DataElementTag,
+  segmented_data_tag: DataElementTag,
+) -> Result<(Vec<u16>, i64, u16), DataError> {
+  let descriptor = data_set.get_ints(descriptor_tag)?;
+
+  let invalid_descriptor_error = |details: &str| {
+    DataError::new_value_invalid(details.to_string())
+      .with_path(&DataSetPath::new_with_data_element(descriptor_tag))
+  };
+
+  if descriptor.len() != 3 {
+    return Err(invalid_descriptor_error(
+      "Palette color lookup table descriptor does not have three values",
+    ));
+  }
+
+  // An entry count of zero means there are 2^16 entries
+  let entry_count = match descriptor[0] {
+    0 => 65536,
+    n => n as usize,
+  };
+  let first_input_value = descriptor[1];
+  let bits_per_entry = descriptor[2];
+
+  if bits_per_entry != 8 && bits_per_entry != 16 {
+    return Err(invalid_descriptor_error(
+      "Palette color lookup table descriptor bits per entry is not 8 or 16",
+    ));
+  }
+
+  if data_set.has(data_tag) {
+    let entries = lookup_table::parse_lut(
+      data_set.get_value(descriptor_tag)?,
+      data_set.get_value(data_tag)?,
+    )
+    .map_err(|e| e.with_path(&DataSetPath::new_with_data_element(data_tag)))?;
+
+    return Ok((entries, first_input_value, bits_per_entry as u16));
+  }
+
+  let entries = if data_set.has(segmented_data_tag) {
+    let bytes = data_set.get_value(segmented_data_tag)?.bytes()?;
+
+    read_words(bytes)
+      .and_then(|words| expand_segmented_lut(&words, entry_count, None, None))
+      .map_err(|e| {
+        e.with_path(&DataSetPath::new_with_data_element(segmented_data_tag))
+      })?
+  } else {
+    // Report the non-segmented lookup table data as missing when neither form
+    // of lookup table data is present
+    return Err(
+      DataError::new_tag_not_present()
+        .with_path(&DataSetPath::new_with_data_element(data_tag)),
+    );
+  };
+
+  if entries.len() != entry_count {
+    return Err(invalid_descriptor_error(&format!(
+      "Palette color lookup table has {} entries but its descriptor \
+       specifies {} entries",
+      entries.len(),
+      entry_count
+    )));
+  }
+
+  // Discard any high bits of 8-bit entries in the segmented lookup table
+  let entries = if bits_per_entry == 8 {
+    entries.into_iter().map(|entry| entry & 0xFF).collect()
+  } else {
+    entries
+  };
+
+  Ok((entries, first_input_value, bits_per_entry as u16))
+}
+
+fn read_words(bytes: &[u8]) -> Result<Vec<u16>, DataError> {
+  if bytes.len() % 2 == 1 {
+    return Err(DataError::new_value_invalid(
+      "Palette color lookup table data has odd length".to_string(),
+    ));
+  }
+
+  let mut words = vec![0u16; bytes.len() / 2];
+  byteorder::LittleEndian::read_u16_into(bytes, words.as_mut_slice());
+
+  Ok(words)
+}
+
+/// Expands segmented palette color lookup table data into a full lookup table.
+/// Segments are either discrete, linear, or indirect, and indirect segments
+/// refer to other segments by their word offset.
+///
+/// An error is returned as soon as the expanded lookup table would have more
+/// than `max_entries` entries. This stops small amounts of segment data, e.g.
+/// indirect segments that repeatedly copy earlier ones, from causing huge
+/// allocations.
+///
+/// Ref: PS3.3 C.7.9.2.
+///
+fn expand_segmented_lut(
+  data: &[u16],
+  max_entries: usize,
+  segment_count: Option<usize>,
+  previous_value: Option<u16>,
+) -> Result<Vec<u16>, DataError> {
+  let segment_error = |details: &str| {
+    DataError::new_value_invalid(format!(
+      "Segmented palette color lookup table is invalid: {}",
+      details
+    ))
+  };
+
+  let too_many_entries_error =
+    || segment_error("expands to more entries than its descriptor specifies");
+
+  let mut lut: Vec<u16> = vec![];
+  let mut offset = 0;
+  let mut segments_read = 0;
+
+  while offset < data.len() && Some(segments_read) != segment_count {
+    if offset + 2 > data.len() {
+      return Err(segment_error("segment is truncated"));
+    }
+
+    let opcode = data[offset];
+    let length = data[offset + 1] as usize;
+    offset += 2;
+
+    // Nested indirect segments aren't followed, which also guards against
+    // indirect segments that refer to themselves
+    if opcode == 2 && segment_count.is_some() {
+      return Err(segment_error("indirect segment is nested"));
+    }
+
+    match opcode {
+      // Discrete segment
+      0 => {
+        if offset + length > data.len() {
+          return Err(segment_error("discrete segment is truncated"));
+        }
+
+        if lut.len() + length > max_entries {
+          return Err(too_many_entries_error());
+        }
+
+        lut.extend_from_slice(&data[offset..offset + length]);
+        offset += length;
+      }
+
+      // Linear segment that interpolates from the previous value
+      1 => {
+        let y0 =
+          lut.last().copied().or(previous_value).ok_or_else(|| {
+            segment_error("linear segment has no start value")
+          })?;
+
+        let y1 = *data
+          .get(offset)
+          .ok_or_else(|| segment_error("linear segment is truncated"))?;
+        offset += 1;
+
+        if lut.len() + length > max_entries {
+          return Err(too_many_entries_error());
+        }
+
+        for i in 1..=length {
+          let y =
+            y0 as f64 + (y1 as f64 - y0 as f64) * i as f64 / length as f64;
+
+          lut.push(y.round() as u16);
+        }
+      }
+
+      // Indirect segment that copies previously defined segments
+      2 => {
+        let previous_value = match lut.last() {
+          Some(value) => *value,
+          None => return Err(segment_error("indirect segment is first")),
+        };
+
+        if offset + 2 > data.len() {
+          return Err(segment_error("indirect segment is truncated"));
+        }
+
+        let segment_offset =
+          (data[offset] as usize) | ((data[offset + 1] as usize) << 16);
+        offset += 2;
+
+        if segment_offset >= offset - 4 {
+          return Err(segment_error("indirect segment offset is invalid"));
+        }
+
+        lut.extend(expand_segmented_lut(
+          &data[segment_offset..],
+          max_entries - lut.len(),
+          Some(length),
+          Some(previous_value),
+        )?);
+      }
+
+      _ => return Err(segment_error("segment opcode is unknown")),
+    }
+
+    segments_read += 1;
+  }
+
+  Ok(lut)
+}
+
+/// Converts the stored indices of a frame of *'PALETTE COLOR'* pixel data to
+/// 8-bit RGB using a palette color lookup table. The returned data holds three
+/// bytes per pixel. Indices are read as 8-bit or 16-bit little endian integers
+/// based on the lookup table's `bits_allocated` value, and indices outside the
+/// range of the lookup table are mapped to its first or last entry.
+///
+/// 16-bit lookup table entries are scaled down to 8 bits.
+///
+pub fn apply_palette(frame: &[u8], lut: &PaletteColorLut) -> Vec<u8> {
+  if lut.red.is_empty() || lut.green.is_empty() || lut.blue.is_empty() {
+    return vec![];
+  }
+
+  if lut.bits_allocated == 16 {
+    frame
+      .chunks_exact(2)
+      .flat_map(|index| {
+        lut.lookup_rgb(byteorder::LittleEndian::read_u16(index) as i64)
+      })
+      .collect()
+  } else {
+    frame
+      .iter()
+      .flat_map(|index| lut.lookup_rgb(*index as i64))
+      .collect()
+  }
+}
+
+/// Reads the palette color lookup table from a data set using
+/// [`get_palette_color_lut()`], and uses it to convert *'PALETTE COLOR'*
+/// indices to 8-bit RGB values. The first input value and bits per entry
+/// specified by the lookup table descriptors are honoured, and indices outside
+/// the range of the lookup table are mapped to its first or last entry.
+///
+/// An error is returned if any of the red, green, or blue lookup tables is
+/// missing or malformed.
+///
+pub fn apply_palette_color_lut(
+  data_set: &DataSet,
+  indices: &[u16],
+) -> Result<Vec<[u8; 3]>, DataError> {
+  let lut = get_palette_color_lut(data_set)?;
+
+  if lut.red.is_empty() || lut.green.is_empty() || lut.blue.is_empty() {
+    return Err(DataError::new_value_invalid(
+      "Palette color lookup table has no entries".to_string(),
+    ));
+  }
+
+  // Pass the indices to apply_palette() as 16-bit values so that indices
+  // above 255 are mapped the same way regardless of the bits allocated
+  let frame: Vec<u8> = indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+  let lut = PaletteColorLut {
+    bits_allocated: 16,
+    ..lut
+  };
+
+  Ok(
+    apply_palette(&frame, &lut)
+      .chunks_exact(3)
+      .map(|rgb| [rgb[0], rgb[1], rgb[2]])
+      .collect(),
+  )
+}
+
+impl PaletteColorLut {
+  /// Returns the 8-bit RGB color for a stored index, clamping it to the range
+  /// of the lookup table. 16-bit entries are scaled down to 8 bits. The
+  /// lookup table must have at least one entry.
+  ///
+  fn lookup_rgb(&self, index: i64) -> [u8; 3] {
+    let max_index =
+      self.red.len().min(self.green.len()).min(self.blue.len()) as i64 - 1;
+    let i = (index - self.first_input_value).clamp(0, max_index) as usize;
+
+    let entry_to_u8 = |entry: u16| {
+      if self.bits_per_entry == 16 {
+        (entry >> 8) as u8
+      } else {
+        entry as u8
+      }
+    };
+
+    [
+      entry_to_u8(self.red[i]),
+      entry_to_u8(self.green[i]),
+      entry_to_u8(self.blue[i]),
+    ]
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::rc::Rc;
+
+  use dcmfx_core::{DataElementValue, ValueRepresentation};
+
+  use super::*;
+
+  fn data_set_with_lut_descriptors(descriptor: [u16; 3]) -> DataSet {
+    let mut ds = DataSet::new();
+
+    ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[8])
+      .unwrap();
+
+    let descriptor_bytes: Vec<u8> =
+      descriptor.iter().flat_map(|v| v.to_le_bytes()).collect();
+
+    for tag in [
+      dictionary::RED_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR.tag,
+      dictionary::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR.tag,
+      dictionary::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DESCRIPTOR.tag,
+    ] {
+      ds.insert(
+        tag,
+        DataElementValue::new_lookup_table_descriptor(
+          ValueRepresentation::UnsignedShort,
+          Rc::new(descriptor_bytes.clone()),
+        )
+        .unwrap(),
+      );
+    }
+
+    ds
+  }
+
+  fn insert_words(ds: &mut DataSet, tag: DataElementTag, words: &[u16]) {
+    ds.insert(
+      tag,
+      DataElementValue::new_other_word_string(
+        words.iter().flat_map(|v| v.to_le_bytes()).collect(),
+      )
+      .unwrap(),
+    );
+  }
+
+  #[test]
+  fn apply_palette_test() {
+    let mut ds = data_set_with_lut_descriptors([4, 10, 16]);
+    insert_words(
+      &mut ds,
+      dictionary::RED_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+      &[0x0000, 0xFFFF, 0x0000, 0x8000],
+    );
+    insert_words(
+      &mut ds,
+      dictionary::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+      &[0x0000, 0x0000, 0xFFFF, 0x8000],
+    );
+    insert_words(
+      &mut ds,
+      dictionary::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+      &[0x0000, 0x0000, 0x0000, 0xFFFF],
+    );
+
+    let lut = get_palette_color_lut(&ds).unwrap();
+    assert_eq!(lut.first_input_value, 10);
+    assert_eq!(lut.bits_per_entry, 16);
+
+    assert_eq!(
+      apply_palette(&[10, 11, 12, 13, 0, 255], &lut),
+      vec![
+        0x00, 0x00, 0x00, //
+        0xFF, 0x00, 0x00, //
+        0x00, 0xFF, 0x00, //
+        0x80, 0x80, 0xFF, //
+        0x00, 0x00, 0x00, //
+        0x80, 0x80, 0xFF, //
+      ]
+    );
+  }
+
+  #[test]
+  fn apply_palette_color_lut_test() {
+    let mut ds = data_set_with_lut_descriptors([3, 100, 8]);
+    for tag in [
+      dictionary::RED_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+      dictionary::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+    ] {
+      insert_words(&mut ds, tag, &[10, 20, 30]);
+    }
+
+    // The blue lookup table is missing
+    assert_eq!(
+      apply_palette_color_lut(&ds, &[100]),
+      Err(DataError::new_tag_not_present().with_path(
+        &DataSetPath::new_with_data_element(
+          dictionary::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag
+        )
+      ))
+    );
+
+    insert_words(
+      &mut ds,
+      dictionary::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+      &[0x0140, 0x0150, 0x0160],
+    );
+
+    assert_eq!(
+      apply_palette_color_lut(&ds, &[99, 100, 101, 102, 103]),
+      Ok(vec![
+        [10, 10, 0x40],
+        [10, 10, 0x40],
+        [20, 20, 0x50],
+        [30, 30, 0x60],
+        [30, 30, 0x60]
+      ])
+    );
+
+    // A lookup table whose length doesn't match its descriptor is malformed
+    insert_words(
+      &mut ds,
+      dictionary::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+      &[0x40, 0x50, 0x60, 0x70, 0x80],
+    );
+    assert!(apply_palette_color_lut(&ds, &[100]).is_err());
+  }
+
+  #[test]
+  fn get_palette_color_lut_8_bit_packed_test() {
+    let mut ds = data_set_with_lut_descriptors([3, 0, 8]);
+
+    // 8-bit entries packed as individual bytes, followed by a padding byte
+    for tag in [
+      dictionary::RED_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+      dictionary::GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+      dictionary::BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+    ] {
+      ds.insert(
+        tag,
+        DataElementValue::new_other_word_string(vec![10, 20, 30, 0]).unwrap(),
+      );
+    }
+
+    let lut = get_palette_color_lut(&ds).unwrap();
+    assert_eq!(lut.red, vec![10, 20, 30]);
+    assert_eq!(lut.green, vec![10, 20, 30]);
+    assert_eq!(lut.blue, vec![10, 20, 30]);
+  }
+
+  #[test]
+  fn get_segmented_palette_color_lut_test() {
+    let mut ds = data_set_with_lut_descriptors([8, 0, 8]);
+
+    // A discrete segment, a linear segment, then an indirect segment that
+    // repeats the first two segments
+    let segmented_data = [0, 2, 10, 20, 1, 2, 40, 2, 2, 0, 0];
+
+    for tag in [
+      dictionary::SEGMENTED_RED_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+      dictionary::SEGMENTED_GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+      dictionary::SEGMENTED_BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+    ] {
+      insert_words(&mut ds, tag, &segmented_data);
+    }
+
+    let lut = get_palette_color_lut(&ds).unwrap();
+    assert_eq!(lut.red, vec![10, 20, 30, 40, 10, 20, 30, 40]);
+
+    assert_eq!(
+      apply_palette(&[0, 2, 7], &lut),
+      vec![10, 10, 10, 30, 30, 30, 40, 40, 40]
+    );
+  }
+
+  #[test]
+  fn get_segmented_palette_color_lut_too_many_entries_test() {
+    let mut ds = data_set_with_lut_descriptors([8, 0, 8]);
+
+    // A discrete segment followed by indirect segments that each copy it,
+    // which expands to more entries than the descriptor specifies
+    let segmented_data = [
+      0, 2, 10, 20, //
+      2, 1, 0, 0, //
+      2, 1, 0, 0, //
+      2, 1, 0, 0, //
+      2, 1, 0, 0, //
+    ];
+
+    for tag in [
+      dictionary::SEGMENTED_RED_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+      dictionary::SEGMENTED_GREEN_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+      dictionary::SEGMENTED_BLUE_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag,
+    ] {
+      insert_words(&mut ds, tag, &segmented_data);
+    }
+
+    assert_eq!(
+      get_palette_color_lut(&ds),
+      Err(
+        DataError::new_value_invalid(
+          "Segmented palette color lookup table is invalid: expands to more \
+           entries than its descriptor specifies"
+            .to_string()
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::SEGMENTED_RED_PALETTE_COLOR_LOOKUP_TABLE_DATA.tag
+        ))
+      )
+    );
+  }
+}
@@ -0,0 +1,125 @@
+//! Describes the layout of the pixel data in a data set.
+//!
+//! Ref: PS3.3 C.7.6.3.
+
+use dcmfx_core::{dictionary, DataError, DataSet};
+
+use crate::validate_pixel_attributes;
+
+/// The attributes of a data set's *'Image Pixel'* module that describe how its
+/// pixel data is laid out. These are needed in order to decode or interpret
+/// the frames of pixel data returned by
+/// [`crate::DataSetPixelDataExtensions::get_pixel_data()`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct PixelDataInfo {
+  /// The *'(0028,0010) Rows'* value.
+  pub rows: u16,
+
+  /// The *'(0028,0011) Columns'* value.
+  pub columns: u16,
+
+  /// The *'(0028,0002) Samples per Pixel'* value.
+  pub samples_per_pixel: u16,
+
+  /// The *'(0028,0100) Bits Allocated'* value.
+  pub bits_allocated: u16,
+
+  /// The *'(0028,0101) Bits Stored'* value.
+  pub bits_stored: u16,
+
+  /// Whether the *'(0028,0103) Pixel Representation'* value specifies that
+  /// stored pixel values are signed.
+  pub is_signed: bool,
+
+  /// The *'(0028,0004) Photometric Interpretation'* value.
+  pub photometric_interpretation: String,
+
+  /// The *'(0028,0006) Planar Configuration'* value. This is zero if the data
+  /// element isn't present, which is the case when there is a single sample
+  /// per pixel.
+  pub planar_configuration: u16,
+}
+
+impl PixelDataInfo {
+  /// Reads the pixel data info out of a data set. The bits allocated, bits
+  /// stored, high bit, and pixel representation values are checked for
+  /// consistency using [`validate_pixel_attributes()`].
+  ///
+  pub fn from_data_set(ds: &DataSet) -> Result<Self, DataError> {
+    validate_pixel_attributes(ds)?;
+
+    let planar_configuration =
+      match ds.get_int(dictionary::PLANAR_CONFIGURATION.tag) {
+        Ok(planar_configuration) => planar_configuration as u16,
+        Err(e) if e.is_tag_not_present() => 0,
+        Err(e) => return Err(e),
+      };
+
+    Ok(Self {
+      rows: ds.get_int(dictionary::ROWS.tag)? as u16,
+      columns: ds.get_int(dictionary::COLUMNS.tag)? as u16,
+      samples_per_pixel: ds.get_int(dictionary::SAMPLES_PER_PIXEL.tag)? as u16,
+      bits_allocated: ds.get_int(dictionary::BITS_ALLOCATED.tag)? as u16,
+      bits_stored: ds.get_int(dictionary::BITS_STORED.tag)? as u16,
+      is_signed: ds.get_int(dictionary::PIXEL_REPRESENTATION.tag)? == 1,
+      photometric_interpretation: ds
+        .get_string(dictionary::PHOTOMETRIC_INTERPRETATION.tag)?
+        .to_string(),
+      planar_configuration,
+    })
+  }
+
+  /// Returns the number of pixels in a single frame.
+  ///
+  pub fn pixel_count(&self) -> usize {
+    self.rows as usize * self.columns as usize
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn from_data_set_test() {
+    let mut ds = DataSet::new();
+    ds.insert_int_value(&dictionary::ROWS, &[4]).unwrap();
+    ds.insert_int_value(&dictionary::COLUMNS, &[6]).unwrap();
+    ds.insert_int_value(&dictionary::SAMPLES_PER_PIXEL, &[1])
+      .unwrap();
+    ds.insert_int_value(&dictionary::BITS_ALLOCATED, &[16])
+      .unwrap();
+    ds.insert_int_value(&dictionary::BITS_STORED, &[12])
+      .unwrap();
+    ds.insert_int_value(&dictionary::HIGH_BIT, &[11]).unwrap();
+    ds.insert_int_value(&dictionary::PIXEL_REPRESENTATION, &[1])
+      .unwrap();
+    ds.insert_string_value(
+      &dictionary::PHOTOMETRIC_INTERPRETATION,
+      &["MONOCHROME2"],
+    )
+    .unwrap();
+
+    let info = PixelDataInfo::from_data_set(&ds).unwrap();
+    assert_eq!(
+      info,
+      PixelDataInfo {
+        rows: 4,
+        columns: 6,
+        samples_per_pixel: 1,
+        bits_allocated: 16,
+        bits_stored: 12,
+        is_signed: true,
+        photometric_interpretation: "MONOCHROME2".to_string(),
+        planar_configuration: 0,
+      }
+    );
+    assert_eq!(info.pixel_count(), 24);
+
+    ds.delete(dictionary::ROWS.tag);
+    assert!(PixelDataInfo::from_data_set(&ds)
+      .unwrap_err()
+      .is_tag_not_present());
+  }
+}
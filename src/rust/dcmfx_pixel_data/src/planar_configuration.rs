@@ -0,0 +1,299 @@
+//! Works with the layout of color pixel data as specified by *'(0028,0006)
+//! Planar Configuration'*.
+//!
+//! Ref: PS3.3 C.7.6.3.1.3.
+
+use dcmfx_core::{dictionary, DataError, DataSetPath};
+
+/// Returns the samples for a single component of a frame of native pixel data,
+/// e.g. the green channel of RGB pixel data. The frame's samples can either be
+/// interleaved, i.e. a planar configuration of zero, or stored one plane after
+/// another, i.e. a planar configuration of one.
+///
+/// Each returned sample occupies `bits_allocated / 8` bytes, in the same byte
+/// order as in the frame.
+///
+pub fn get_component_plane(
+  frame: &[u8],
+  component: usize,
+  samples_per_pixel: i64,
+  planar_configuration: i64,
+  bits_allocated: i64,
+) -> Result<Vec<u8>, DataError> {
+  if bits_allocated <= 0 || bits_allocated % 8 != 0 {
+    return Err(
+      DataError::new_value_invalid(format!(
+        "Bits allocated value of {} is not supported for extracting a \
+         component plane",
+        bits_allocated
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::BITS_ALLOCATED.tag,
+      )),
+    );
+  }
+
+  if samples_per_pixel <= 0 || component as i64 >= samples_per_pixel {
+    return Err(
+      DataError::new_value_invalid(format!(
+        "Component {} is not present in pixel data with {} samples per pixel",
+        component, samples_per_pixel
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::SAMPLES_PER_PIXEL.tag,
+      )),
+    );
+  }
+
+  let bytes_per_sample = bits_allocated as usize / 8;
+  let samples_per_pixel = samples_per_pixel as usize;
+  let bytes_per_pixel = bytes_per_sample * samples_per_pixel;
+
+  if !frame.len().is_multiple_of(bytes_per_pixel) {
+    return Err(
+      DataError::new_value_invalid(format!(
+        "Frame length of {} bytes is not a multiple of the pixel size of {} \
+         bytes",
+        frame.len(),
+        bytes_per_pixel
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::PIXEL_DATA.tag,
+      )),
+    );
+  }
+
+  let pixel_count = frame.len() / bytes_per_pixel;
+
+  match planar_configuration {
+    0 => Ok(
+      frame
+        .chunks_exact(bytes_per_pixel)
+        .flat_map(|pixel| {
+          &pixel
+            [component * bytes_per_sample..(component + 1) * bytes_per_sample]
+        })
+        .copied()
+        .collect(),
+    ),
+
+    1 => {
+      let plane_size = pixel_count * bytes_per_sample;
+      let offset = component * plane_size;
+
+      Ok(frame[offset..offset + plane_size].to_vec())
+    }
+
+    _ => Err(
+      DataError::new_value_invalid(format!(
+        "Planar configuration value of {} is not valid",
+        planar_configuration
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::PLANAR_CONFIGURATION.tag,
+      )),
+    ),
+  }
+}
+
+/// Converts a frame of native pixel data with a planar configuration of one,
+/// i.e. `R R R ... G G G ... B B B ...`, to a planar configuration of zero,
+/// i.e. `R G B R G B ...`. Interleaved samples are the layout expected by most
+/// image libraries.
+///
+/// An error is returned if the length of the data doesn't match the size of a
+/// frame with the specified dimensions.
+///
+pub fn planar_to_interleaved(
+  data: &[u8],
+  rows: usize,
+  columns: usize,
+  samples_per_pixel: usize,
+  bits_allocated: usize,
+) -> Result<Vec<u8>, DataError> {
+  convert_planar_configuration(
+    data,
+    rows,
+    columns,
+    samples_per_pixel,
+    bits_allocated,
+    true,
+  )
+}
+
+/// Converts a frame of native pixel data with a planar configuration of zero,
+/// i.e. `R G B R G B ...`, to a planar configuration of one, i.e.
+/// `R R R ... G G G ... B B B ...`. This is the inverse of
+/// [`planar_to_interleaved()`].
+///
+pub fn interleaved_to_planar(
+  data: &[u8],
+  rows: usize,
+  columns: usize,
+  samples_per_pixel: usize,
+  bits_allocated: usize,
+) -> Result<Vec<u8>, DataError> {
+  convert_planar_configuration(
+    data,
+    rows,
+    columns,
+    samples_per_pixel,
+    bits_allocated,
+    false,
+  )
+}
+
+/// Converts a frame of native pixel data between the two planar
+/// configurations.
+///
+fn convert_planar_configuration(
+  data: &[u8],
+  rows: usize,
+  columns: usize,
+  samples_per_pixel: usize,
+  bits_allocated: usize,
+  is_planar_input: bool,
+) -> Result<Vec<u8>, DataError> {
+  if bits_allocated == 0 || !bits_allocated.is_multiple_of(8) {
+    return Err(
+      DataError::new_value_invalid(format!(
+        "Bits allocated value of {} is not supported for planar configuration \
+         conversion",
+        bits_allocated
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::BITS_ALLOCATED.tag,
+      )),
+    );
+  }
+
+  let bytes_per_sample = bits_allocated / 8;
+  let pixel_count = rows * columns;
+  let plane_size = pixel_count * bytes_per_sample;
+  let expected_length = plane_size * samples_per_pixel;
+
+  if data.len() != expected_length {
+    return Err(
+      DataError::new_value_invalid(format!(
+        "Frame length of {} bytes does not match the expected frame size of {} \
+         bytes",
+        data.len(),
+        expected_length
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::PIXEL_DATA.tag,
+      )),
+    );
+  }
+
+  let mut output = vec![0u8; expected_length];
+
+  for pixel in 0..pixel_count {
+    for sample in 0..samples_per_pixel {
+      let planar_offset = sample * plane_size + pixel * bytes_per_sample;
+      let interleaved_offset =
+        (pixel * samples_per_pixel + sample) * bytes_per_sample;
+
+      let (source, target) = if is_planar_input {
+        (planar_offset, interleaved_offset)
+      } else {
+        (interleaved_offset, planar_offset)
+      };
+
+      output[target..target + bytes_per_sample]
+        .copy_from_slice(&data[source..source + bytes_per_sample]);
+    }
+  }
+
+  Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn get_component_plane_interleaved_test() {
+    let frame = [
+      10, 11, 12, 20, 21, 22, //
+      30, 31, 32, 40, 41, 42,
+    ];
+
+    assert_eq!(
+      get_component_plane(&frame, 1, 3, 0, 8),
+      Ok(vec![11, 21, 31, 41])
+    );
+
+    // 16-bit samples
+    assert_eq!(
+      get_component_plane(&[1, 0, 2, 0, 3, 0, 4, 0, 5, 0, 6, 0], 1, 3, 0, 16),
+      Ok(vec![2, 0, 5, 0])
+    );
+  }
+
+  #[test]
+  fn get_component_plane_planar_test() {
+    let frame = [
+      10, 20, 30, 40, //
+      11, 21, 31, 41, //
+      12, 22, 32, 42,
+    ];
+
+    assert_eq!(
+      get_component_plane(&frame, 1, 3, 1, 8),
+      Ok(vec![11, 21, 31, 41])
+    );
+  }
+
+  #[test]
+  fn planar_to_interleaved_test() {
+    let planar = [
+      10, 20, 30, 40, //
+      11, 21, 31, 41, //
+      12, 22, 32, 42,
+    ];
+    let interleaved = [
+      10, 11, 12, 20, 21, 22, //
+      30, 31, 32, 40, 41, 42,
+    ];
+
+    assert_eq!(
+      planar_to_interleaved(&planar, 2, 2, 3, 8),
+      Ok(interleaved.to_vec())
+    );
+    assert_eq!(
+      interleaved_to_planar(&interleaved, 2, 2, 3, 8),
+      Ok(planar.to_vec())
+    );
+
+    // 16-bit samples keep their bytes together
+    assert_eq!(
+      planar_to_interleaved(&[1, 0, 2, 0, 3, 0, 4, 0, 5, 0, 6, 0], 1, 2, 3, 16),
+      Ok(vec![1, 0, 3, 0, 5, 0, 2, 0, 4, 0, 6, 0])
+    );
+
+    assert_eq!(
+      planar_to_interleaved(&planar, 2, 3, 3, 8),
+      Err(
+        DataError::new_value_invalid(
+          "Frame length of 12 bytes does not match the expected frame size of \
+           18 bytes"
+            .to_string()
+        )
+        .with_path(&DataSetPath::new_with_data_element(
+          dictionary::PIXEL_DATA.tag
+        ))
+      )
+    );
+  }
+
+  #[test]
+  fn get_component_plane_error_test() {
+    let frame = [0; 12];
+
+    assert!(get_component_plane(&frame, 3, 3, 0, 8).is_err());
+    assert!(get_component_plane(&frame, 0, 3, 2, 8).is_err());
+    assert!(get_component_plane(&frame, 0, 3, 0, 12).is_err());
+    assert!(get_component_plane(&frame[0..11], 0, 3, 0, 8).is_err());
+  }
+}
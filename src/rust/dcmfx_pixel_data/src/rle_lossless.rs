@@ -0,0 +1,277 @@
+//! Decodes frames of pixel data that use the 'RLE Lossless' transfer syntax.
+//!
+//! Ref: PS3.5 8.2.2, PS3.5 Annex G.
+
+use std::borrow::Cow;
+
+use byteorder::ByteOrder;
+use dcmfx_core::{dictionary, DataError, DataSetPath};
+
+use crate::Frame;
+
+/// The maximum number of segments in an RLE Lossless frame, as limited by the
+/// size of its header.
+///
+const MAX_SEGMENT_COUNT: usize = 15;
+
+/// Decodes a frame of 'RLE Lossless' pixel data into its raw pixel values. If
+/// the frame is made up of multiple fragments then they are concatenated prior
+/// to decoding.
+///
+/// Each segment of the frame holds one byte of each sample, with the segments
+/// for the most significant bytes first. The segments are reassembled so that
+/// the returned samples are interleaved, i.e. with a planar configuration of
+/// zero, and samples larger than one byte are stored in little endian byte
+/// order.
+///
+pub fn decode_rle_frame(
+  frame: &Frame,
+  rows: usize,
+  columns: usize,
+  samples_per_pixel: usize,
+  bits_allocated: usize,
+) -> Result<Vec<u8>, DataError> {
+  let invalid_pixel_data_error = |details: String| {
+    DataError::new_value_invalid(details).with_path(
+      &DataSetPath::new_with_data_element(dictionary::PIXEL_DATA.tag),
+    )
+  };
+
+  if bits_allocated == 0 || !bits_allocated.is_multiple_of(8) {
+    return Err(
+      DataError::new_value_invalid(format!(
+        "RLE Lossless pixel data must have a multiple of 8 bits allocated, but \
+         it has {}",
+        bits_allocated
+      ))
+      .with_path(&DataSetPath::new_with_data_element(
+        dictionary::BITS_ALLOCATED.tag,
+      )),
+    );
+  }
+
+  let bytes_per_sample = bits_allocated / 8;
+  let segment_count = samples_per_pixel * bytes_per_sample;
+  let pixel_count = rows * columns;
+
+  if segment_count == 0 || segment_count > MAX_SEGMENT_COUNT {
+    return Err(invalid_pixel_data_error(format!(
+      "RLE Lossless pixel data can't have {} segments",
+      segment_count
+    )));
+  }
+
+  let data: Cow<[u8]> = match frame.as_slice() {
+    [fragment] => Cow::Borrowed(fragment),
+    fragments => Cow::Owned(fragments.concat()),
+  };
+
+  // Read the segment count and offsets from the header
+  if data.len() < 64 {
+    return Err(invalid_pixel_data_error(
+      "RLE Lossless data is too short to contain a header".to_string(),
+    ));
+  }
+
+  let mut header = [0u32; 16];
+  byteorder::LittleEndian::read_u32_into(&data[0..64], &mut header);
+
+  if header[0] as usize != segment_count {
+    return Err(invalid_pixel_data_error(format!(
+      "RLE Lossless data has {} segments but {} were expected",
+      header[0], segment_count
+    )));
+  }
+
+  let offsets: Vec<usize> = header[1..=segment_count]
+    .iter()
+    .map(|offset| *offset as usize)
+    .collect();
+
+  // Decode each segment into a plane of bytes
+  let mut segments = Vec::with_capacity(segment_count);
+  for (i, offset) in offsets.iter().enumerate() {
+    let end = offsets.get(i + 1).copied().unwrap_or(data.len());
+
+    if *offset < 64 || *offset > end || end > data.len() {
+      return Err(invalid_pixel_data_error(format!(
+        "RLE Lossless segment {} has an invalid offset",
+        i
+      )));
+    }
+
+    let segment =
+      decode_segment(&data[*offset..end], pixel_count).ok_or_else(|| {
+        invalid_pixel_data_error(format!(
+          "RLE Lossless segment {} decoded to fewer than {} bytes",
+          i, pixel_count
+        ))
+      })?;
+
+    segments.push(segment);
+  }
+
+  // Interleave the segments into samples, putting the least significant byte
+  // of multi-byte samples first
+  let mut pixels = vec![0u8; pixel_count * segment_count];
+  for (i, segment) in segments.iter().enumerate() {
+    let sample = i / bytes_per_sample;
+    let byte = bytes_per_sample - 1 - i % bytes_per_sample;
+
+    for (pixel, value) in segment.iter().enumerate() {
+      pixels[(pixel * samples_per_pixel + sample) * bytes_per_sample + byte] =
+        *value;
+    }
+  }
+
+  Ok(pixels)
+}
+
+/// Decodes a single RLE segment that uses the PackBits algorithm. Returns
+/// `None` if the segment decodes to fewer than the expected number of bytes.
+/// Any bytes beyond the expected number, such as padding, are ignored.
+///
+fn decode_segment(mut data: &[u8], expected_length: usize) -> Option<Vec<u8>> {
+  let mut output = Vec::with_capacity(expected_length);
+
+  while output.len() < expected_length {
+    match data {
+      // A header byte of 0 to 127 is followed by that number plus one literal
+      // bytes
+      [n @ 0..=127, rest @ ..] => {
+        let count = *n as usize + 1;
+        if rest.len() < count {
+          return None;
+        }
+
+        output.extend_from_slice(&rest[..count]);
+        data = &rest[count..];
+      }
+
+      // A header byte of -128 is a no-op
+      [128, rest @ ..] => data = rest,
+
+      // A header byte of -1 to -127 is followed by a single byte that is
+      // repeated one minus the header byte times
+      [n, value, rest @ ..] => {
+        let count = 257 - *n as usize;
+
+        output.resize(output.len() + count, *value);
+        data = rest;
+      }
+
+      _ => return None,
+    }
+  }
+
+  output.truncate(expected_length);
+
+  Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Builds an RLE Lossless frame from the given encoded segments.
+  ///
+  fn rle_frame(segments: &[&[u8]]) -> Vec<u8> {
+    let mut header = [0u32; 16];
+    header[0] = segments.len() as u32;
+
+    let mut offset = 64;
+    for (i, segment) in segments.iter().enumerate() {
+      header[i + 1] = offset;
+      offset += segment.len() as u32;
+    }
+
+    let mut frame: Vec<u8> = header
+      .iter()
+      .flat_map(|value| value.to_le_bytes())
+      .collect();
+    for segment in segments {
+      frame.extend_from_slice(segment);
+    }
+
+    frame
+  }
+
+  #[test]
+  fn decode_rle_frame_test() {
+    // A single 8-bit sample using a literal run and a replicate run, with a
+    // no-op between them
+    let frame = rle_frame(&[&[0x01, 10, 20, 0x80, 0xFE, 30, 0x00]]);
+    assert_eq!(
+      decode_rle_frame(&vec![frame.as_slice()], 1, 5, 1, 8),
+      Ok(vec![10, 20, 30, 30, 30])
+    );
+
+    // The same frame split across two fragments
+    assert_eq!(
+      decode_rle_frame(&vec![&frame[0..66], &frame[66..]], 1, 5, 1, 8),
+      Ok(vec![10, 20, 30, 30, 30])
+    );
+
+    // 16-bit samples have their most significant bytes in the first segment,
+    // and are returned with the least significant byte first
+    let frame = rle_frame(&[&[0x01, 0x12, 0xAB, 0x00], &[0x01, 0x34, 0xCD]]);
+    assert_eq!(
+      decode_rle_frame(&vec![frame.as_slice()], 2, 1, 1, 16),
+      Ok(vec![0x34, 0x12, 0xCD, 0xAB])
+    );
+
+    // Three 8-bit samples per pixel are interleaved
+    let frame = rle_frame(&[&[0xFF, 1], &[0xFF, 2], &[0x01, 3, 4]]);
+    assert_eq!(
+      decode_rle_frame(&vec![frame.as_slice()], 1, 2, 3, 8),
+      Ok(vec![1, 2, 3, 1, 2, 4])
+    );
+
+    // Three 16-bit samples per pixel
+    let frame = rle_frame(&[
+      &[0x00, 1],
+      &[0x00, 2],
+      &[0x00, 3],
+      &[0x00, 4],
+      &[0x00, 5],
+      &[0x00, 6],
+    ]);
+    assert_eq!(
+      decode_rle_frame(&vec![frame.as_slice()], 1, 1, 3, 16),
+      Ok(vec![2, 1, 4, 3, 6, 5])
+    );
+  }
+
+  #[test]
+  fn decode_rle_frame_error_test() {
+    let pixel_data_error = |details: &str| {
+      Err(DataError::new_value_invalid(details.to_string()).with_path(
+        &DataSetPath::new_with_data_element(dictionary::PIXEL_DATA.tag),
+      ))
+    };
+
+    let frame = rle_frame(&[&[0x01, 10, 20]]);
+
+    assert_eq!(
+      decode_rle_frame(&vec![frame.as_slice()], 1, 2, 1, 16),
+      pixel_data_error("RLE Lossless data has 1 segments but 2 were expected")
+    );
+
+    assert_eq!(
+      decode_rle_frame(&vec![frame.as_slice()], 1, 3, 1, 8),
+      pixel_data_error("RLE Lossless segment 0 decoded to fewer than 3 bytes")
+    );
+
+    assert_eq!(
+      decode_rle_frame(&vec![&frame[0..32]], 1, 2, 1, 8),
+      pixel_data_error("RLE Lossless data is too short to contain a header")
+    );
+
+    assert_eq!(
+      decode_rle_frame(&vec![frame.as_slice()], 1, 2, 4, 32),
+      pixel_data_error("RLE Lossless pixel data can't have 16 segments")
+    );
+
+    assert!(decode_rle_frame(&vec![frame.as_slice()], 1, 2, 1, 12).is_err());
+  }
+}
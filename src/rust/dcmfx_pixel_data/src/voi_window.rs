@@ -0,0 +1,77 @@
+//! Applies a VOI window to pixel values to produce 8-bit grayscale values that
+//! are suitable for display.
+//!
+//! Ref: PS3.3 C.11.2.1.2.
+
+/// Applies the linear VOI window transform defined by the given window center
+/// and width to pixel values and returns the resulting 8-bit display values.
+/// The input values are normally the output of the Modality LUT, see
+/// [`crate::apply_modality_lut()`], and the window is normally the one
+/// returned by [`crate::DataSetPixelDataExtensions::default_window()`].
+///
+/// Values below the window map to 0 and values above it map to 255. When the
+/// photometric interpretation is *'MONOCHROME1'* the output is inverted so that
+/// the minimum value is displayed as white.
+///
+/// Window widths less than one are treated as one.
+///
+pub fn apply_voi_window(
+  samples: &[f64],
+  window_center: f64,
+  window_width: f64,
+  photometric: &str,
+) -> Vec<u8> {
+  let window_width = window_width.max(1.0);
+
+  let lower_bound = window_center - 0.5 - (window_width - 1.0) / 2.0;
+  let upper_bound = window_center - 0.5 + (window_width - 1.0) / 2.0;
+
+  let is_inverted = photometric == "MONOCHROME1";
+
+  samples
+    .iter()
+    .map(|x| {
+      let y = if *x <= lower_bound {
+        0.0
+      } else if *x > upper_bound {
+        255.0
+      } else {
+        ((x - (window_center - 0.5)) / (window_width - 1.0) + 0.5) * 255.0
+      };
+
+      let y = y.round().clamp(0.0, 255.0) as u8;
+
+      if is_inverted {
+        255 - y
+      } else {
+        y
+      }
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn apply_voi_window_test() {
+    let samples = [-1000.0, 0.0, 40.0, 80.0, 200.0, 1000.0];
+
+    assert_eq!(
+      apply_voi_window(&samples, 40.0, 400.0, "MONOCHROME2"),
+      vec![0, 102, 128, 153, 230, 255]
+    );
+
+    assert_eq!(
+      apply_voi_window(&samples, 40.0, 400.0, "MONOCHROME1"),
+      vec![255, 153, 127, 102, 25, 0]
+    );
+
+    // A window width of one gives a hard threshold
+    assert_eq!(
+      apply_voi_window(&[9.0, 9.5, 10.0], 10.0, 1.0, "MONOCHROME2"),
+      vec![0, 0, 255]
+    );
+  }
+}